@@ -0,0 +1,156 @@
+//! ## Test Fixtures
+//!
+//! Setup code that many tests need, written once. The builders make the values tests start
+//! from, and `TempEnv` changes environment variables for as long as a test runs.
+//!
+//! None of it is inside `#[cfg(test)]`, so other crates can use it in their tests too, by
+//! adding this crate under `[dev-dependencies]` in their `Cargo.toml`:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! adder = { path = "../adder" }
+//! ```
+
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::ops::RangeInclusive;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::{Guess, Rectangle};
+
+/// A `width` by `height` rectangle with its top left corner at the origin.
+pub fn rect(width: i32, height: i32) -> Rectangle<i32> {
+    Rectangle::new(0, 0, width, height)
+}
+
+/// A guess of every number in `range`, each bounded by `range` itself.
+///
+/// # Panics
+///
+/// If `range` is empty.
+pub fn guesses(range: RangeInclusive<i32>) -> Vec<Guess> {
+    let (min, max) = (*range.start(), *range.end());
+    assert!(
+        min <= max,
+        "no guesses in the empty range {}..={}",
+        min,
+        max
+    );
+    range
+        .map(|value| Guess::with_bounds(value, min, max).unwrap())
+        .collect()
+}
+
+/// Environment variables are shared by the whole process, and tests run in parallel
+/// threads, so only one `TempEnv` can exist at a time.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Changes environment variables, and puts them back the way they were when it's dropped,
+/// even if the test panics. This is the RAII pattern: the value owns the change, so the
+/// change can't outlive it.
+///
+/// ```
+/// use adder::fixtures::TempEnv;
+///
+/// {
+///     let mut temp = TempEnv::new();
+///     temp.set("ADDER_EXAMPLE", "1");
+///     assert_eq!(Ok("1".to_string()), std::env::var("ADDER_EXAMPLE"));
+/// }
+/// assert!(std::env::var("ADDER_EXAMPLE").is_err());
+/// ```
+///
+/// While a `TempEnv` exists, making another one waits for it to be dropped, so tests that
+/// use one don't see each other's variables. Tests that read the variables without one
+/// can still run alongside.
+pub struct TempEnv {
+    /// Each variable changed, with the value it had before, in the order they changed.
+    saved: Vec<(OsString, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TempEnv {
+    pub fn new() -> TempEnv {
+        // A test that panicked while holding the lock poisons it. Its `TempEnv` has still
+        // put its variables back, so the lock is safe to use.
+        let lock = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        TempEnv {
+            saved: Vec::new(),
+            _lock: lock,
+        }
+    }
+
+    pub fn set(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut TempEnv {
+        self.save(key.as_ref());
+        env::set_var(key, value);
+        self
+    }
+
+    pub fn remove(&mut self, key: impl AsRef<OsStr>) -> &mut TempEnv {
+        self.save(key.as_ref());
+        env::remove_var(key);
+        self
+    }
+
+    fn save(&mut self, key: &OsStr) {
+        self.saved.push((key.to_os_string(), env::var_os(key)));
+    }
+}
+
+impl Default for TempEnv {
+    fn default() -> TempEnv {
+        TempEnv::new()
+    }
+}
+
+/// The changes are undone last first, so a variable changed twice ends up with the value
+/// it had before the first change.
+impl Drop for TempEnv {
+    fn drop(&mut self) {
+        for (key, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => env::set_var(&key, value),
+                None => env::remove_var(&key),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_cover_the_range() {
+        let all = guesses(3..=6);
+        let values: Vec<i32> = all.iter().map(Guess::value).collect();
+        assert_eq!(vec![3, 4, 5, 6], values);
+        assert!(all.iter().all(|guess| (guess.min(), guess.max()) == (3, 6)));
+    }
+
+    #[test]
+    fn temp_env_puts_variables_back() {
+        {
+            let mut temp = TempEnv::new();
+            temp.set("ADDER_FIXTURES_SET", "during")
+                .set("ADDER_FIXTURES_SET", "again")
+                .set("ADDER_FIXTURES_OTHER", "during");
+            assert_eq!(Ok(String::from("again")), env::var("ADDER_FIXTURES_SET"));
+            assert_eq!(Ok(String::from("during")), env::var("ADDER_FIXTURES_OTHER"));
+        }
+        assert!(env::var("ADDER_FIXTURES_SET").is_err());
+        assert!(env::var("ADDER_FIXTURES_OTHER").is_err());
+    }
+
+    #[test]
+    fn temp_env_puts_variables_back_after_a_panic() {
+        let result = std::panic::catch_unwind(|| {
+            let mut temp = TempEnv::new();
+            temp.set("ADDER_FIXTURES_PANIC", "during");
+            panic!("the test failed");
+        });
+        assert!(result.is_err());
+        let _temp = TempEnv::new();
+        assert!(env::var("ADDER_FIXTURES_PANIC").is_err());
+    }
+}
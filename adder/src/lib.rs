@@ -1,4 +1,13 @@
 #![allow(unused_doc_comments)]
+#![allow(clippy::empty_line_after_doc_comments)]
+#![allow(clippy::items_after_test_module)]
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+pub mod fixtures;
+pub mod prop;
 
 /// ## How to Write Tests
 ///
@@ -32,7 +41,6 @@ mod tests {
         assert_eq!(2 + 2, 4);
     }
 
-    use std::f32::consts::E;
     /// Each test is run in a new thread, and when the main thread sees that a test
     /// thread has died, the test is marked as failed.
     // #[test]
@@ -46,11 +54,15 @@ mod tests {
     #[test]
     fn larger_can_hold_smaller() {
         let larger = Rectangle {
+            x: 0,
+            y: 0,
             width: 8,
             height: 7,
         };
 
         let smaller = Rectangle {
+            x: 0,
+            y: 0,
             width: 5,
             height: 1,
         };
@@ -61,11 +73,15 @@ mod tests {
     #[test]
     fn smaller_cannot_hold_larger() {
         let larger = Rectangle {
+            x: 0,
+            y: 0,
             width: 8,
             height: 7,
         };
 
         let smaller = Rectangle {
+            x: 0,
+            y: 0,
             width: 5,
             height: 1,
         };
@@ -73,6 +89,216 @@ mod tests {
         assert!(!smaller.can_hold(&larger));
     }
 
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rectangle<i32> {
+        Rectangle::new(x, y, width, height)
+    }
+
+    #[test]
+    fn rectangles_contain_the_points_inside_their_edges() {
+        let r = rect(2, 3, 4, 5);
+        assert!(r.contains_point(2, 3));
+        assert!(r.contains_point(5, 7));
+        assert!(r.contains_point(4, 5));
+        // The right and bottom edges are just outside.
+        assert!(!r.contains_point(6, 3));
+        assert!(!r.contains_point(2, 8));
+        assert!(!r.contains_point(1, 3));
+        assert!(!r.contains_point(2, 2));
+    }
+
+    #[test]
+    fn empty_rectangles_contain_no_points() {
+        assert!(!rect(2, 3, 0, 5).contains_point(2, 3));
+        assert!(!rect(2, 3, 4, 0).contains_point(2, 3));
+        assert!(!rect(0, 0, 0, 0).contains_point(0, 0));
+        assert!(rect(0, 0, 0, 0).is_empty());
+        assert!(!rect(0, 0, 1, 1).is_empty());
+    }
+
+    #[test]
+    fn overlapping_rectangles() {
+        let r = rect(0, 0, 10, 10);
+        assert!(r.overlaps(&rect(5, 5, 10, 10)));
+        assert!(r.overlaps(&rect(-5, -5, 10, 10)));
+        assert!(r.overlaps(&rect(2, 2, 2, 2)));
+        assert!(rect(2, 2, 2, 2).overlaps(&r));
+        assert!(r.overlaps(&r));
+        assert!(r.overlaps(&rect(-1, 4, 12, 1)));
+    }
+
+    #[test]
+    fn rectangles_that_only_touch_do_not_overlap() {
+        let r = rect(0, 0, 10, 10);
+        assert!(!r.overlaps(&rect(10, 0, 5, 5)));
+        assert!(!r.overlaps(&rect(0, 10, 5, 5)));
+        assert!(!r.overlaps(&rect(-5, -5, 5, 5)));
+        assert!(!r.overlaps(&rect(20, 20, 5, 5)));
+    }
+
+    #[test]
+    fn empty_rectangles_overlap_nothing() {
+        let r = rect(0, 0, 10, 10);
+        assert!(!r.overlaps(&rect(5, 5, 0, 3)));
+        assert!(!rect(5, 5, 3, 0).overlaps(&r));
+        assert!(!rect(5, 5, 0, 0).overlaps(&rect(5, 5, 0, 0)));
+    }
+
+    #[test]
+    fn intersection_is_the_shared_area() {
+        let r = rect(0, 0, 10, 10);
+        assert_eq!(Some(rect(5, 5, 5, 5)), r.intersection(&rect(5, 5, 10, 10)));
+        assert_eq!(
+            Some(rect(0, 0, 5, 5)),
+            r.intersection(&rect(-5, -5, 10, 10))
+        );
+        assert_eq!(Some(rect(2, 2, 2, 2)), r.intersection(&rect(2, 2, 2, 2)));
+        assert_eq!(Some(r), r.intersection(&r));
+        assert_eq!(Some(rect(0, 4, 10, 1)), r.intersection(&rect(-1, 4, 12, 1)));
+    }
+
+    #[test]
+    fn intersection_of_rectangles_that_do_not_overlap_is_none() {
+        let r = rect(0, 0, 10, 10);
+        assert_eq!(None, r.intersection(&rect(10, 0, 5, 5)));
+        assert_eq!(None, r.intersection(&rect(20, 20, 5, 5)));
+        assert_eq!(None, r.intersection(&rect(5, 5, 0, 0)));
+        assert_eq!(None, rect(5, 5, 0, 0).intersection(&r));
+    }
+
+    #[test]
+    fn union_bounds_covers_both() {
+        let r = rect(0, 0, 10, 10);
+        assert_eq!(rect(0, 0, 15, 15), r.union_bounds(&rect(5, 5, 10, 10)));
+        assert_eq!(rect(-5, -5, 15, 15), r.union_bounds(&rect(-5, -5, 10, 10)));
+        assert_eq!(r, r.union_bounds(&rect(2, 2, 2, 2)));
+        assert_eq!(rect(0, 0, 25, 25), r.union_bounds(&rect(20, 20, 5, 5)));
+        assert_eq!(r, r.union_bounds(&r));
+    }
+
+    #[test]
+    fn union_bounds_ignores_empty_rectangles() {
+        let r = rect(0, 0, 10, 10);
+        assert_eq!(r, r.union_bounds(&rect(50, 50, 0, 0)));
+        assert_eq!(r, rect(-50, 3, 0, 4).union_bounds(&r));
+        assert!(rect(1, 1, 0, 0).union_bounds(&rect(9, 9, 0, 0)).is_empty());
+    }
+
+    #[test]
+    fn scale_keeps_the_top_left_corner() {
+        assert_eq!(rect(2, 3, 8, 10), rect(2, 3, 4, 5).scale(2));
+        assert_eq!(rect(2, 3, 4, 5), rect(2, 3, 4, 5).scale(1));
+        let flat = rect(2, 3, 4, 5).scale(0);
+        assert_eq!(rect(2, 3, 0, 0), flat);
+        assert!(flat.is_empty());
+    }
+
+    #[test]
+    fn translate_moves_without_resizing() {
+        assert_eq!(rect(5, -1, 4, 5), rect(2, 3, 4, 5).translate(3, -4));
+        assert_eq!(rect(2, 3, 4, 5), rect(2, 3, 4, 5).translate(0, 0));
+        assert_eq!(rect(-3, -3, 0, 0), rect(0, 0, 0, 0).translate(-3, -3));
+    }
+
+    #[test]
+    fn negative_sizes_are_empty() {
+        assert!(rect(0, 0, -3, 4).is_empty());
+        assert!(!rect(0, 0, -3, 4).contains_point(-1, 1));
+        assert_eq!(None, rect(0, 0, -3, 4).intersection(&rect(-5, -5, 10, 10)));
+    }
+
+    #[test]
+    fn unsigned_rectangles() {
+        let larger: Rectangle<u32> = Rectangle::new(0, 0, 8, 7);
+        let smaller = Rectangle::new(1, 1, 5, 1);
+        assert!(larger.can_hold(&smaller));
+        assert_eq!(56, larger.area());
+        assert_eq!(Some(smaller), larger.intersection(&smaller));
+        assert_eq!(
+            Rectangle::new(3, 2, 16, 14),
+            larger.scale(2).translate(3, 2)
+        );
+    }
+
+    #[test]
+    fn floating_point_rectangles() {
+        let r = Rectangle::new(0.0, 0.0, 2.5, 4.0);
+        assert_eq!(10.0, r.area());
+        assert!(r.contains_point(2.49, 3.99));
+        assert!(!r.contains_point(2.5, 1.0));
+        assert!(r.can_hold(&Rectangle::new(0.0, 0.0, 2.4, 3.9)));
+        assert_eq!(
+            Some(Rectangle::new(1.25, 0.0, 1.25, 4.0)),
+            r.intersection(&r.translate(1.25, 0.0))
+        );
+        assert_eq!(Rectangle::new(0.0, 0.0, 1.25, 2.0), r.scale(0.5));
+        assert!(r.scale(0.0).is_empty());
+    }
+
+    #[test]
+    fn a_rectangle_never_holds_one_that_holds_it() {
+        prop::for_all(
+            prop::pairs(prop::rectangles(20), prop::rectangles(20)),
+            |(a, b)| !(a.can_hold(b) && b.can_hold(a)),
+        );
+    }
+
+    #[test]
+    fn can_hold_needs_room_on_both_sides() {
+        use crate::fixtures::rect;
+
+        assert!(rect(8, 7).can_hold(&rect(7, 6)));
+        assert!(!rect(8, 7).can_hold(&rect(8, 6)));
+        assert!(!rect(8, 7).can_hold(&rect(7, 7)));
+        assert!(rect(1, 1).can_hold(&rect(0, 0)));
+    }
+
+    #[test]
+    fn no_guess_is_further_than_the_range_from_the_secret() {
+        for guess in fixtures::guesses(1..=100) {
+            assert!(guess.distance_to(50) <= 50);
+            assert_eq!(guess.is_higher_than(50), guess.value() > 50);
+        }
+    }
+
+    #[test]
+    fn a_rectangle_cannot_hold_itself() {
+        prop::for_all(prop::rectangles(20), |r| !r.can_hold(r));
+    }
+
+    #[test]
+    fn intersections_are_inside_both_rectangles() {
+        prop::for_all(
+            prop::pairs(prop::rectangles(20), prop::rectangles(20)),
+            |(a, b)| match a.intersection(b) {
+                Some(both) => a.union_bounds(&both) == *a && b.union_bounds(&both) == *b,
+                None => !a.overlaps(b),
+            },
+        );
+    }
+
+    #[test]
+    fn guesses_are_made_only_within_their_bounds() {
+        let bounds = prop::pairs(prop::integers(-50..=50), prop::integers(-50..=50));
+        prop::for_all(
+            prop::pairs(prop::integers(-100..=100), bounds),
+            |(value, (min, max))| match Guess::with_bounds(*value, *min, *max) {
+                Ok(guess) => min <= value && value <= max && guess.value() == *value,
+                Err(GuessError::EmptyRange { .. }) => min > max,
+                Err(GuessError::TooLow { .. }) => value < min,
+                Err(GuessError::TooHigh { .. }) => value > max,
+            },
+        );
+    }
+
+    #[test]
+    fn every_greeting_contains_the_name() {
+        prop::for_all(prop::strings(12), |name| {
+            Locale::ALL
+                .iter()
+                .all(|locale| greet(name, *locale).contains(name.as_str()))
+        });
+    }
+
     #[test]
     fn it_adds_two() {
         assert_eq!(4, add_two(2));
@@ -86,23 +312,101 @@ mod tests {
     /// Any arguments specified after the required arguments of `assert!`, `assert_eq!`,
     /// and `assert_ne!` macros will be passed along to the `format!` macro, so we can
     /// pass a format string that contains `{}` placeholders and values go in those placeholders.
-    /// ```rust
-    /// #[test]
-    /// fn greeting_contains_name() {
-    ///     let result = greeting("Carol");
-    ///     assert!(
-    ///         result.contains("Carol"),
-    ///         "Greeting did not contain a name, value was `{}`",
-    ///         result
-    ///     );
-    /// }
-    /// ```
+    #[test]
+    fn greeting_contains_name() {
+        let result = greet("Carol", Locale::English);
+        assert!(
+            result.contains("Carol"),
+            "Greeting did not contain a name, value was `{}`",
+            result
+        );
+    }
+
+    #[test]
+    fn greetings_in_every_locale_contain_the_name() {
+        for locale in Locale::ALL {
+            let result = greet("Carol", locale);
+            assert!(
+                result.contains("Carol"),
+                "{:?} greeting did not contain a name, value was `{}`",
+                locale,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn greetings_use_the_locale_template() {
+        assert_eq!("Hello, Carol!", greet("Carol", Locale::English));
+        assert_eq!("Bonjour, Carol !", greet("Carol", Locale::French));
+        assert_eq!("Hallo, Carol!", greet("Carol", Locale::German));
+        assert_eq!("¡Hola, Carol!", greet("Carol", Locale::Spanish));
+        assert_eq!("こんにちは、Carolさん！", greet("Carol", Locale::Japanese));
+    }
+
+    #[test]
+    fn names_with_braces_are_left_alone() {
+        assert_eq!("Hello, {}!", greet("{}", Locale::English));
+    }
+
+    #[test]
+    fn locales_differ() {
+        assert_ne!(
+            greet("Carol", Locale::English),
+            greet("Carol", Locale::German)
+        );
+    }
+
     #[test]
     #[should_panic(expected = "Guess value must be less than or equal to 100")]
     fn greater_than_100() {
         Guess::new(200);
     }
 
+    #[test]
+    fn with_bounds_accepts_values_in_range() {
+        let guess = Guess::with_bounds(10, 10, 20).unwrap();
+        assert_eq!(10, guess.value());
+        assert_eq!((10, 20), (guess.min(), guess.max()));
+        assert!(Guess::with_bounds(20, 10, 20).is_ok());
+    }
+
+    #[test]
+    fn with_bounds_rejects_values_out_of_range() {
+        assert_eq!(
+            Err(GuessError::TooLow { value: 9, min: 10 }),
+            Guess::with_bounds(9, 10, 20)
+        );
+        assert_eq!(
+            Err(GuessError::TooHigh { value: 21, max: 20 }),
+            Guess::with_bounds(21, 10, 20)
+        );
+        assert_eq!(
+            Err(GuessError::EmptyRange { min: 20, max: 10 }),
+            Guess::with_bounds(15, 20, 10)
+        );
+    }
+
+    #[test]
+    fn errors_read_like_the_panics() {
+        let error = Guess::with_bounds(0, 1, 100).unwrap_err();
+        assert_eq!(
+            "Guess value must be greater than or equal to 1, got 0.",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn guesses_compare_with_the_secret_number() {
+        let guess = Guess::new(50);
+        assert!(guess.is_higher_than(42));
+        assert!(!guess.is_higher_than(50));
+        assert!(!guess.is_higher_than(77));
+        assert_eq!(8, guess.distance_to(42));
+        assert_eq!(0, guess.distance_to(50));
+        assert_eq!(27, guess.distance_to(77));
+    }
+
     /// ## Using `Result<T, E>` in Tests
     ///
     /// We can also write tests that use `Result<T, E>`!
@@ -127,24 +431,191 @@ mod tests {
     }
 }
 
-struct Rectangle {
-    width: u32,
-    height: u32,
+/// A rectangle on a grid, with its top left corner at `x`, `y`. `y` grows downwards, like
+/// on a screen.
+///
+/// A rectangle covers the points from `x` up to, but not including, `x + width`, and the
+/// same for `y`. So two rectangles that only share an edge don't overlap, and a rectangle
+/// with a width or height of zero is *empty*: it doesn't cover any points at all.
+///
+/// The coordinates can be of any `Number` type: `Rectangle<u32>` for cells on a screen,
+/// `Rectangle<f64>` for shapes in a drawing, and so on. Sizes of zero or less are empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle<T> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+/// What a type needs for rectangles to be made of it: copying, comparing, and the
+/// arithmetic the methods do. `Default` gives zero, for the built-in number types.
+///
+/// `Number` has no methods of its own. It's only there so the bounds are written once,
+/// here, instead of on every `impl` that needs them. The blanket `impl` makes every type
+/// that meets them a `Number`, without anyone having to say so.
+pub trait Number:
+    Copy + PartialOrd + Default + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+}
+
+impl<T> Number for T where
+    T: Copy + PartialOrd + Default + Add<Output = T> + Sub<Output = T> + Mul<Output = T>
+{
+}
+
+/// `PartialOrd` rather than `Ord`, so floats can be coordinates, which means `Ord::max`
+/// and `Ord::min` aren't there.
+fn max<T: Number>(a: T, b: T) -> T {
+    if a < b {
+        b
+    } else {
+        a
+    }
 }
 
-impl Rectangle {
-    fn can_hold(&self, other: &Rectangle) -> bool {
+fn min<T: Number>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+impl<T: Number> Rectangle<T> {
+    pub fn new(x: T, y: T, width: T, height: T) -> Rectangle<T> {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Whether `other` would fit inside, with room to spare on both sides.
+    pub fn can_hold(&self, other: &Rectangle<T>) -> bool {
         self.width > other.width && self.height > other.height
     }
+
+    /// Empty rectangles have no area, rather than a negative one.
+    pub fn area(&self) -> T {
+        if self.is_empty() {
+            T::default()
+        } else {
+            self.width * self.height
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let zero = T::default();
+        // Written with `!` and `>`, so a float NaN size counts as empty too.
+        !(self.width > zero && self.height > zero)
+    }
+
+    /// The first column to the right of the rectangle.
+    pub fn right(&self) -> T {
+        self.x + self.width
+    }
+
+    /// The first row below the rectangle.
+    pub fn bottom(&self) -> T {
+        self.y + self.height
+    }
+
+    pub fn contains_point(&self, x: T, y: T) -> bool {
+        self.x <= x && x < self.right() && self.y <= y && y < self.bottom()
+    }
+
+    /// Whether the two rectangles share any points. Empty rectangles don't overlap anything.
+    pub fn overlaps(&self, other: &Rectangle<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The points both rectangles cover, or `None` if there aren't any.
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>> {
+        if self.is_empty() || other.is_empty() {
+            return None;
+        }
+        let left = max(self.x, other.x);
+        let top = max(self.y, other.y);
+        let right = min(self.right(), other.right());
+        let bottom = min(self.bottom(), other.bottom());
+        if left < right && top < bottom {
+            Some(Rectangle::new(left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+
+    /// The smallest rectangle that covers both. An empty rectangle has no points to cover,
+    /// so it doesn't make the result any bigger, wherever it is.
+    pub fn union_bounds(&self, other: &Rectangle<T>) -> Rectangle<T> {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+        let left = min(self.x, other.x);
+        let top = min(self.y, other.y);
+        let right = max(self.right(), other.right());
+        let bottom = max(self.bottom(), other.bottom());
+        Rectangle::new(left, top, right - left, bottom - top)
+    }
+
+    /// The rectangle `factor` times as wide and as high, with the same top left corner.
+    pub fn scale(&self, factor: T) -> Rectangle<T> {
+        Rectangle::new(self.x, self.y, self.width * factor, self.height * factor)
+    }
+
+    /// The same rectangle, moved `dx` to the right and `dy` down.
+    pub fn translate(&self, dx: T, dy: T) -> Rectangle<T> {
+        Rectangle::new(self.x + dx, self.y + dy, self.width, self.height)
+    }
 }
 
 pub fn add_two(a: i32) -> i32 {
     a + 2
 }
 
-pub fn greeting(name: &str) -> String {
-    // format!("Hello {}!", name)
-    String::from("Hello!")
+/// A language to greet people in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    French,
+    German,
+    Spanish,
+    Japanese,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 5] = [
+        Locale::English,
+        Locale::French,
+        Locale::German,
+        Locale::Spanish,
+        Locale::Japanese,
+    ];
+
+    /// How to greet someone, with `{}` where their name goes. `format!` needs its format
+    /// string at compile time, so these are filled in by `greet` instead.
+    fn greeting_template(self) -> &'static str {
+        match self {
+            Locale::English => "Hello, {}!",
+            Locale::French => "Bonjour, {} !",
+            Locale::German => "Hallo, {}!",
+            Locale::Spanish => "¡Hola, {}!",
+            Locale::Japanese => "こんにちは、{}さん！",
+        }
+    }
+}
+
+/// Greets `name` in the language of `locale`.
+pub fn greet(name: &str, locale: Locale) -> String {
+    // Only the first `{}` is replaced, and the name isn't searched afterwards, so a name
+    // with `{}` in it comes out as it went in.
+    locale.greeting_template().replacen("{}", name, 1)
 }
 
 /// ## Checking for Panics with `should_panic`
@@ -155,24 +626,96 @@ pub fn greeting(name: &str) -> String {
 /// The test passes if the code inside the function panics; the test fails if the
 /// code inside the function doesn't panic.
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Guess {
     value: i32,
+    min: i32,
+    max: i32,
 }
 
+/// Why a value can't be a `Guess`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessError {
+    TooLow {
+        value: i32,
+        min: i32,
+    },
+    TooHigh {
+        value: i32,
+        max: i32,
+    },
+    /// `min` is greater than `max`, so no value would do.
+    EmptyRange {
+        min: i32,
+        max: i32,
+    },
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::TooLow { value, min } => write!(
+                f,
+                "Guess value must be greater than or equal to {}, got {}.",
+                min, value
+            ),
+            GuessError::TooHigh { value, max } => write!(
+                f,
+                "Guess value must be less than or equal to {}, got {}.",
+                max, value
+            ),
+            GuessError::EmptyRange { min, max } => {
+                write!(f, "Guess range {}..={} is empty.", min, max)
+            }
+        }
+    }
+}
+
+impl Error for GuessError {}
+
 impl Guess {
+    /// A guess from 1 to 100.
+    ///
+    /// # Panics
+    ///
+    /// If `value` is outside that range. `with_bounds` returns an error instead.
     pub fn new(value: i32) -> Guess {
-        if value < 1 {
-            panic!(
-                "Guess value must be greater than or equal to 1, got {}.",
-                value
-            );
-        } else if value > 100 {
-            panic!(
-                "Guess value must be less than or equal to 100, got {}.",
-                value
-            );
+        Guess::with_bounds(value, 1, 100).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// A guess from `min` to `max`, inclusive.
+    pub fn with_bounds(value: i32, min: i32, max: i32) -> Result<Guess, GuessError> {
+        if min > max {
+            Err(GuessError::EmptyRange { min, max })
+        } else if value < min {
+            Err(GuessError::TooLow { value, min })
+        } else if value > max {
+            Err(GuessError::TooHigh { value, max })
+        } else {
+            Ok(Guess { value, min, max })
         }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn min(&self) -> i32 {
+        self.min
+    }
 
-        Guess { value }
+    pub fn max(&self) -> i32 {
+        self.max
     }
-}
\ No newline at end of file
+
+    /// Whether the guess is too high for the secret number `secret`.
+    pub fn is_higher_than(&self, secret: i32) -> bool {
+        self.value > secret
+    }
+
+    /// How far the guess is from `secret`, either way. `abs_diff` returns a `u32`, which
+    /// can hold the distance between any two `i32`s without overflowing.
+    pub fn distance_to(&self, secret: i32) -> u32 {
+        self.value.abs_diff(secret)
+    }
+}
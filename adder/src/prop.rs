@@ -0,0 +1,158 @@
+//! ## Property-Based Testing
+//!
+//! An example-based test checks one input we thought of. A *property* is something that
+//! should hold for every input, like "a rectangle can never hold a rectangle that can hold
+//! it". `for_all` checks a property against many inputs made by a *generator*, and panics
+//! with the first input it doesn't hold for.
+//!
+//! A generator is any closure that takes an `Rng` and returns a value. The inputs are
+//! random, but the `Rng` starts from a fixed seed, so a test checks the same inputs every
+//! time it runs, and a failure can always be reproduced.
+
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+use crate::Rectangle;
+
+/// How many inputs `for_all` tries.
+pub const CASES: u32 = 100;
+
+/// The seed `for_all` starts from.
+pub const SEED: u64 = 0x5eed;
+
+/// A small pseudo-random number generator (SplitMix64). It's nowhere near good enough for
+/// cryptography, but it's fast, and it's the same on every machine for the same seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`. Every number in it is about as likely as any other.
+    pub fn i32_in(&mut self, range: RangeInclusive<i32>) -> i32 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {}..={}", start, end);
+        // Done in `i64`, since the span of a range of `i32`s may not fit in one.
+        let span = end as i64 - start as i64 + 1;
+        (start as i64 + (self.next_u64() % span as u64) as i64) as i32
+    }
+}
+
+/// Checks `property` for `CASES` inputs made by `generate`, starting from `SEED`.
+///
+/// # Panics
+///
+/// With the first input `property` returns `false` for.
+pub fn for_all<T: Debug>(generate: impl Fn(&mut Rng) -> T, property: impl Fn(&T) -> bool) {
+    for_all_seeded(SEED, CASES, generate, property);
+}
+
+/// Like `for_all`, with `cases` inputs, starting from `seed`.
+pub fn for_all_seeded<T: Debug>(
+    seed: u64,
+    cases: u32,
+    generate: impl Fn(&mut Rng) -> T,
+    property: impl Fn(&T) -> bool,
+) {
+    let mut rng = Rng::new(seed);
+    for case in 1..=cases {
+        let input = generate(&mut rng);
+        if !property(&input) {
+            panic!(
+                "property failed for {:?} (case {} of {}, seed {})",
+                input, case, cases, seed
+            );
+        }
+    }
+}
+
+/// Numbers in `range`.
+pub fn integers(range: RangeInclusive<i32>) -> impl Fn(&mut Rng) -> i32 {
+    move |rng| rng.i32_in(range.clone())
+}
+
+/// Strings of up to `max_len` characters. Most are ASCII letters and digits, with some
+/// spaces, punctuation, and characters outside ASCII mixed in, since those are where
+/// string handling tends to go wrong.
+pub fn strings(max_len: usize) -> impl Fn(&mut Rng) -> String {
+    const SPECIAL: [char; 8] = [' ', '{', '}', '!', 'é', 'ß', 'こ', '🦀'];
+    const PLAIN: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    move |rng| {
+        let len = rng.i32_in(0..=max_len as i32) as usize;
+        (0..len)
+            .map(|_| {
+                if rng.i32_in(0..=4) == 0 {
+                    SPECIAL[rng.i32_in(0..=SPECIAL.len() as i32 - 1) as usize]
+                } else {
+                    PLAIN[rng.i32_in(0..=PLAIN.len() as i32 - 1) as usize] as char
+                }
+            })
+            .collect()
+    }
+}
+
+/// Rectangles with corners from `-max` to `max`, and widths and heights from 0 to `max`.
+/// Some of them are empty.
+pub fn rectangles(max: i32) -> impl Fn(&mut Rng) -> Rectangle<i32> {
+    move |rng| {
+        Rectangle::new(
+            rng.i32_in(-max..=max),
+            rng.i32_in(-max..=max),
+            rng.i32_in(0..=max),
+            rng.i32_in(0..=max),
+        )
+    }
+}
+
+/// Pairs of a value from `first` and one from `second`.
+pub fn pairs<A, B>(
+    first: impl Fn(&mut Rng) -> A,
+    second: impl Fn(&mut Rng) -> B,
+) -> impl Fn(&mut Rng) -> (A, B) {
+    move |rng| (first(rng), second(rng))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_seed_gives_the_same_numbers() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn numbers_stay_in_their_range() {
+        for_all(integers(-3..=3), |n| (-3..=3).contains(n));
+        for_all(integers(i32::MIN..=i32::MAX), |_| true);
+        for_all(integers(7..=7), |n| *n == 7);
+    }
+
+    #[test]
+    fn strings_are_no_longer_than_asked() {
+        for_all(strings(5), |s| s.chars().count() <= 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "property failed for")]
+    fn a_property_that_does_not_hold_fails() {
+        for_all(integers(0..=100), |n| *n < 50);
+    }
+}
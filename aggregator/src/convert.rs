@@ -0,0 +1,129 @@
+// Converting Between Content Types
+// Implementing `From<Tweet> for NewsArticle` tells Rust how to turn a tweet into an article,
+// and the standard library then gives us the matching `Into` for free:
+// `let article: NewsArticle = tweet.into();`.
+//
+// `IntoArticle` puts the same conversion behind a method that any content type can have.
+// Instead of implementing it for each type, we write one *blanket implementation*: every
+// `Summary` type that an article can be made `From` gets `into_article`. That includes
+// `NewsArticle` itself, because the standard library implements `From<T> for T`.
+
+use crate::render::preview;
+use crate::{BlogPost, NewsArticle, Summary, Tweet};
+
+// How much of a tweet becomes the headline of its article.
+pub const HEADLINE_CHARS: usize = 40;
+
+pub trait IntoArticle: Summary + Sized {
+    fn into_article(self) -> NewsArticle;
+}
+
+impl<T> IntoArticle for T
+where
+    T: Summary,
+    NewsArticle: From<T>,
+{
+    fn into_article(self) -> NewsArticle {
+        NewsArticle::from(self)
+    }
+}
+
+// A tweet has no headline, so the start of its text becomes one. Where it was published
+// stands in for where it was reported from.
+impl From<Tweet> for NewsArticle {
+    fn from(tweet: Tweet) -> NewsArticle {
+        NewsArticle {
+            headline: preview(&tweet.content, HEADLINE_CHARS),
+            location: String::from("Twitter"),
+            author: tweet.username,
+            content: tweet.content,
+            published_at: tweet.published_at,
+        }
+    }
+}
+
+// A blog post maps field for field, except for its tags, which an article has no place for.
+impl From<BlogPost> for NewsArticle {
+    fn from(post: BlogPost) -> NewsArticle {
+        NewsArticle {
+            headline: post.title,
+            location: String::from("blog"),
+            author: post.author,
+            content: post.body,
+            published_at: post.published_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweet(content: &str) -> Tweet {
+        Tweet {
+            id: 7,
+            username: String::from("horse_ebooks"),
+            content: String::from(content),
+            reply_to: None,
+            retweet: false,
+            published_at: 1_634_000_000,
+        }
+    }
+
+    #[test]
+    fn a_tweet_becomes_an_article_with_its_start_as_the_headline() {
+        let article = NewsArticle::from(tweet("Everything happens so much"));
+
+        assert_eq!("Everything happens so much", article.headline);
+        assert_eq!("Twitter", article.location);
+        assert_eq!("horse_ebooks", article.author);
+        assert_eq!("Everything happens so much", article.content);
+        assert_eq!(1_634_000_000, article.published_at);
+    }
+
+    #[test]
+    fn long_tweets_are_cut_to_a_headline() {
+        let content = "of course, as you probably already know, people";
+        let article: NewsArticle = tweet(content).into();
+
+        assert_eq!(
+            "of course, as you probably already know,...",
+            article.headline
+        );
+        assert_eq!(content, article.content);
+
+        let exact = "x".repeat(HEADLINE_CHARS);
+        assert_eq!(exact, NewsArticle::from(tweet(&exact)).headline);
+    }
+
+    #[test]
+    fn a_post_keeps_its_fields_but_not_its_tags() {
+        let post = BlogPost {
+            title: String::from("Why Rust?"),
+            author: String::from("ferris"),
+            body: String::from("Safe, fast, productive: pick three."),
+            tags: vec![String::from("rust")],
+            published_at: 1_634_100_000,
+        };
+        let article = post.into_article();
+
+        assert_eq!("Why Rust?", article.headline);
+        assert_eq!("blog", article.location);
+        assert_eq!("ferris", article.author);
+        assert_eq!("Safe, fast, productive: pick three.", article.content);
+        assert_eq!(1_634_100_000, article.published_at);
+    }
+
+    #[test]
+    fn every_convertible_type_gets_into_article() {
+        let article = tweet("hi").into_article();
+        assert_eq!("hi", article.headline);
+
+        // An article converts to itself, through `From<T> for T`.
+        let same = article.into_article();
+        assert_eq!(
+            ("hi", "Twitter"),
+            (same.headline.as_str(), same.location.as_str())
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// Finding the Same Story in Several Feeds
+// When items come from more than one feed, the same story often shows up in several of
+// them, with small differences in capitalization, punctuation, or spacing. We consider two
+// items duplicates when their headlines match after normalizing away those differences, or,
+// for items without a headline like tweets, when their contents do.
+//
+// Instead of comparing every item with every other item, we look each normalized key up in
+// a `HashMap`, so the whole pass takes time proportional to the number of items. The map is
+// keyed by the normalized text itself rather than by a hash of it: two different stories
+// can have the same hash, and then they'd be merged into one.
+
+use std::collections::HashMap;
+
+use crate::{Feed, Summary};
+
+// One story, and the names of the feeds that carried it, in the order they were given.
+pub struct Deduped<'a> {
+    pub item: &'a dyn Summary,
+    pub sources: Vec<String>,
+}
+
+// Lowercases `text`, drops punctuation, and collapses runs of whitespace to one space, so
+// "Penguins WIN  the Cup!" and "penguins win the cup" come out the same.
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The text that decides whether two items are the same story.
+pub fn key(item: &dyn Summary) -> String {
+    normalize(item.headline().unwrap_or(item.content()))
+}
+
+// Collapses duplicates across `feeds`, given as `(name, feed)` pairs. The first copy of each
+// story is the one kept, and items come out in the order they were first seen.
+// The results borrow the items from the feeds, so the lifetime `'a` ties them together: the
+// feeds have to outlive what this returns.
+pub fn dedup<'a>(feeds: &[(&str, &'a Feed)]) -> Vec<Deduped<'a>> {
+    let mut deduped: Vec<Deduped<'a>> = Vec::new();
+    // Maps a key to where its story is in `deduped`.
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (name, feed) in feeds {
+        for item in feed.iter() {
+            let key = key(item);
+            match seen.get(&key) {
+                Some(&index) => {
+                    let sources = &mut deduped[index].sources;
+                    if !sources.iter().any(|source| source == name) {
+                        sources.push(name.to_string());
+                    }
+                }
+                None => {
+                    seen.insert(key, deduped.len());
+                    deduped.push(Deduped {
+                        item,
+                        sources: vec![name.to_string()],
+                    });
+                }
+            }
+        }
+    }
+
+    deduped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NewsArticle, Tweet};
+
+    fn article(headline: &str, author: &str) -> NewsArticle {
+        NewsArticle {
+            headline: String::from(headline),
+            location: String::from("Pittsburgh, PA, USA"),
+            author: String::from(author),
+            content: String::from("The Pittsburgh Penguins once again are the best hockey team."),
+            published_at: 0,
+        }
+    }
+
+    #[test]
+    fn normalizing_ignores_case_spacing_and_punctuation() {
+        assert_eq!("penguins win the cup", normalize("Penguins WIN  the Cup!"));
+        assert_eq!("penguins win the cup", normalize("  penguins\twin, the cup "));
+        assert_eq!("its 31", normalize("It's -- 3-1"));
+        assert_eq!("", normalize("?! ..."));
+    }
+
+    #[test]
+    fn the_first_copy_is_kept_with_every_source() {
+        let mut news = Feed::new();
+        news.push(article("Penguins win the Cup!", "Iceburgh"));
+        news.push(article("Rain all week", "Iceburgh"));
+        let mut wire = Feed::new();
+        wire.push(article("PENGUINS WIN THE CUP", "Wire"));
+        wire.push(Tweet {
+            id: 1,
+            username: String::from("horse_ebooks"),
+            content: String::from("of course, as you probably already know, people"),
+            reply_to: None,
+            retweet: false,
+            published_at: 0,
+        });
+
+        let deduped = dedup(&[("news", &news), ("wire", &wire), ("news", &news)]);
+
+        assert_eq!(3, deduped.len());
+        assert_eq!(Some("Penguins win the Cup!"), deduped[0].item.headline());
+        assert_eq!("Iceburgh", deduped[0].item.author());
+        assert_eq!(vec!["news", "wire"], deduped[0].sources);
+        assert_eq!(vec!["news"], deduped[1].sources);
+        assert_eq!("horse_ebooks", deduped[2].item.author());
+    }
+
+    #[test]
+    fn different_stories_are_kept_apart() {
+        let mut feed = Feed::new();
+        feed.push(article("Penguins win", "Iceburgh"));
+        feed.push(article("Penguins win again", "Iceburgh"));
+
+        assert_eq!(2, dedup(&[("news", &feed)]).len());
+    }
+}
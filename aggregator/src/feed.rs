@@ -0,0 +1,244 @@
+// Trait Objects: Mixing Types in One Collection
+// `notify` is generic, so each call works on one concrete type that the compiler knows
+// about. A feed needs to hold tweets, articles, and posts side by side, and a `Vec<T>`
+// can only hold one `T`. A *trait object*, `Box<dyn Summary>`, points to any value whose
+// type implements `Summary`, so a `Vec<Box<dyn Summary>>` can hold them all.
+// Calls through a trait object use *dynamic dispatch*: which `summarize` runs is looked
+// up at runtime instead of being chosen by the compiler.
+
+use std::num::NonZeroUsize;
+
+use crate::Summary;
+
+#[derive(Default)]
+pub struct Feed {
+    items: Vec<Box<dyn Summary>>,
+}
+
+impl Feed {
+    pub fn new() -> Feed {
+        Feed { items: Vec::new() }
+    }
+
+    // Taking `impl Summary + 'static` lets callers push a plain `Tweet` instead of boxing
+    // it themselves. The `'static` bound means the item can't hold borrowed data, since the
+    // feed keeps it for as long as the feed lives.
+    pub fn push(&mut self, item: impl Summary + 'static) {
+        self.items.push(Box::new(item));
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn sort_by_recency(&mut self) {
+        crate::sort_by_recency(&mut self.items);
+    }
+
+    pub fn summarize_all(&self) -> Vec<String> {
+        self.iter().map(|item| item.summarize()).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Summary> {
+        self.items.iter().map(|item| item.as_ref())
+    }
+
+    // The items for which `predicate` returns `true`, in feed order. They're borrowed from
+    // the feed, since a `Box<dyn Summary>` can't be cloned into a new one.
+    pub fn filter(&self, predicate: impl Fn(&dyn Summary) -> bool) -> Vec<&dyn Summary> {
+        self.iter().filter(|item| predicate(*item)).collect()
+    }
+
+    // Items by `author`, ignoring case and a leading `@`, so "@Horse_ebooks" finds
+    // horse_ebooks's tweets.
+    pub fn by_author(&self, author: &str) -> Vec<&dyn Summary> {
+        let author = author.trim_start_matches('@');
+        self.filter(|item| item.author().eq_ignore_ascii_case(author))
+    }
+
+    // Items with `keyword` among their keywords, ignoring case and punctuation.
+    pub fn by_keyword(&self, keyword: &str) -> Vec<&dyn Summary> {
+        let keyword = crate::dedup::normalize(keyword);
+        self.filter(|item| item.keywords().contains(&keyword))
+    }
+}
+
+// Paging Through a Feed
+// `pages` is for going through a whole feed in one go. A `Cursor` remembers where a reader
+// stopped, so the next page can be fetched later, say on the next request to a web server.
+// It's only two numbers, so it's `Copy` and cheap to hand around.
+// A page of no items would never get anywhere, so the page size is a `NonZeroUsize`: a
+// `usize` that the type system guarantees isn't 0, so there's nothing to check at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub offset: usize,
+    pub page_size: NonZeroUsize,
+}
+
+impl Cursor {
+    // A cursor at the start of a feed.
+    pub fn start(page_size: NonZeroUsize) -> Cursor {
+        Cursor {
+            offset: 0,
+            page_size,
+        }
+    }
+}
+
+impl Feed {
+    // The items, `page_size` at a time; the last page may be shorter.
+    pub fn pages(&self, page_size: NonZeroUsize) -> impl Iterator<Item = &[Box<dyn Summary>]> {
+        self.items.chunks(page_size.get())
+    }
+
+    // The page at `cursor`, and a cursor for the page after it, or `None` when this is the
+    // last page. A cursor past the end gives an empty page.
+    pub fn page(&self, cursor: Cursor) -> (&[Box<dyn Summary>], Option<Cursor>) {
+        let start = cursor.offset.min(self.items.len());
+        let end = start
+            .saturating_add(cursor.page_size.get())
+            .min(self.items.len());
+
+        let next = if end < self.items.len() {
+            Some(Cursor {
+                offset: end,
+                ..cursor
+            })
+        } else {
+            None
+        };
+        (&self.items[start..end], next)
+    }
+}
+
+// Implementing `IntoIterator` for `&Feed` is what makes `for item in &feed` work.
+impl<'a> IntoIterator for &'a Feed {
+    type Item = &'a Box<dyn Summary>;
+    type IntoIter = std::slice::Iter<'a, Box<dyn Summary>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlogPost, NewsArticle, Tweet};
+
+    fn feed(len: u64) -> Feed {
+        let mut feed = Feed::new();
+        for id in 0..len {
+            feed.push(Tweet {
+                id,
+                username: String::from("horse_ebooks"),
+                content: format!("tweet {}", id),
+                reply_to: None,
+                retweet: false,
+                published_at: id,
+            });
+        }
+        feed
+    }
+
+    fn mixed() -> Feed {
+        let mut feed = Feed::new();
+        feed.push(Tweet {
+            id: 1,
+            username: String::from("Horse_ebooks"),
+            content: String::from("Everything happens so much"),
+            reply_to: None,
+            retweet: false,
+            published_at: 0,
+        });
+        feed.push(NewsArticle {
+            headline: String::from("Rust 2.0?"),
+            location: String::from("Internet"),
+            author: String::from("ferris"),
+            content: String::from("Not yet."),
+            published_at: 0,
+        });
+        feed.push(BlogPost {
+            title: String::from("Why Rust"),
+            author: String::from("horse_ebooks"),
+            body: String::from("Because."),
+            tags: vec![String::from("opinion")],
+            published_at: 0,
+        });
+        feed
+    }
+
+    fn contents(items: Vec<&dyn Summary>) -> Vec<&str> {
+        items.into_iter().map(|item| item.content()).collect()
+    }
+
+    #[test]
+    fn filters_keep_feed_order() {
+        let feed = mixed();
+        let short = feed.filter(|item| item.content().len() < 10);
+        assert_eq!(vec!["Not yet.", "Because."], contents(short));
+        assert!(feed.filter(|_| false).is_empty());
+    }
+
+    #[test]
+    fn authors_match_without_case_or_an_at() {
+        let feed = mixed();
+        let expected = vec!["Everything happens so much", "Because."];
+        assert_eq!(expected, contents(feed.by_author("@HORSE_EBOOKS")));
+        assert_eq!(expected, contents(feed.by_author("horse_ebooks")));
+        assert!(feed.by_author("horse").is_empty());
+    }
+
+    #[test]
+    fn keywords_are_normalized_and_come_from_headlines_and_tags() {
+        let feed = mixed();
+        assert_eq!(
+            vec!["Not yet.", "Because."],
+            contents(feed.by_keyword("RUST!"))
+        );
+        assert_eq!(vec!["Because."], contents(feed.by_keyword("Opinion")));
+        assert_eq!(
+            vec!["Everything happens so much"],
+            contents(feed.by_keyword("  happens, "))
+        );
+        assert!(feed.by_keyword("rus").is_empty());
+    }
+
+    fn size(n: usize) -> NonZeroUsize {
+        NonZeroUsize::new(n).unwrap()
+    }
+
+    #[test]
+    fn pages_split_the_feed_with_a_short_last_page() {
+        let lengths: Vec<usize> = feed(5).pages(size(2)).map(<[_]>::len).collect();
+        assert_eq!(vec![2, 2, 1], lengths);
+        assert_eq!(0, feed(0).pages(size(3)).count());
+    }
+
+    #[test]
+    fn a_cursor_walks_the_pages_one_at_a_time() {
+        let feed = feed(5);
+        let (page, next) = feed.page(Cursor::start(size(3)));
+        assert_eq!(3, page.len());
+        let next = next.unwrap();
+        assert_eq!(3, next.offset);
+
+        let (page, next) = feed.page(next);
+        assert_eq!(
+            vec!["tweet 3", "tweet 4"],
+            page.iter().map(|item| item.content()).collect::<Vec<_>>()
+        );
+        assert_eq!(None, next);
+
+        let (page, next) = feed.page(Cursor {
+            offset: 10,
+            page_size: size(3),
+        });
+        assert!(page.is_empty());
+        assert_eq!(None, next);
+    }
+}
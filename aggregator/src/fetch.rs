@@ -0,0 +1,371 @@
+// Fetching Articles over HTTP
+// Only built with `--features fetch`, so that offline builds don't include networking code.
+//
+// The standard library has TCP but no HTTP client, so `fetch_article` speaks just enough
+// HTTP/1.0 to send a GET request and read the reply. HTTP/1.0 keeps it simple: the server
+// closes the connection when the page is done, so we read until the end instead of parsing
+// lengths or chunks. There's no TLS, so only `http://` URLs work.
+//
+// The page is picked apart with plain string searching, not a real HTML parser: the title
+// comes from `<title>`, the author from `<meta name="author">`, and the body from the text
+// of the `<p>` elements. That's enough for most simple article pages.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::NewsArticle;
+
+// How long to wait for the server to connect, or to send the next part of the page.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+// The most of a reply we read, headers included. A server that sends more, whether by
+// mistake or on purpose, would otherwise fill up memory.
+pub const MAX_REPLY: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum FetchError {
+    // The URL couldn't be understood.
+    InvalidUrl(String),
+    // `https://` and other schemes need more than a plain TCP connection.
+    UnsupportedScheme(String),
+    Io(io::Error),
+    // The server answered with something other than 200 OK.
+    Status(u16),
+    // The reply wasn't HTTP, or the page had no title to use as a headline.
+    Malformed(&'static str),
+    // The reply was longer than `MAX_REPLY` bytes.
+    TooLarge,
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::InvalidUrl(url) => write!(f, "invalid URL: {}", url),
+            FetchError::UnsupportedScheme(scheme) => {
+                write!(f, "unsupported scheme {} (only http is supported)", scheme)
+            }
+            FetchError::Io(e) => write!(f, "{}", e),
+            FetchError::Status(code) => write!(f, "server answered with status {}", code),
+            FetchError::Malformed(what) => write!(f, "malformed page: {}", what),
+            FetchError::TooLarge => write!(f, "reply is bigger than {} bytes", MAX_REPLY),
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FetchError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// Lets `?` turn an `io::Error` into a `FetchError`.
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> FetchError {
+        FetchError::Io(e)
+    }
+}
+
+// Downloads the page at `url` and makes an article of it. The article's location is the
+// host it came from, and since pages don't agree on how to mark a publication date, its
+// `published_at` is when it was fetched.
+pub fn fetch_article(url: &str) -> Result<NewsArticle, FetchError> {
+    let (host, port, path) = parse_url(url)?;
+    let page = get(&host, port, &path)?;
+
+    let headline = element_text(&page, "title").ok_or(FetchError::Malformed("no <title>"))?;
+    let author = meta_content(&page, "author").unwrap_or_default();
+    let content = paragraphs(&page).join("\n\n");
+    let published_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+
+    Ok(NewsArticle {
+        headline,
+        location: host,
+        author,
+        content,
+        published_at,
+    })
+}
+
+// Splits `http://host:port/path` into its parts. The port defaults to 80 and the path to `/`.
+fn parse_url(url: &str) -> Result<(String, u16, String), FetchError> {
+    let (scheme, rest) = url
+        .split_once("://")
+        .ok_or_else(|| FetchError::InvalidUrl(url.to_string()))?;
+    if scheme != "http" {
+        return Err(FetchError::UnsupportedScheme(scheme.to_string()));
+    }
+
+    let (authority, path) = match rest.find('/') {
+        Some(slash) => (&rest[..slash], &rest[slash..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => {
+            let port = port
+                .parse()
+                .map_err(|_| FetchError::InvalidUrl(url.to_string()))?;
+            (host, port)
+        }
+        None => (authority, 80),
+    };
+    if host.is_empty() {
+        return Err(FetchError::InvalidUrl(url.to_string()));
+    }
+
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+// Connects to the first address of `host` that answers within `TIMEOUT`. A name can stand
+// for several addresses, like an IPv6 and an IPv4 one, and not all of them may work.
+fn connect(host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("no address for {}", host))
+    }))
+}
+
+// Sends a GET request and returns the body of a 200 OK reply.
+fn get(host: &str, port: u16, path: &str) -> Result<String, FetchError> {
+    let mut stream = connect(host, port)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: aggregator\r\nConnection: close\r\n\r\n",
+        path, host
+    )?;
+
+    // One byte more than the limit, to tell a reply that's exactly `MAX_REPLY` long from
+    // one that's longer.
+    let mut reply = Vec::new();
+    stream.take(MAX_REPLY + 1).read_to_end(&mut reply)?;
+    if reply.len() as u64 > MAX_REPLY {
+        return Err(FetchError::TooLarge);
+    }
+    let reply = String::from_utf8_lossy(&reply);
+
+    let (head, body) = reply
+        .split_once("\r\n\r\n")
+        .ok_or(FetchError::Malformed("no end of headers"))?;
+    // The status line looks like `HTTP/1.1 200 OK`.
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or(FetchError::Malformed("no status line"))?;
+    if status != 200 {
+        return Err(FetchError::Status(status));
+    }
+
+    Ok(body.to_string())
+}
+
+// The text inside the first `<name ...>...</name>` element, with tags removed.
+fn element_text(page: &str, name: &str) -> Option<String> {
+    let lower = page.to_ascii_lowercase();
+    let start = find_tag(&lower, name, 0)?;
+    let content_start = start + lower[start..].find('>')? + 1;
+    let end = content_start + lower[content_start..].find(&format!("</{}", name))?;
+    Some(clean(&page[content_start..end]))
+}
+
+// The text of every `<p>` element, in order, skipping empty ones.
+fn paragraphs(page: &str) -> Vec<String> {
+    let lower = page.to_ascii_lowercase();
+    let mut found = Vec::new();
+    let mut from = 0;
+
+    while let Some(start) = find_tag(&lower, "p", from) {
+        let Some(open_end) = lower[start..].find('>') else {
+            break;
+        };
+        let content_start = start + open_end + 1;
+        let end = lower[content_start..]
+            .find("</p")
+            .map_or(page.len(), |end| content_start + end);
+        let text = clean(&page[content_start..end]);
+        if !text.is_empty() {
+            found.push(text);
+        }
+        from = end;
+    }
+
+    found
+}
+
+// Where the next `<name` tag starts at or after `from`, making sure it isn't just a tag
+// whose name begins the same way, like `<pre>` when looking for `<p>`.
+fn find_tag(lower: &str, name: &str, from: usize) -> Option<usize> {
+    let open = format!("<{}", name);
+    let mut from = from;
+    while let Some(found) = lower[from..].find(&open) {
+        let start = from + found;
+        let after = lower[start + open.len()..].chars().next();
+        if after.is_some_and(|c| c == '>' || c == '/' || c.is_whitespace()) {
+            return Some(start);
+        }
+        from = start + open.len();
+    }
+    None
+}
+
+// The `content` of `<meta name="{name}" content="...">`.
+fn meta_content(page: &str, name: &str) -> Option<String> {
+    let lower = page.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(start) = find_tag(&lower, "meta", from) {
+        let end = start + lower[start..].find('>')?;
+        let tag = &page[start..end];
+        if attribute(tag, "name").is_some_and(|value| value.eq_ignore_ascii_case(name)) {
+            return attribute(tag, "content").map(|value| decode_entities(&value));
+        }
+        from = end;
+    }
+    None
+}
+
+// The value of `name="..."` (or with single quotes) inside a tag. The name has to follow
+// whitespace, so looking for `name` doesn't find `data-name=` or `username=`.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let pattern = format!("{}=", name.to_ascii_lowercase());
+    let start = lower
+        .match_indices(&pattern)
+        .map(|(found, _)| found)
+        .find(|&found| lower[..found].ends_with(|c: char| c.is_whitespace()))?
+        + pattern.len();
+    let quote = tag[start..].chars().next()?;
+    if quote != '"' && quote != '\'' {
+        return None;
+    }
+    let value_start = start + 1;
+    let value_end = value_start + tag[value_start..].find(quote)?;
+    Some(tag[value_start..value_end].to_string())
+}
+
+// Removes tags, decodes entities, and collapses whitespace.
+fn clean(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = decode_entities(&text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Decodes the handful of entities that show up in most text.
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_split_into_host_port_and_path() {
+        assert_eq!(
+            ("example.com".to_string(), 80, "/".to_string()),
+            parse_url("http://example.com").unwrap()
+        );
+        assert_eq!(
+            ("localhost".to_string(), 8080, "/news/1?x=2".to_string()),
+            parse_url("http://localhost:8080/news/1?x=2").unwrap()
+        );
+        assert!(matches!(
+            parse_url("https://example.com/"),
+            Err(FetchError::UnsupportedScheme(scheme)) if scheme == "https"
+        ));
+        for url in ["example.com", "http://", "http://host:port/", "http://:80/"] {
+            assert!(
+                matches!(parse_url(url), Err(FetchError::InvalidUrl(_))),
+                "{}",
+                url
+            );
+        }
+    }
+
+    const PAGE: &str = r#"<html><head>
+<TITLE>Penguins &amp; Friends</TITLE>
+<meta data-name="author" content="Wrong">
+<meta username="author" content="Wrong too">
+<meta name="Author" content='Iceburgh &quot;the Mascot&quot;'>
+</head><body>
+<pre>not a paragraph</pre>
+<p>The Pittsburgh Penguins   once again
+are the <b>best</b> hockey team.</p>
+<p class="empty">  </p>
+<P>In the NHL.
+</body></html>"#;
+
+    #[test]
+    fn element_text_strips_tags_and_entities() {
+        assert_eq!(
+            Some("Penguins & Friends".to_string()),
+            element_text(PAGE, "title")
+        );
+        assert_eq!(
+            Some("not a paragraph".to_string()),
+            element_text(PAGE, "pre")
+        );
+        assert_eq!(None, element_text(PAGE, "h1"));
+    }
+
+    #[test]
+    fn paragraphs_skip_empty_ones_and_lookalike_tags() {
+        assert_eq!(
+            vec![
+                "The Pittsburgh Penguins once again are the best hockey team.".to_string(),
+                "In the NHL.".to_string(),
+            ],
+            paragraphs(PAGE)
+        );
+    }
+
+    #[test]
+    fn meta_content_matches_the_name_attribute_only() {
+        assert_eq!(
+            Some(r#"Iceburgh "the Mascot""#.to_string()),
+            meta_content(PAGE, "author")
+        );
+        assert_eq!(None, meta_content(PAGE, "description"));
+    }
+
+    #[test]
+    fn attributes_need_whitespace_before_their_name() {
+        let tag = r#"<meta data-name="a" username='b' name="c""#;
+        assert_eq!(Some("c".to_string()), attribute(tag, "name"));
+        assert_eq!(Some("a".to_string()), attribute(tag, "data-name"));
+        assert_eq!(None, attribute(tag, "content"));
+        assert_eq!(None, attribute("<meta name=c>", "name"));
+    }
+}
@@ -25,8 +25,26 @@
 // in this case. We've also declared the trait as `pub` so that crates depending on this crate
 // can make use of this trait too.
 
-use std::fmt::{Debug, Display};
-use std::iter::Sum;
+use std::fmt::Debug;
+
+pub mod convert;
+pub mod dedup;
+pub mod feed;
+pub mod locale;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod metrics;
+pub mod rank;
+pub mod render;
+pub mod sink;
+pub mod threads;
+
+pub use convert::IntoArticle;
+pub use feed::Feed;
+pub use locale::{Locale, Templates};
+pub use metrics::Metrics;
+pub use render::Render;
+pub use sink::Sink;
 
 // ### Implementing a Trait on a Type
 #[derive(Debug)]
@@ -35,6 +53,8 @@ pub struct NewsArticle {
     pub location: String,
     pub author: String,
     pub content: String,
+    // Seconds since the Unix epoch, like every `published_at` below.
+    pub published_at: u64,
 }
 
 // impl Summary for NewsArticle {
@@ -45,10 +65,19 @@ pub struct NewsArticle {
 
 #[derive(Debug)]
 pub struct Tweet {
+    pub id: u64,
     pub username: String,
     pub content: String,
-    pub reply: bool,
+    // The `id` of the tweet this one answers, if it's a reply.
+    pub reply_to: Option<u64>,
     pub retweet: bool,
+    pub published_at: u64,
+}
+
+impl Tweet {
+    pub fn is_reply(&self) -> bool {
+        self.reply_to.is_some()
+    }
 }
 
 // impl Summary for Tweet {
@@ -82,20 +111,209 @@ pub struct Tweet {
 // Default implementations can call other methods in the same trait, even if those other methods
 // don't have a default implementation. In this way, a trait can provide a lot of useful
 // functionality and only require implementors to specify a small part of it.
-pub trait Summary {
+//
+// `Summary: Timestamped` makes `Timestamped` a *supertrait*: a type can only implement
+// `Summary` if it implements `Timestamped` too, and in return anything that has a `Summary`,
+// even a `dyn Summary`, can be asked when it was published.
+//
+// The default `summarize` is in English, `summarize_in` picks another locale, and
+// `summarize_with` also takes the templates to use (see the `locale` module).
+pub trait Summary: Timestamped {
     fn summarize_author(&self) -> String;
 
     fn summarize(&self) -> String {
-        format!("(Read more from {}...)", self.summarize_author())
+        self.summarize_in(Locale::default())
+    }
+
+    fn summarize_in(&self, locale: Locale) -> String {
+        self.summarize_with(&Templates::new(), locale)
+    }
+
+    fn summarize_with(&self, templates: &Templates, locale: Locale) -> String {
+        locale::fill(templates.template(locale), &self.summarize_author())
+    }
+
+    // The item's main text, for code that has to look inside items it only knows as
+    // `dyn Summary`, like the `dedup` module.
+    fn content(&self) -> &str;
+
+    // The item's title, for the kinds of content that have one.
+    fn headline(&self) -> Option<&str> {
+        None
+    }
+
+    // Who the item is by, plainly: no `@` or other decoration like `summarize_author` adds.
+    fn author(&self) -> &str;
+
+    // The words the item is about, for searching: those of its headline and content,
+    // normalized the way `dedup::normalize` does it.
+    fn keywords(&self) -> Vec<String> {
+        let headline = self.headline().unwrap_or_default();
+        dedup::normalize(&format!("{} {}", headline, self.content()))
+            .split(' ')
+            .filter(|word| !word.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+// When a piece of content was published, in seconds since the Unix epoch.
+pub trait Timestamped {
+    fn published_at(&self) -> u64;
+}
+
+impl Timestamped for NewsArticle {
+    fn published_at(&self) -> u64 {
+        self.published_at
+    }
+}
+
+impl Timestamped for Tweet {
+    fn published_at(&self) -> u64 {
+        self.published_at
+    }
+}
+
+impl Timestamped for BlogPost {
+    fn published_at(&self) -> u64 {
+        self.published_at
     }
 }
 
+impl Timestamped for PodcastEpisode {
+    fn published_at(&self) -> u64 {
+        self.published_at
+    }
+}
+
+// Newest first. `sort_by_key` is stable, so items published at the same second keep their
+// order. `Reverse` flips the comparison of whatever it wraps.
+pub fn sort_by_recency(items: &mut [Box<dyn Summary>]) {
+    items.sort_by_key(|item| std::cmp::Reverse(item.published_at()));
+}
+
 // To use this version of `Summary`, we only need to define `summarize_author` when we implement
 // the trait on a type.
 impl Summary for Tweet {
     fn summarize_author(&self) -> String {
         format!("@{}", self.username)
     }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+
+    fn author(&self) -> &str {
+        &self.username
+    }
+}
+
+// A news article keeps the format from the start of this section: headline, author, and
+// where it was reported from.
+impl Summary for NewsArticle {
+    fn summarize_author(&self) -> String {
+        self.author.clone()
+    }
+
+    fn summarize(&self) -> String {
+        format!("{}, by {} ({})", self.headline, self.summarize_author(), self.location)
+    }
+
+    fn content(&self) -> &str {
+        &self.content
+    }
+
+    fn headline(&self) -> Option<&str> {
+        Some(&self.headline)
+    }
+
+    fn author(&self) -> &str {
+        &self.author
+    }
+}
+
+// The trait works just as well for types whose data looks nothing alike. A blog post has
+// tags, a podcast episode has a running time, and each overrides `summarize` to show what
+// matters for that kind of content.
+#[derive(Debug)]
+pub struct BlogPost {
+    pub title: String,
+    pub author: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub published_at: u64,
+}
+
+impl Summary for BlogPost {
+    fn summarize_author(&self) -> String {
+        self.author.clone()
+    }
+
+    fn summarize(&self) -> String {
+        if self.tags.is_empty() {
+            format!("{}, by {}", self.title, self.summarize_author())
+        } else {
+            format!("{}, by {} [{}]", self.title, self.summarize_author(), self.tags.join(", "))
+        }
+    }
+
+    fn content(&self) -> &str {
+        &self.body
+    }
+
+    fn headline(&self) -> Option<&str> {
+        Some(&self.title)
+    }
+
+    fn author(&self) -> &str {
+        &self.author
+    }
+
+    // A post's tags are keywords too. An overriding method can't call the default one, so
+    // this repeats what the default does and adds the tags.
+    fn keywords(&self) -> Vec<String> {
+        let text = format!("{} {} {}", self.title, self.body, self.tags.join(" "));
+        dedup::normalize(&text)
+            .split(' ')
+            .filter(|word| !word.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct PodcastEpisode {
+    pub show: String,
+    pub episode: u32,
+    pub duration_secs: u64,
+    pub description: String,
+    pub published_at: u64,
+}
+
+impl Summary for PodcastEpisode {
+    // A podcast is "by" its show.
+    fn summarize_author(&self) -> String {
+        self.show.clone()
+    }
+
+    fn summarize(&self) -> String {
+        format!(
+            "{} #{} ({}:{:02}): {}",
+            self.summarize_author(),
+            self.episode,
+            self.duration_secs / 60,
+            self.duration_secs % 60,
+            self.description
+        )
+    }
+
+    fn content(&self) -> &str {
+        &self.description
+    }
+
+    fn author(&self) -> &str {
+        &self.show
+    }
 }
 
 // Trait as Parameters
@@ -113,6 +331,15 @@ pub fn notify<T: Summary>(item: &T) {
     println!("Breaking news! {}", item.summarize());
 }
 
+// `notify` for any number of items, of any mix of types, sent wherever the caller likes.
+// The items are trait objects so they can have different types; the sink is a generic so
+// each kind of sink gets its own compiled copy of the function.
+pub fn notify_all<S: Sink>(items: &[&dyn Summary], sink: &mut S) {
+    for item in items {
+        sink.send(format!("Breaking news! {}", item.summarize()));
+    }
+}
+
 // The `impl Trait` syntax is convenient and makes for more concise code in simple
 // cases, while the fuller trait bound syntax can express more complexity in other
 // cases.
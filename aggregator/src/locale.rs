@@ -0,0 +1,149 @@
+// Localized Summaries
+// The default `summarize` used to be one English `format!` string. Now it asks a template
+// provider, `Templates`, for the template of a locale, so the same items can be summarized in
+// other languages, and programs can register templates of their own.
+//
+// Templates use `{}` where the author goes, like `format!`. They can't actually be passed to
+// `format!`, though: its format string has to be a literal the compiler can check, and ours
+// are chosen at runtime. So `fill` substitutes the author itself.
+//
+// The registered templates are a plain value that's passed to `Summary::summarize_with`,
+// not something shared by the whole program. Registering a template only changes the
+// summaries made with that `Templates`, so two parts of a program, or two tests running at
+// the same time, can't change each other's wording.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Locale {
+    // The built-in "read more" template for this locale.
+    pub fn read_more(self) -> &'static str {
+        match self {
+            Locale::English => "(Read more from {}...)",
+            Locale::French => "(Lire la suite de {}...)",
+            Locale::German => "(Mehr von {} lesen...)",
+            Locale::Spanish => "(Leer más de {}...)",
+        }
+    }
+}
+
+// The templates to summarize with: registered ones where there are any, and the built-in
+// ones for the other locales. `Templates::new()` only has the built-in ones.
+#[derive(Debug, Clone, Default)]
+pub struct Templates {
+    registered: Vec<(Locale, String)>,
+}
+
+impl Templates {
+    pub fn new() -> Templates {
+        Templates::default()
+    }
+
+    // Uses `template` instead of the built-in one for `locale`. It has to have a `{}` for
+    // the author to go in.
+    pub fn register(&mut self, locale: Locale, template: &str) -> Result<(), String> {
+        if !template.contains("{}") {
+            return Err(format!(
+                "template {:?} has no {{}} for the author",
+                template
+            ));
+        }
+
+        self.registered
+            .retain(|(registered_locale, _)| *registered_locale != locale);
+        self.registered.push((locale, template.to_string()));
+        Ok(())
+    }
+
+    // The template for `locale`: a registered one if there is one, otherwise the built-in
+    // one.
+    pub fn template(&self, locale: Locale) -> &str {
+        self.registered
+            .iter()
+            .find(|(registered_locale, _)| *registered_locale == locale)
+            .map_or(locale.read_more(), |(_, template)| template)
+    }
+}
+
+// Puts `author` where the first `{}` in `template` is.
+pub fn fill(template: &str, author: &str) -> String {
+    template.replacen("{}", author, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Summary, Tweet};
+
+    fn tweet() -> Tweet {
+        Tweet {
+            id: 1,
+            username: String::from("horse_ebooks"),
+            content: String::new(),
+            reply_to: None,
+            retweet: false,
+            published_at: 0,
+        }
+    }
+
+    #[test]
+    fn the_author_goes_where_the_first_braces_are() {
+        assert_eq!("(by ferris {})", fill("(by {} {})", "ferris"));
+        assert_eq!("no author", fill("no author", "ferris"));
+    }
+
+    #[test]
+    fn registered_templates_replace_the_built_in_ones() {
+        let mut templates = Templates::new();
+        assert_eq!("(Mehr von {} lesen...)", templates.template(Locale::German));
+
+        templates
+            .register(Locale::German, "(Weiter mit {})")
+            .unwrap();
+        templates.register(Locale::German, "(Mehr von {})").unwrap();
+        assert_eq!("(Mehr von {})", templates.template(Locale::German));
+        // The other locales keep theirs.
+        assert_eq!(
+            "(Read more from {}...)",
+            templates.template(Locale::English)
+        );
+    }
+
+    #[test]
+    fn templates_need_somewhere_for_the_author() {
+        let mut templates = Templates::new();
+
+        assert!(templates
+            .register(Locale::French, "(Lire la suite)")
+            .is_err());
+        assert_eq!(
+            "(Lire la suite de {}...)",
+            templates.template(Locale::French)
+        );
+    }
+
+    #[test]
+    fn summaries_only_use_the_templates_they_are_given() {
+        let tweet = tweet();
+        let mut templates = Templates::new();
+        templates
+            .register(Locale::English, "(More from {} inside...)")
+            .unwrap();
+
+        assert_eq!(
+            "(More from @horse_ebooks inside...)",
+            tweet.summarize_with(&templates, Locale::English)
+        );
+        assert_eq!("(Read more from @horse_ebooks...)", tweet.summarize());
+        assert_eq!(
+            "(Leer más de @horse_ebooks...)",
+            tweet.summarize_in(Locale::Spanish)
+        );
+    }
+}
@@ -1,16 +1,29 @@
-use aggregator::{NewsArticle, notify, returns_summarizable, Summary, Tweet};
+use std::num::NonZeroUsize;
+
+use aggregator::feed::Cursor;
+use aggregator::{dedup, rank, render, threads};
+use aggregator::sink::Stdout;
+use aggregator::{notify, notify_all, IntoArticle, Locale, Metrics, Render, BlogPost, Feed, NewsArticle, PodcastEpisode, Summary, Templates, Tweet};
 
 fn main() {
     let tweet = Tweet {
+        id: 1,
         username: String::from("horse_ebooks"),
         content: String::from(
             "of course, as you probably already know, people",
         ),
-        reply: false,
+        reply_to: None,
         retweet: false,
+        published_at: 1_634_000_000,
     };
 
     println!("1 new tweet: {}", tweet.summarize());
+    println!("1 nouveau tweet : {}", tweet.summarize_in(Locale::French));
+
+    // Our own wording for English, used by the summaries made with these templates.
+    let mut templates = Templates::new();
+    templates.register(Locale::English, "(More from {} inside...)").unwrap();
+    println!("1 new tweet: {}", tweet.summarize_with(&templates, Locale::English));
 
     // Default implementation.
     // let article = NewsArticle {
@@ -31,4 +44,127 @@ fn main() {
     // Traits as Parameters
     notify(&tweet);
 
+    // Types with different shapes, each with its own summary.
+    let post = BlogPost {
+        title: String::from("Why Rust?"),
+        author: String::from("ferris"),
+        body: String::from("Safe, fast, productive: pick three."),
+        tags: vec![String::from("rust"), String::from("opinion")],
+        published_at: 1_634_100_000,
+    };
+    let episode = PodcastEpisode {
+        show: String::from("New Rustacean"),
+        episode: 12,
+        duration_secs: 1_385,
+        description: String::from("Traits and trait objects"),
+        published_at: 1_633_900_000,
+    };
+
+    notify(&post);
+    notify(&episode);
+
+    // Or all at once, to any `Sink`.
+    notify_all(&[&post, &episode], &mut Stdout);
+
+    let article = NewsArticle {
+        headline: String::from("Penguins win the Stanley Cup Championship"),
+        location: String::from("Pittsburgh, PA, USA"),
+        author: String::from("Iceburgh"),
+        content: String::from(
+            "The Pittsburgh Penguins once again are the best \
+            hockey team in the NHL."
+        ),
+        published_at: 1_634_050_000,
+    };
+    println!("{}", render::cards(&[&tweet, &article]));
+
+    println!(
+        "{} words, about {} seconds to read",
+        article.word_count(),
+        article.estimated_reading_time().as_secs()
+    );
+
+    // The same content, ready for a README or a web page.
+    print!("{}", post.to_markdown());
+    print!("{}", tweet.to_html());
+
+    // Trait objects let one feed hold all of them.
+    let mut feed = Feed::new();
+    feed.push(tweet);
+    feed.push(article);
+    feed.push(post);
+    feed.push(episode);
+
+    // Another source carrying one of the same stories, with its own capitalization.
+    let mut wire = Feed::new();
+    wire.push(NewsArticle {
+        headline: String::from("PENGUINS WIN THE STANLEY CUP CHAMPIONSHIP!"),
+        location: String::from("Pittsburgh"),
+        author: String::from("Wire staff"),
+        content: String::from("Pittsburgh takes the cup again."),
+        published_at: 1_634_060_000,
+    });
+    for story in dedup::dedup(&[("local", &feed), ("wire", &wire)]) {
+        println!("{} [{}]", story.item.summarize(), story.sources.join(", "));
+    }
+
+    // A conversation, rebuilt from who replied to what.
+    let tweet_by = |id, username: &str, content: &str, reply_to| Tweet {
+        id,
+        username: String::from(username),
+        content: String::from(content),
+        reply_to,
+        retweet: false,
+        published_at: 1_634_000_000 + id,
+    };
+    let conversation = vec![
+        tweet_by(10, "ferris", "Which trait should I implement first?", None),
+        tweet_by(11, "bors", "Display, so you can print it.", Some(10)),
+        tweet_by(12, "ferris", "Thanks!", Some(11)),
+        tweet_by(13, "corro", "Debug, you can derive it.", Some(10)),
+    ];
+    for thread in threads::threads(&conversation) {
+        println!("{}", thread.summarize());
+    }
+
+    // Promoting a tweet into the long-form pipeline.
+    let promoted = tweet_by(20, "ferris", "Rust 2021 is out, with disjoint closure captures!", None);
+    println!("{}", promoted.into_article().summarize());
+
+    // Topic digests from the mixed feed.
+    for item in feed.by_keyword("Rust") {
+        println!("About Rust: {}", item.summarize());
+    }
+    println!("{} item(s) by @horse_ebooks", feed.by_author("@horse_ebooks").len());
+    let long = feed.filter(|item| item.content().len() > 40);
+    println!("{} long item(s)", long.len());
+
+    // Newest first.
+    feed.sort_by_recency();
+    for (number, page) in feed.pages(NonZeroUsize::new(3).unwrap()).enumerate() {
+        println!("Page {}: {} item(s)", number + 1, page.len());
+    }
+
+    // Two items at a time, picking up where the last page left off.
+    let mut cursor = Some(Cursor::start(NonZeroUsize::new(2).unwrap()));
+    while let Some(at) = cursor {
+        let (page, next) = feed.page(at);
+        println!("Items {}-{}", at.offset + 1, at.offset + page.len());
+        cursor = next;
+    }
+
+    // The two items most worth reading: recent ones, about Rust, by people we follow.
+    let scorers: Vec<Box<dyn rank::Scorer>> = vec![
+        Box::new(rank::Recency { now: 1_634_100_000, half_life_secs: 86_400 }),
+        Box::new(rank::KeywordWeights::new().weight("rust", 2.0).weight("hockey", -1.0)),
+        Box::new(rank::AuthorAllowlist::new(&["@horse_ebooks"])),
+    ];
+    for ranked in rank::top(&feed, &scorers, 2) {
+        println!("{:.2} {}", ranked.score, ranked.item.summarize());
+    }
+
+    for summary in feed.summarize_all() {
+        println!("* {}", summary);
+    }
+
 }
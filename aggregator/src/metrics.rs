@@ -0,0 +1,30 @@
+// Metrics for Digests
+// Both methods have default implementations built on `Summary::content`, so a type gets
+// them with an empty `impl` block, the same trick as `impl Summary for NewsArticle {}` in
+// the default implementations section. `Metrics: Summary` is what lets the defaults call
+// `content`.
+
+use std::time::Duration;
+
+use crate::{BlogPost, NewsArticle, Summary, Tweet};
+
+// A typical adult silent reading speed.
+pub const WORDS_PER_MINUTE: u64 = 200;
+
+pub trait Metrics: Summary {
+    fn word_count(&self) -> usize {
+        self.content().split_whitespace().count()
+    }
+
+    // Rounded up to the second, so anything with words in it takes some time to read.
+    fn estimated_reading_time(&self) -> Duration {
+        let words = self.word_count() as u64;
+        Duration::from_secs((words * 60).div_ceil(WORDS_PER_MINUTE))
+    }
+}
+
+impl Metrics for NewsArticle {}
+
+impl Metrics for Tweet {}
+
+impl Metrics for BlogPost {}
@@ -0,0 +1,239 @@
+// Ranking a Feed
+// A feed sorted by recency shows the newest items first, whether or not the reader cares
+// about them. Ranking gives every item a score instead, and shows the best ones first.
+//
+// What makes an item good differs from reader to reader, so a score comes from a `Scorer`,
+// and this module has a few: one for recency, one for keywords, and one for favorite
+// authors. Any closure that takes an item and returns a number is a scorer too, and a
+// `Vec` of boxed scorers adds their scores up, so they can be mixed freely.
+
+use std::collections::HashMap;
+
+use crate::{dedup, Feed, Summary};
+
+pub trait Scorer {
+    // Higher is better. Scores only mean something compared with other scores from the
+    // same scorer.
+    fn score(&self, item: &dyn Summary) -> f64;
+}
+
+// A closure works as a scorer, like `|item: &dyn Summary| item.content().len() as f64`.
+impl<F> Scorer for F
+where
+    F: Fn(&dyn Summary) -> f64,
+{
+    fn score(&self, item: &dyn Summary) -> f64 {
+        self(item)
+    }
+}
+
+// The sum of the scores of several scorers. They're trait objects so they can be of
+// different types.
+impl Scorer for Vec<Box<dyn Scorer>> {
+    fn score(&self, item: &dyn Summary) -> f64 {
+        self.iter().map(|scorer| scorer.score(item)).sum()
+    }
+}
+
+// Newer is better: an item published at `now` scores 1.0, and its score halves every
+// `half_life_secs` seconds after that. Items from the future count as published now.
+pub struct Recency {
+    pub now: u64,
+    pub half_life_secs: u64,
+}
+
+impl Scorer for Recency {
+    fn score(&self, item: &dyn Summary) -> f64 {
+        let age = self.now.saturating_sub(item.published_at());
+        0.5_f64.powf(age as f64 / self.half_life_secs.max(1) as f64)
+    }
+}
+
+// The sum of the weights of the keywords an item has. Keywords are normalized the way
+// `Summary::keywords` normalizes them, so "Rust!" and "rust" are the same keyword. A
+// negative weight pushes items about that keyword down.
+#[derive(Debug, Default)]
+pub struct KeywordWeights {
+    weights: HashMap<String, f64>,
+}
+
+impl KeywordWeights {
+    pub fn new() -> KeywordWeights {
+        KeywordWeights::default()
+    }
+
+    // Taking and returning `self` lets weights be chained onto `new`.
+    pub fn weight(mut self, keyword: &str, weight: f64) -> KeywordWeights {
+        self.weights.insert(dedup::normalize(keyword), weight);
+        self
+    }
+}
+
+impl Scorer for KeywordWeights {
+    // Each keyword counts once, however many times the item mentions it.
+    fn score(&self, item: &dyn Summary) -> f64 {
+        let mut keywords = item.keywords();
+        keywords.sort();
+        keywords.dedup();
+        keywords
+            .iter()
+            .filter_map(|keyword| self.weights.get(keyword))
+            .sum()
+    }
+}
+
+// 1.0 for items by one of the authors, 0.0 for the rest. Authors are compared like
+// `Feed::by_author` compares them: ignoring case and a leading `@`.
+#[derive(Debug, Default)]
+pub struct AuthorAllowlist {
+    authors: Vec<String>,
+}
+
+impl AuthorAllowlist {
+    pub fn new(authors: &[&str]) -> AuthorAllowlist {
+        AuthorAllowlist {
+            authors: authors
+                .iter()
+                .map(|author| author.trim_start_matches('@').to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Scorer for AuthorAllowlist {
+    fn score(&self, item: &dyn Summary) -> f64 {
+        let allowed = self
+            .authors
+            .iter()
+            .any(|author| author.eq_ignore_ascii_case(item.author()));
+        if allowed {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+// An item and the score it got.
+pub struct Ranked<'a> {
+    pub item: &'a dyn Summary,
+    pub score: f64,
+}
+
+// The `n` items of `feed` with the highest scores, best first. Items with the same score
+// stay in feed order. `f64` isn't `Ord`, because NaN isn't less or greater than anything,
+// so we sort with `total_cmp`, which puts every value somewhere. It would put NaN above
+// infinity, though, so a NaN score, from a scorer that divided by zero say, is ranked as
+// the lowest score there is instead.
+pub fn top<'a>(feed: &'a Feed, scorer: &impl Scorer, n: usize) -> Vec<Ranked<'a>> {
+    let mut ranked: Vec<Ranked> = feed
+        .iter()
+        .map(|item| Ranked {
+            item,
+            score: scorer.score(item),
+        })
+        .collect();
+    ranked.sort_by(|a, b| rank_key(b.score).total_cmp(&rank_key(a.score)));
+    ranked.truncate(n);
+    ranked
+}
+
+fn rank_key(score: f64) -> f64 {
+    if score.is_nan() {
+        f64::NEG_INFINITY
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tweet;
+
+    fn tweet(id: u64, username: &str, content: &str, published_at: u64) -> Tweet {
+        Tweet {
+            id,
+            username: String::from(username),
+            content: String::from(content),
+            reply_to: None,
+            retweet: false,
+            published_at,
+        }
+    }
+
+    #[test]
+    fn recency_halves_every_half_life() {
+        let recency = Recency {
+            now: 100,
+            half_life_secs: 10,
+        };
+
+        assert_eq!(1.0, recency.score(&tweet(1, "a", "", 100)));
+        assert_eq!(0.5, recency.score(&tweet(2, "a", "", 90)));
+        assert_eq!(0.25, recency.score(&tweet(3, "a", "", 80)));
+        // From the future counts as now, and a half-life of 0 as 1 second.
+        assert_eq!(1.0, recency.score(&tweet(4, "a", "", 120)));
+        let instant = Recency {
+            now: 100,
+            half_life_secs: 0,
+        };
+        assert_eq!(0.5, instant.score(&tweet(5, "a", "", 99)));
+    }
+
+    #[test]
+    fn keywords_count_once_however_they_are_written() {
+        let weights = KeywordWeights::new()
+            .weight("Rust!", 2.0)
+            .weight("java", -1.0);
+
+        let item = tweet(1, "a", "rust, RUST and Rust... not Java", 0);
+        assert_eq!(1.0, weights.score(&item));
+        assert_eq!(0.0, weights.score(&tweet(2, "a", "nothing here", 0)));
+    }
+
+    #[test]
+    fn allowed_authors_ignore_case_and_the_at() {
+        let allowlist = AuthorAllowlist::new(&["@Horse_Ebooks", "ferris"]);
+
+        assert_eq!(1.0, allowlist.score(&tweet(1, "horse_ebooks", "", 0)));
+        assert_eq!(1.0, allowlist.score(&tweet(2, "Ferris", "", 0)));
+        assert_eq!(0.0, allowlist.score(&tweet(3, "corro", "", 0)));
+    }
+
+    #[test]
+    fn scorers_in_a_vec_add_up() {
+        let scorers: Vec<Box<dyn Scorer>> = vec![
+            Box::new(AuthorAllowlist::new(&["ferris"])),
+            Box::new(KeywordWeights::new().weight("rust", 2.0)),
+            Box::new(|item: &dyn Summary| item.content().len() as f64),
+        ];
+
+        assert_eq!(3.0 + 4.0, scorers.score(&tweet(1, "ferris", "rust", 0)));
+        assert_eq!(2.0, scorers.score(&tweet(2, "corro", "ok", 0)));
+        assert_eq!(
+            0.0,
+            Vec::<Box<dyn Scorer>>::new().score(&tweet(3, "a", "", 0))
+        );
+    }
+
+    #[test]
+    fn top_keeps_ties_in_feed_order_and_nan_last() {
+        let mut feed = Feed::new();
+        for (id, content) in [(1, "bb"), (2, "nan"), (3, "a"), (4, "cc"), (5, "dd")] {
+            feed.push(tweet(id, "a", content, 0));
+        }
+        let scorer = |item: &dyn Summary| match item.content() {
+            "nan" => f64::NAN,
+            content => content.len() as f64,
+        };
+
+        let ranked = top(&feed, &scorer, 10);
+        let contents: Vec<&str> = ranked.iter().map(|r| r.item.content()).collect();
+        assert_eq!(vec!["bb", "cc", "dd", "a", "nan"], contents);
+        assert!(ranked[4].score.is_nan());
+
+        assert_eq!(2, top(&feed, &scorer, 2).len());
+        assert!(top(&feed, &scorer, 0).is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+// Presentation: How Content Looks on Screen
+// `Summary` says *what* to show in one line; how an item looks when it's shown in full is a
+// separate concern. The standard library's `Display` trait is the usual place for that, and
+// implementing it means `{}` works with our types in `println!`, `format!`, and friends.
+// (`Debug`, which `{:?}` uses, is derived on every content type instead: it's for
+// programmers, so the generated output is good enough.)
+
+use std::fmt::{self, Display};
+
+use crate::{BlogPost, NewsArticle, PodcastEpisode, Tweet};
+
+// How many characters of the body a card shows.
+pub const PREVIEW_CHARS: usize = 60;
+
+// The first `max_chars` characters of `text`, with "..." if anything was cut.
+// Counting `chars` rather than bytes keeps us from slicing a character in half.
+pub fn preview(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+// Renders each item as a card, with a blank line between cards.
+// `&dyn Display` lets tweets and articles share one slice.
+pub fn cards(items: &[&dyn Display]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// @horse_ebooks (reply)
+// of course, as you probably already know, people
+impl Display for Tweet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "@{}", self.username)?;
+        if self.is_reply() {
+            write!(f, " (reply)")?;
+        }
+        if self.retweet {
+            write!(f, " (retweet)")?;
+        }
+        write!(f, "\n{}", preview(&self.content, PREVIEW_CHARS))
+    }
+}
+
+// Penguins win the Stanley Cup Championship
+// by Iceburgh, Pittsburgh, PA, USA
+// The Pittsburgh Penguins once again are the best hockey team...
+impl Display for NewsArticle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.headline)?;
+        writeln!(f, "by {}, {}", self.author, self.location)?;
+        write!(f, "{}", preview(&self.content, PREVIEW_CHARS))
+    }
+}
+
+// Rendering for Other Formats
+// A digest might end up in a web page or a README instead of a terminal. `Render` gives
+// each content type a Markdown and an HTML form.
+// Everything in the content came from users, so it's escaped before it goes in: otherwise a
+// tweet containing `<script>` would become part of the page, and a headline starting with
+// `#` would turn into a heading.
+pub trait Render {
+    fn to_markdown(&self) -> String;
+    fn to_html(&self) -> String;
+}
+
+// Replaces the characters that mean something in HTML with character references.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Puts a backslash before every character that Markdown could treat as formatting.
+// HTML is allowed in Markdown too, so `<` and `&` are escaped the HTML way.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '&' => escaped.push_str("&amp;"),
+            '\\' | '`' | '*' | '_' | '{' | '}' | '[' | ']' | '(' | ')' | '#' | '+' | '-' | '.'
+            | '!' | '|' | '>' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Render for NewsArticle {
+    fn to_markdown(&self) -> String {
+        format!(
+            "## {}\n\n*{}, {}*\n\n{}\n",
+            escape_markdown(&self.headline),
+            escape_markdown(&self.author),
+            escape_markdown(&self.location),
+            escape_markdown(&self.content)
+        )
+    }
+
+    fn to_html(&self) -> String {
+        format!(
+            "<article>\n<h2>{}</h2>\n<p class=\"byline\">{}, {}</p>\n<p>{}</p>\n</article>\n",
+            escape_html(&self.headline),
+            escape_html(&self.author),
+            escape_html(&self.location),
+            escape_html(&self.content)
+        )
+    }
+}
+
+impl Render for Tweet {
+    fn to_markdown(&self) -> String {
+        format!(
+            "**@{}**{}: {}\n",
+            escape_markdown(&self.username),
+            if self.retweet { " retweeted" } else { "" },
+            escape_markdown(&self.content)
+        )
+    }
+
+    fn to_html(&self) -> String {
+        format!(
+            "<blockquote class=\"tweet\">\n<p>{}</p>\n<footer>@{}{}</footer>\n</blockquote>\n",
+            escape_html(&self.content),
+            escape_html(&self.username),
+            if self.retweet { " retweeted" } else { "" }
+        )
+    }
+}
+
+impl Render for BlogPost {
+    fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "## {}\n\n*by {}*\n\n{}\n",
+            escape_markdown(&self.title),
+            escape_markdown(&self.author),
+            escape_markdown(&self.body)
+        );
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| escape_markdown(tag)).collect();
+            markdown.push_str(&format!("\nTags: {}\n", tags.join(", ")));
+        }
+        markdown
+    }
+
+    fn to_html(&self) -> String {
+        let tags: String = self
+            .tags
+            .iter()
+            .map(|tag| format!("<li>{}</li>", escape_html(tag)))
+            .collect();
+        format!(
+            "<article>\n<h2>{}</h2>\n<p class=\"byline\">by {}</p>\n<p>{}</p>\n<ul class=\"tags\">{}</ul>\n</article>\n",
+            escape_html(&self.title),
+            escape_html(&self.author),
+            escape_html(&self.body),
+            tags
+        )
+    }
+}
+
+impl Render for PodcastEpisode {
+    fn to_markdown(&self) -> String {
+        format!(
+            "### {} \\#{} ({}:{:02})\n\n{}\n",
+            escape_markdown(&self.show),
+            self.episode,
+            self.duration_secs / 60,
+            self.duration_secs % 60,
+            escape_markdown(&self.description)
+        )
+    }
+
+    fn to_html(&self) -> String {
+        format!(
+            "<article class=\"episode\">\n<h3>{} #{} ({}:{:02})</h3>\n<p>{}</p>\n</article>\n",
+            escape_html(&self.show),
+            self.episode,
+            self.duration_secs / 60,
+            self.duration_secs % 60,
+            escape_html(&self.description)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_escaping_neutralizes_markup() {
+        assert_eq!(
+            "&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt;",
+            escape_html("<script>alert('hi')</script>")
+        );
+        // Text that already looks escaped is escaped again, so it shows as typed.
+        assert_eq!("&amp;amp; &amp;lt;", escape_html("&amp; &lt;"));
+        assert_eq!(
+            "&lt;a href=&quot;x&quot; title=&#39;y&#39;&gt;",
+            escape_html(r#"<a href="x" title='y'>"#)
+        );
+        assert_eq!("plain text", escape_html("plain text"));
+    }
+
+    #[test]
+    fn markdown_escaping_neutralizes_formatting() {
+        assert_eq!(
+            r"\*bold\* \_it\_ \[link\]\(url\) \# \`code\`",
+            escape_markdown("*bold* _it_ [link](url) # `code`")
+        );
+        assert_eq!(
+            r"&amp;amp; a\\b \- \+ \!",
+            escape_markdown(r"&amp; a\b - + !")
+        );
+        // `<` becomes a character reference, which is enough to stop a tag.
+        assert_eq!(r"&lt;script\>", escape_markdown("<script>"));
+    }
+
+    #[test]
+    fn rendered_tweets_escape_their_content() {
+        let tweet = Tweet {
+            id: 1,
+            username: String::from("evil_"),
+            content: String::from("<script>steal()</script> *now*"),
+            reply_to: None,
+            retweet: false,
+            published_at: 0,
+        };
+        assert!(tweet
+            .to_html()
+            .contains("<p>&lt;script&gt;steal()&lt;/script&gt; *now*</p>"));
+        assert_eq!(
+            "**@evil\\_**: &lt;script\\>steal\\(\\)&lt;/script\\> \\*now\\*\n",
+            tweet.to_markdown()
+        );
+    }
+}
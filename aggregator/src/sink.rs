@@ -0,0 +1,96 @@
+// Where notifications go.
+// `notify` calls `println!` itself, so the only thing it can ever do is print, and a test
+// can't see what it printed. Taking the destination as a parameter fixes both: the caller
+// decides, and anything that implements `Sink` will do.
+
+use std::sync::mpsc::Sender;
+
+pub trait Sink {
+    fn send(&mut self, message: String);
+}
+
+// Prints each message on its own line.
+pub struct Stdout;
+
+impl Sink for Stdout {
+    fn send(&mut self, message: String) {
+        println!("{}", message);
+    }
+}
+
+// Collects the messages, which is handy in tests.
+// We can implement our own trait on `Vec`, a type from the standard library, because the
+// trait is local to our crate.
+impl Sink for Vec<String> {
+    fn send(&mut self, message: String) {
+        self.push(message);
+    }
+}
+
+// Hands each message to another thread. If the receiving end has hung up, nobody is left to
+// read the message, so it's dropped.
+impl Sink for Sender<String> {
+    fn send(&mut self, message: String) {
+        let _ = Sender::send(self, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+
+    use crate::{notify_all, NewsArticle, Summary};
+
+    fn article(headline: &str) -> NewsArticle {
+        NewsArticle {
+            headline: String::from(headline),
+            location: String::from("Pittsburgh, PA, USA"),
+            author: String::from("Iceburgh"),
+            content: String::new(),
+            published_at: 0,
+        }
+    }
+
+    #[test]
+    fn a_vec_collects_every_message_in_order() {
+        let first = article("Penguins win");
+        let second = article("Rain all week");
+        let items: [&dyn Summary; 2] = [&first, &second];
+
+        let mut sink = Vec::new();
+        notify_all(&items, &mut sink);
+
+        assert_eq!(
+            vec![
+                "Breaking news! Penguins win, by Iceburgh (Pittsburgh, PA, USA)",
+                "Breaking news! Rain all week, by Iceburgh (Pittsburgh, PA, USA)",
+            ],
+            sink
+        );
+    }
+
+    #[test]
+    fn a_channel_hands_the_messages_to_another_thread() {
+        let (mut sender, receiver) = mpsc::channel();
+        let reader = thread::spawn(move || receiver.iter().collect::<Vec<String>>());
+
+        let story = article("Penguins win");
+        notify_all(&[&story as &dyn Summary], &mut sender);
+        drop(sender);
+
+        assert_eq!(
+            vec!["Breaking news! Penguins win, by Iceburgh (Pittsburgh, PA, USA)"],
+            reader.join().unwrap()
+        );
+    }
+
+    #[test]
+    fn a_channel_nobody_reads_drops_the_messages() {
+        let (mut sender, receiver) = mpsc::channel::<String>();
+        drop(receiver);
+
+        let story = article("Penguins win");
+        notify_all(&[&story as &dyn Summary], &mut sender);
+    }
+}
@@ -0,0 +1,196 @@
+// Reply Threads
+// Each tweet knows which tweet it replies to, if any, which is enough to rebuild whole
+// conversations: a thread is a tweet together with the threads that reply to it, a tree.
+// A `Thread` borrows its tweets instead of owning them, so building threads doesn't copy
+// or move anything out of the caller's list.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{Summary, Timestamped, Tweet};
+
+#[derive(Debug)]
+pub struct Thread<'a> {
+    pub root: &'a Tweet,
+    // In the order they appear in the list the thread was built from.
+    pub replies: Vec<Thread<'a>>,
+}
+
+impl<'a> Thread<'a> {
+    // Every tweet in the thread, the root first and each reply before the replies to it.
+    pub fn tweets(&self) -> Vec<&'a Tweet> {
+        let mut tweets = vec![self.root];
+        for reply in &self.replies {
+            tweets.extend(reply.tweets());
+        }
+        tweets
+    }
+
+    pub fn len(&self) -> usize {
+        1 + self.replies.iter().map(Thread::len).sum::<usize>()
+    }
+
+    // A thread always has its root tweet, so it's never empty. Clippy expects `is_empty`
+    // wherever there's a `len`.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    // The usernames in the thread, each once, starting with whoever began it.
+    pub fn participants(&self) -> Vec<&'a str> {
+        let mut participants: Vec<&str> = Vec::new();
+        for tweet in self.tweets() {
+            if !participants.contains(&tweet.username.as_str()) {
+                participants.push(&tweet.username);
+            }
+        }
+        participants
+    }
+}
+
+// Groups `tweets` into threads, one for each tweet that isn't a reply, in the order the
+// tweets are given. A reply to a tweet that isn't in the list starts a thread of its own,
+// since we can't see what it answers. Tweets that reply to each other in a loop have no
+// such tweet to start from, so the first of each loop starts one, after all the others.
+pub fn threads(tweets: &[Tweet]) -> Vec<Thread<'_>> {
+    let known: HashMap<u64, &Tweet> = tweets.iter().map(|tweet| (tweet.id, tweet)).collect();
+
+    // Replies, grouped by the id of the tweet they answer.
+    let mut replies: HashMap<u64, Vec<&Tweet>> = HashMap::new();
+    let mut roots = Vec::new();
+    for tweet in tweets {
+        match tweet.reply_to {
+            Some(parent) if known.contains_key(&parent) => {
+                replies.entry(parent).or_default().push(tweet)
+            }
+            _ => roots.push(tweet),
+        }
+    }
+
+    let mut placed = HashSet::new();
+    let mut threads: Vec<Thread> = roots
+        .into_iter()
+        .map(|root| build(root, &mut replies, &mut placed))
+        .collect();
+    for tweet in tweets {
+        if !placed.contains(&tweet.id) {
+            threads.push(build(tweet, &mut replies, &mut placed));
+        }
+    }
+    threads
+}
+
+// Builds the thread under `root`, and adds the id of every tweet in it to `placed`. A tweet
+// that's already in a thread is left out, so a loop of replies ends where it comes back
+// around instead of going around forever.
+fn build<'a>(
+    root: &'a Tweet,
+    replies: &mut HashMap<u64, Vec<&'a Tweet>>,
+    placed: &mut HashSet<u64>,
+) -> Thread<'a> {
+    placed.insert(root.id);
+    let children = replies.remove(&root.id).unwrap_or_default();
+    let mut thread = Thread {
+        root,
+        replies: Vec::new(),
+    };
+    for reply in children {
+        if !placed.contains(&reply.id) {
+            thread.replies.push(build(reply, replies, placed));
+        }
+    }
+    thread
+}
+
+// A whole thread summarizes as one item, so a feed can show a conversation in one line.
+impl Timestamped for Thread<'_> {
+    // The latest activity, so a thread with fresh replies sorts as recent.
+    fn published_at(&self) -> u64 {
+        self.tweets()
+            .iter()
+            .map(|tweet| tweet.published_at)
+            .max()
+            .unwrap_or(self.root.published_at)
+    }
+}
+
+impl Summary for Thread<'_> {
+    fn summarize_author(&self) -> String {
+        self.root.summarize_author()
+    }
+
+    // @horse_ebooks and 2 others: of course, as you probably already know, people (5 tweets)
+    fn summarize(&self) -> String {
+        let others = self.participants().len() - 1;
+        let who = match others {
+            0 => self.summarize_author(),
+            1 => format!("{} and 1 other", self.summarize_author()),
+            _ => format!("{} and {} others", self.summarize_author(), others),
+        };
+        let count = match self.len() {
+            1 => String::from("1 tweet"),
+            n => format!("{} tweets", n),
+        };
+        format!("{}: {} ({})", who, self.root.content, count)
+    }
+
+    fn content(&self) -> &str {
+        &self.root.content
+    }
+
+    fn author(&self) -> &str {
+        &self.root.username
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweet(id: u64, username: &str, reply_to: Option<u64>) -> Tweet {
+        Tweet {
+            id,
+            username: String::from(username),
+            content: format!("tweet {}", id),
+            reply_to,
+            retweet: false,
+            published_at: id,
+        }
+    }
+
+    fn ids(thread: &Thread) -> Vec<u64> {
+        thread.tweets().iter().map(|tweet| tweet.id).collect()
+    }
+
+    #[test]
+    fn replies_hang_under_what_they_answer() {
+        let tweets = [
+            tweet(1, "a", None),
+            tweet(2, "b", Some(1)),
+            tweet(3, "c", Some(99)),
+            tweet(4, "a", Some(2)),
+        ];
+        let threads = threads(&tweets);
+
+        assert_eq!(2, threads.len());
+        assert_eq!(vec![1, 2, 4], ids(&threads[0]));
+        assert_eq!(vec!["a", "b"], threads[0].participants());
+        assert_eq!(vec![3], ids(&threads[1]));
+    }
+
+    #[test]
+    fn tweets_replying_in_a_loop_still_come_out() {
+        let tweets = [
+            tweet(1, "a", None),
+            tweet(2, "b", Some(3)),
+            tweet(3, "c", Some(2)),
+            tweet(4, "d", Some(3)),
+        ];
+        let threads = threads(&tweets);
+
+        assert_eq!(2, threads.len());
+        assert_eq!(vec![1], ids(&threads[0]));
+        assert_eq!(vec![2, 3, 4], ids(&threads[1]));
+        let total: usize = threads.iter().map(Thread::len).sum();
+        assert_eq!(tweets.len(), total);
+    }
+}
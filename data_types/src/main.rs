@@ -1,5 +1,12 @@
 use std::io;
 
+use safe_math::{parse_index, try_convert, Arithmetic};
+
+mod safe_math;
+
+// The notes below are doc comments on statements, which rustdoc doesn't use, and most
+// bindings only demonstrate a type, so they are never read.
+#[allow(unused_doc_comments, unused_variables)]
 fn main() {
     /// We look at two data type subsets: scalar and compound.
     /// Rust is a *statically typed* language.
@@ -28,6 +35,31 @@ fn main() {
     /// * Return the `None` value if there is overflow with the `checked_*` methods.
     /// * Return the value and a boolean indicating whether there was overflow with `overflow_*` methods.
     /// * Saturate at the value's minimum or maximum values with `saturating_*` methods.
+    ///
+    /// The `safe_math` module puts the wrapping, saturating, and checked variants behind
+    /// one `Arithmetic` trait, and `try_convert` checks the range when converting between
+    /// integer types.
+    let level: u8 = 250;
+    println!(
+        "250 + 10 as u8: wrapping {}, saturating {}, checked {:?}",
+        level.add_wrapping(10),
+        level.add_saturating(10),
+        level.add_checked(10)
+    );
+    println!(
+        "0 - 1 as u8: wrapping {}, saturating {}, checked {:?}",
+        0u8.sub_wrapping(1),
+        0u8.sub_saturating(1),
+        0u8.sub_checked(1)
+    );
+    println!(
+        "i32::MAX * 2: wrapping {}, saturating {}, checked {:?}",
+        i32::MAX.mul_wrapping(2),
+        i32::MAX.mul_saturating(2),
+        i32::MAX.mul_checked(2)
+    );
+    let too_big: Result<u8, _> = try_convert(300i32);
+    println!("300i32 as u8: {:?}", too_big);
 
     /// ### Floating-Point Types
     ///
@@ -144,13 +176,18 @@ fn main() {
         .read_line(&mut index)
         .expect("Failed to read line");
 
-    let index: usize = index
-        .trim()
-        .parse()
-        .expect("Index entered was not a number");
-
-    let element = a[index]; // Rust checks that the index we've specified is less than the array length.
-                            // If the index is greater than or equal to the length, Rust will panic.
+    // Rust checks that the index we've specified is less than the array length.
+    // If the index is greater than or equal to the length, Rust will panic. Checking
+    // with `parse_index` first lets us report the problem instead.
+    let index = match parse_index(&index, a.len()) {
+        Ok(index) => index,
+        Err(e) => {
+            println!("Invalid index: {}", e);
+            return;
+        }
+    };
+
+    let element = a[index];
 
     println!(
         "The value of the element at index {} is: {}",
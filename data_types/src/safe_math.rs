@@ -0,0 +1,172 @@
+//! Numeric helpers that report problems as values instead of panicking.
+//!
+//! Integer overflow panics in debug builds and silently wraps in release builds,
+//! and indexing an array out of bounds always panics. The functions here return
+//! a `Result` or an `Option` so the caller decides what to do.
+
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum MathError {
+    /// The value doesn't fit in the target integer type.
+    OutOfRange,
+    /// The text isn't a non-negative whole number.
+    NotANumber(String),
+    /// The index is greater than or equal to the length of the array.
+    IndexOutOfBounds { index: usize, len: usize },
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MathError::OutOfRange => write!(f, "value is out of range for the target type"),
+            MathError::NotANumber(input) => write!(f, "{:?} is not a number", input),
+            MathError::IndexOutOfBounds { index, len } => {
+                write!(f, "index {} is out of bounds for length {}", index, len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+/// Converts between integer types, checking that the value fits.
+///
+/// `as` casts never fail: `300i32 as u8` is `44` and `-1i32 as u32` is
+/// `4294967295`. `TryFrom` checks the range instead, and this function maps its
+/// error to `MathError::OutOfRange`.
+pub fn try_convert<T, U: TryFrom<T>>(value: T) -> Result<U, MathError> {
+    U::try_from(value).map_err(|_| MathError::OutOfRange)
+}
+
+/// Parses user input as an index into an array of length `len`.
+///
+/// Rust checks every index at runtime and panics if it is out of bounds; checking
+/// first lets the program tell the user what went wrong instead.
+pub fn parse_index(input: &str, len: usize) -> Result<usize, MathError> {
+    let input = input.trim();
+    let index: usize = input
+        .parse()
+        .map_err(|_| MathError::NotANumber(input.to_string()))?;
+
+    if index < len {
+        Ok(index)
+    } else {
+        Err(MathError::IndexOutOfBounds { index, len })
+    }
+}
+
+/// The three ways of explicitly handling overflow behind one trait:
+/// * `wrapping_*` wraps around at the type's boundary.
+/// * `saturating_*` stops at the type's minimum or maximum value.
+/// * `checked_*` returns `None` on overflow.
+///
+/// The integer types already have these as inherent methods, so the trait methods
+/// are prefixed with the operation to avoid shadowing them.
+pub trait Arithmetic: Sized {
+    fn add_wrapping(self, rhs: Self) -> Self;
+    fn add_saturating(self, rhs: Self) -> Self;
+    fn add_checked(self, rhs: Self) -> Option<Self>;
+
+    fn sub_wrapping(self, rhs: Self) -> Self;
+    fn sub_saturating(self, rhs: Self) -> Self;
+    fn sub_checked(self, rhs: Self) -> Option<Self>;
+
+    fn mul_wrapping(self, rhs: Self) -> Self;
+    fn mul_saturating(self, rhs: Self) -> Self;
+    fn mul_checked(self, rhs: Self) -> Option<Self>;
+}
+
+// Every integer type implements the trait the same way, so a macro writes the
+// implementations for us.
+macro_rules! impl_arithmetic {
+    ($($t:ty),*) => {
+        $(
+            impl Arithmetic for $t {
+                fn add_wrapping(self, rhs: Self) -> Self { self.wrapping_add(rhs) }
+                fn add_saturating(self, rhs: Self) -> Self { self.saturating_add(rhs) }
+                fn add_checked(self, rhs: Self) -> Option<Self> { self.checked_add(rhs) }
+
+                fn sub_wrapping(self, rhs: Self) -> Self { self.wrapping_sub(rhs) }
+                fn sub_saturating(self, rhs: Self) -> Self { self.saturating_sub(rhs) }
+                fn sub_checked(self, rhs: Self) -> Option<Self> { self.checked_sub(rhs) }
+
+                fn mul_wrapping(self, rhs: Self) -> Self { self.wrapping_mul(rhs) }
+                fn mul_saturating(self, rhs: Self) -> Self { self.saturating_mul(rhs) }
+                fn mul_checked(self, rhs: Self) -> Option<Self> { self.checked_mul(rhs) }
+            }
+        )*
+    };
+}
+
+impl_arithmetic!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_convert_checks_both_boundaries() {
+        assert_eq!(Ok(255u8), try_convert(255i32));
+        assert_eq!(Err(MathError::OutOfRange), try_convert::<i32, u8>(256));
+        assert_eq!(Ok(0u32), try_convert(0i64));
+        assert_eq!(Err(MathError::OutOfRange), try_convert::<i64, u32>(-1));
+        assert_eq!(Ok(i8::MIN), try_convert(-128i64));
+        assert_eq!(Err(MathError::OutOfRange), try_convert::<i64, i8>(-129));
+        assert_eq!(Ok(u64::MAX as u128), try_convert(u64::MAX));
+    }
+
+    #[test]
+    fn parse_index_validates_before_indexing() {
+        assert_eq!(Ok(0), parse_index("0", 5));
+        assert_eq!(Ok(4), parse_index(" 4\n", 5));
+        assert_eq!(
+            Err(MathError::IndexOutOfBounds { index: 5, len: 5 }),
+            parse_index("5", 5)
+        );
+        assert_eq!(
+            Err(MathError::IndexOutOfBounds { index: 0, len: 0 }),
+            parse_index("0", 0)
+        );
+        assert_eq!(
+            Err(MathError::NotANumber(String::from("-1"))),
+            parse_index("-1", 5)
+        );
+        assert_eq!(
+            Err(MathError::NotANumber(String::from("two"))),
+            parse_index("two", 5)
+        );
+    }
+
+    #[test]
+    fn unsigned_arithmetic_at_the_boundaries() {
+        assert_eq!(0, u8::MAX.add_wrapping(1));
+        assert_eq!(u8::MAX, u8::MAX.add_saturating(1));
+        assert_eq!(None, u8::MAX.add_checked(1));
+        assert_eq!(Some(u8::MAX), 254u8.add_checked(1));
+
+        assert_eq!(u32::MAX, 0u32.sub_wrapping(1));
+        assert_eq!(0, 0u32.sub_saturating(1));
+        assert_eq!(None, 0u32.sub_checked(1));
+
+        assert_eq!(0, 128u8.mul_wrapping(2));
+        assert_eq!(u8::MAX, 128u8.mul_saturating(2));
+        assert_eq!(None, 128u8.mul_checked(2));
+    }
+
+    #[test]
+    fn signed_arithmetic_at_the_boundaries() {
+        assert_eq!(i8::MIN, i8::MAX.add_wrapping(1));
+        assert_eq!(i8::MAX, i8::MAX.add_saturating(1));
+        assert_eq!(None, i8::MAX.add_checked(1));
+
+        assert_eq!(i32::MAX, i32::MIN.sub_wrapping(1));
+        assert_eq!(i32::MIN, i32::MIN.sub_saturating(1));
+        assert_eq!(None, i32::MIN.sub_checked(1));
+
+        assert_eq!(i64::MIN, i64::MIN.mul_wrapping(-1));
+        assert_eq!(i64::MAX, i64::MIN.mul_saturating(-1));
+        assert_eq!(None, i64::MIN.mul_checked(-1));
+        assert_eq!(Some(-6), 2i64.mul_checked(-3));
+    }
+}
@@ -2,6 +2,10 @@
 
 use std::ops::Deref;
 
+use my_rc::MyRc;
+
+mod my_rc;
+
 struct MyBox<T>(T);
 
 impl<T> MyBox<T> {
@@ -173,5 +177,18 @@ fn main() {
     /// reference to that data, but the borrowing rules don't guarantee that.
     /// Therefore, Rust cannot make the assumption that converting an immutable reference to a
     /// mutable reference is possible.
-    ()
+    let x = 5;
+    let y = MyBox::new(x);
+    assert_eq!(5, *y);
+
+    /// ### Sharing a Value with a Reference-Counted Pointer
+    ///
+    /// `MyRc<T>` (in *my_rc.rs*) combines `Deref` with the `Drop` trait: every clone
+    /// points at the same value and bumps a count, and the last clone to be dropped
+    /// frees the value.
+    let a = MyRc::new(String::from("Rust"));
+    let b = MyRc::clone(&a);
+    println!("{} has {} owners", *b, MyRc::strong_count(&a));
+    drop(a);
+    println!("{} has {} owner", *b, MyRc::strong_count(&b));
 }
\ No newline at end of file
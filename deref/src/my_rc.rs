@@ -0,0 +1,157 @@
+//! A reference-counted smart pointer built from the same pieces as `MyBox<T>`.
+//!
+//! `MyBox<T>` owns its value directly, so there can only ever be one owner. To
+//! share a value, `MyRc<T>` keeps the value on the heap next to a count of how
+//! many `MyRc<T>` pointers exist. Cloning bumps the count, dropping lowers it, and
+//! the last owner to be dropped frees the allocation.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+/// The heap allocation shared by every clone of a `MyRc<T>`.
+///
+/// The count lives in a `Cell` because clones only hold `&` access to it, yet
+/// still need to change it.
+struct RcBox<T> {
+    strong: Cell<usize>,
+    value: T,
+}
+
+pub struct MyRc<T> {
+    ptr: NonNull<RcBox<T>>,
+    // Tells the compiler that `MyRc<T>` owns an `RcBox<T>`, so dropping a
+    // `MyRc<T>` may drop a `T`. A raw pointer alone doesn't say that.
+    phantom: PhantomData<RcBox<T>>,
+}
+
+impl<T> MyRc<T> {
+    pub fn new(value: T) -> MyRc<T> {
+        let boxed = Box::new(RcBox {
+            strong: Cell::new(1),
+            value,
+        });
+
+        // `Box::leak` hands over the allocation without freeing it; from here on
+        // `Drop for MyRc<T>` is responsible for it.
+        MyRc {
+            ptr: NonNull::from(Box::leak(boxed)),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of `MyRc<T>` pointers sharing this value, like
+    /// `Rc::strong_count`. It's an associated function rather than a method so it
+    /// can't be confused with a method on `T` reached through `Deref`.
+    pub fn strong_count(this: &MyRc<T>) -> usize {
+        this.inner().strong.get()
+    }
+
+    fn inner(&self) -> &RcBox<T> {
+        // SAFETY: the allocation stays alive as long as any `MyRc<T>` points to it,
+        // and `self` is one of those pointers.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> Clone for MyRc<T> {
+    /// Cloning copies the pointer, not the value, and bumps the count.
+    fn clone(&self) -> MyRc<T> {
+        let strong = &self.inner().strong;
+        strong.set(strong.get() + 1);
+
+        MyRc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Deref for MyRc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().value
+    }
+}
+
+impl<T> Drop for MyRc<T> {
+    fn drop(&mut self) {
+        let strong = &self.inner().strong;
+        strong.set(strong.get() - 1);
+
+        if strong.get() == 0 {
+            // SAFETY: this was the last owner, so nothing else can reach the
+            // allocation. Rebuilding the `Box` frees it and drops the value.
+            unsafe {
+                drop(Box::from_raw(self.ptr.as_ptr()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// Counts how many times it has been dropped through a shared counter. The
+    /// counter is a standard `Rc` so the test can still read it afterwards.
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn clone_shares_the_value_and_bumps_the_count() {
+        let a = MyRc::new(String::from("Rust"));
+        assert_eq!(1, MyRc::strong_count(&a));
+
+        let b = MyRc::clone(&a);
+        assert_eq!(2, MyRc::strong_count(&a));
+        assert_eq!(2, MyRc::strong_count(&b));
+        assert_eq!("Rust", *b);
+
+        // Deref coercion works just like with `MyBox<T>`.
+        let s: &str = &a;
+        assert_eq!(4, s.len());
+
+        drop(a);
+        assert_eq!(1, MyRc::strong_count(&b));
+    }
+
+    #[test]
+    fn value_is_dropped_exactly_once_by_the_last_owner() {
+        let drops = Rc::new(Cell::new(0));
+
+        let a = MyRc::new(DropCounter(Rc::clone(&drops)));
+        let b = a.clone();
+        let c = b.clone();
+
+        drop(a);
+        drop(c);
+        assert_eq!(0, drops.get());
+
+        drop(b);
+        assert_eq!(1, drops.get());
+    }
+
+    #[test]
+    fn owners_can_be_dropped_in_any_order() {
+        let drops = Rc::new(Cell::new(0));
+
+        {
+            let owners: Vec<_> = {
+                let first = MyRc::new(DropCounter(Rc::clone(&drops)));
+                (0..5).map(|_| first.clone()).collect()
+            };
+            assert_eq!(5, MyRc::strong_count(&owners[0]));
+        }
+
+        assert_eq!(1, drops.get());
+    }
+}
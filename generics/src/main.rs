@@ -47,6 +47,21 @@ fn main() {
     let p3 = p1.mixup(p2);
     println!("p3.x = {}, p3.y = {}", p3.x, p3.y);
 
+    // Combinators
+    // Each of these keeps `x` and `y` independent, so coordinate conversions become
+    // one-liners. Here a pixel position becomes a cell position on a grid of
+    // 8x16-pixel cells.
+    let pixel = Point { x: 100u32, y: 50u32 };
+    let cell = pixel.map_x(|x| x / 8).map_y(|y| y / 16);
+    println!("cell.x = {}, cell.y = {}", cell.x, cell.y);
+
+    let offset = Point { x: 2, y: 1.5 };
+    let moved = cell.zip_with(offset, |a, b| a + b, |a, b| f64::from(a) + b);
+    println!("moved.x = {}, moved.y = {}", moved.x, moved.y);
+
+    let swapped = Point { x: "row", y: 3 }.swap_axes();
+    println!("swapped.x = {}, swapped.y = {}", swapped.x, swapped.y);
+
     // Rust performs monomorphization of the code using generics at compile time.
     // We pay no runtime cost for using generics.
 }
@@ -111,4 +126,41 @@ impl<X1, Y1> Point<X1, Y1> {
             y: other.y,
         }
     }
+
+    // Combines two points coordinate by coordinate. Because `x` and `y` can have
+    // different types, a single closure couldn't accept both; each axis gets its
+    // own closure, and each may produce a new type.
+    fn zip_with<X2, Y2, X3, Y3, FX, FY>(self, other: Point<X2, Y2>, fx: FX, fy: FY) -> Point<X3, Y3>
+    where
+        FX: FnOnce(X1, X2) -> X3,
+        FY: FnOnce(Y1, Y2) -> Y3,
+    {
+        Point {
+            x: fx(self.x, other.x),
+            y: fy(self.y, other.y),
+        }
+    }
+
+    // The type parameters swap places along with the values.
+    fn swap_axes(self) -> Point<Y1, X1> {
+        Point {
+            x: self.y,
+            y: self.x,
+        }
+    }
+
+    // Transforms one coordinate, leaving the other (and its type) untouched.
+    fn map_x<X2, F: FnOnce(X1) -> X2>(self, f: F) -> Point<X2, Y1> {
+        Point {
+            x: f(self.x),
+            y: self.y,
+        }
+    }
+
+    fn map_y<Y2, F: FnOnce(Y1) -> Y2>(self, f: F) -> Point<X1, Y2> {
+        Point {
+            x: self.x,
+            y: f(self.y),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,217 @@
+//! Settings for a game, from the command line and the environment.
+//!
+//! ```text
+//! guessing_game [--difficulty easy|normal|hard] [--min N] [--max N] [--seed N] [--hints]
+//!               [--host ADDR | --join ADDR | --computer] [--debug]
+//! ```
+//!
+//! `--computer` turns the game around: the player thinks of a number, and the computer
+//! guesses it.
+//!
+//! `--host` waits for another player at `ADDR`, like `0.0.0.0:7878`, after asking for the
+//! secret number, and `--join` guesses the number someone is hosting there.
+//!
+//! With `--hints`, a wrong guess is answered with whether it was warmer or colder than the
+//! one before, instead of too small or too big.
+//!
+//! Without a difficulty, the number is from 1 to 100, and there's no limit on guesses. A
+//! difficulty picks the range, which `--min` and `--max` can still change, and the limit.
+//!
+//! The range and the seed can also be set with the `GUESS_MIN`, `GUESS_MAX`, and
+//! `GUESS_SEED` environment variables. An option on the command line wins over the
+//! variable.
+
+use crate::Difficulty;
+
+/// Whose computer the secret number is on, when two players play over the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Network {
+    /// Pick the secret number, and wait for a guesser at this address.
+    Host(String),
+    /// Guess the number hosted at this address.
+    Join(String),
+}
+
+/// The secret number is picked from `min..=max`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub min: u32,
+    pub max: u32,
+    /// `None` for as many guesses as it takes.
+    pub max_attempts: Option<u32>,
+    /// With a seed, the random number generator picks the same secret number every time,
+    /// which makes a game reproducible for tests and demos.
+    pub seed: Option<u64>,
+    /// Whether to give warmer/colder hints.
+    pub hints: bool,
+    /// `None` for a game on this computer alone.
+    pub network: Option<Network>,
+    /// Whether the computer guesses, instead of the player.
+    pub computer: bool,
+    /// Whether to print the secret number, which spoils the game otherwise.
+    pub debug: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            min: 1,
+            max: 100,
+            max_attempts: None,
+            seed: None,
+            hints: false,
+            network: None,
+            computer: false,
+            debug: false,
+        }
+    }
+}
+
+impl Config {
+    /// Builds the settings from `args`, which doesn't include the program name, and the
+    /// variables `var` looks up. `var` is `std::env::var(name).ok()` outside of tests.
+    pub fn build(
+        mut args: impl Iterator<Item = String>,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Config, String> {
+        let mut config = Config::default();
+        // The range is only known once the difficulty is, which may come after `--min`.
+        let mut min = None;
+        let mut max = None;
+        let mut difficulty = None;
+
+        if let Some(value) = var("GUESS_MIN") {
+            min = Some(parse("GUESS_MIN", &value)?);
+        }
+        if let Some(value) = var("GUESS_MAX") {
+            max = Some(parse("GUESS_MAX", &value)?);
+        }
+        if let Some(seed) = var("GUESS_SEED") {
+            config.seed = Some(parse("GUESS_SEED", &seed)?);
+        }
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--debug" => config.debug = true,
+                "--hints" => config.hints = true,
+                "--computer" => config.computer = true,
+                "--host" | "--join" => {
+                    let addr = args
+                        .next()
+                        .ok_or_else(|| format!("{} needs an address after it", arg))?;
+                    config.network = Some(match arg.as_str() {
+                        "--host" => Network::Host(addr),
+                        _ => Network::Join(addr),
+                    });
+                }
+                "--difficulty" | "--min" | "--max" | "--seed" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("{} needs a value after it", arg))?;
+                    match arg.as_str() {
+                        "--difficulty" => difficulty = Some(value.parse::<Difficulty>()?),
+                        "--min" => min = Some(parse(&arg, &value)?),
+                        "--max" => max = Some(parse(&arg, &value)?),
+                        _ => config.seed = Some(parse(&arg, &value)?),
+                    }
+                }
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        if let Some(difficulty) = difficulty {
+            config.min = *difficulty.range().start();
+            config.max = *difficulty.range().end();
+            config.max_attempts = Some(difficulty.max_attempts());
+        }
+        config.min = min.unwrap_or(config.min);
+        config.max = max.unwrap_or(config.max);
+
+        if config.min > config.max {
+            return Err(format!(
+                "the range {}..={} is empty: the minimum is bigger than the maximum",
+                config.min, config.max
+            ));
+        }
+        Ok(config)
+    }
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{} should be a number, not {:?}", name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn no_vars(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn defaults_to_one_to_a_hundred() {
+        assert_eq!(Ok(Config::default()), Config::build(args(&[]), no_vars));
+    }
+
+    #[test]
+    fn arguments_win_over_variables() {
+        let vars = |name: &str| match name {
+            "GUESS_MIN" => Some("10".to_string()),
+            "GUESS_MAX" => Some("20".to_string()),
+            "GUESS_SEED" => Some("7".to_string()),
+            _ => None,
+        };
+        let config = Config::build(args(&["--max", "50", "--debug"]), vars).unwrap();
+        assert_eq!(
+            Config {
+                min: 10,
+                max: 50,
+                max_attempts: None,
+                seed: Some(7),
+                hints: false,
+                network: None,
+                computer: false,
+                debug: true
+            },
+            config
+        );
+    }
+
+    #[test]
+    fn a_difficulty_sets_the_range_and_the_limit() {
+        let config = Config::build(args(&["--min", "500", "--difficulty", "hard"]), no_vars);
+        let config = config.unwrap();
+        assert_eq!((500, 1000), (config.min, config.max));
+        assert_eq!(Some(10), config.max_attempts);
+    }
+
+    #[test]
+    fn the_last_network_option_wins() {
+        let config = Config::build(args(&["--host", ":7878", "--join", "a:1"]), no_vars);
+        assert_eq!(
+            Some(Network::Join("a:1".to_string())),
+            config.unwrap().network
+        );
+    }
+
+    #[test]
+    fn bad_settings_are_errors() {
+        assert!(Config::build(args(&["--min"]), no_vars).is_err());
+        assert!(Config::build(args(&["--seed", "x"]), no_vars).is_err());
+        assert!(Config::build(args(&["--min", "9", "--max", "3"]), no_vars).is_err());
+        assert!(Config::build(args(&["--loud"]), no_vars).is_err());
+        assert!(Config::build(args(&["--join"]), no_vars).is_err());
+        assert!(Config::build(args(&["--difficulty", "medium"]), no_vars).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Making sense of what the player types.
+//!
+//! A line that isn't a guess isn't just skipped: each way it can be wrong gets its own
+//! `InputError`, which says what to type instead.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// What the player asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Guess(u32),
+    Quit,
+}
+
+/// Why a line isn't a guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    /// Nothing but whitespace.
+    Empty,
+    /// Something that isn't a whole number.
+    NotANumber(String),
+    /// A whole number, but not one the secret number could be. It's an `i64`, so negative
+    /// numbers and ones too big for a `u32` fit too.
+    OutOfRange { value: i64, min: u32, max: u32 },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Empty => write!(f, "Type a number, or q to quit."),
+            InputError::NotANumber(text) => {
+                write!(
+                    f,
+                    "{:?} isn't a number. Type a whole number, like 42.",
+                    text
+                )
+            }
+            InputError::OutOfRange { value, min, max } => write!(
+                f,
+                "{} can't be it: the secret number is between {} and {}.",
+                value, min, max
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Reads one line the player typed, as a guess from `range`, or `q` to quit.
+pub fn parse(line: &str, range: RangeInclusive<u32>) -> Result<Command, InputError> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Err(InputError::Empty);
+    }
+    if line.eq_ignore_ascii_case("q") {
+        return Ok(Command::Quit);
+    }
+    let value: i64 = line
+        .parse()
+        .map_err(|_| InputError::NotANumber(line.to_string()))?;
+    match u32::try_from(value) {
+        Ok(guess) if range.contains(&guess) => Ok(Command::Guess(guess)),
+        _ => Err(InputError::OutOfRange {
+            value,
+            min: *range.start(),
+            max: *range.end(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_and_quitting() {
+        assert_eq!(Ok(Command::Guess(42)), parse(" 42\n", 1..=100));
+        assert_eq!(Ok(Command::Quit), parse("Q\n", 1..=100));
+    }
+
+    #[test]
+    fn each_mistake_has_its_own_error() {
+        assert_eq!(Err(InputError::Empty), parse("  \n", 1..=100));
+        assert_eq!(
+            Err(InputError::NotANumber("forty".to_string())),
+            parse("forty\n", 1..=100)
+        );
+        assert_eq!(
+            Err(InputError::OutOfRange {
+                value: -5,
+                min: 1,
+                max: 100
+            }),
+            parse("-5", 1..=100)
+        );
+        assert!(matches!(
+            parse("5000000000", 1..=100),
+            Err(InputError::OutOfRange { .. })
+        ));
+    }
+}
@@ -0,0 +1,342 @@
+//! The rules of the guessing game, with no input or output in them. `main.rs` reads the
+//! guesses and prints the results; everything in between happens here, where it can be
+//! tested.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+pub use crate::config::{Config, Network};
+
+pub mod config;
+pub mod input;
+pub mod net;
+pub mod solver;
+
+/// How a guess compares to the secret number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    TooSmall,
+    TooBig,
+    Correct,
+}
+
+/// How a guess compares to the one before it, for the warmer/colder hint mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// Closer to the secret number than the guess before.
+    Warmer,
+    /// Farther from it.
+    Colder,
+    /// Just as far, on the other side, or the same guess again.
+    Same,
+}
+
+/// How hard a game is: the bigger the range, and the fewer the attempts, the harder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// The range the secret number is picked from.
+    pub fn range(self) -> RangeInclusive<u32> {
+        match self {
+            Difficulty::Easy => 1..=50,
+            Difficulty::Normal => 1..=100,
+            Difficulty::Hard => 1..=1000,
+        }
+    }
+
+    /// How many guesses the player gets. Halving the range each time always finds the
+    /// number within 6 guesses on Easy, 7 on Normal, and 10 on Hard, so Hard leaves no room
+    /// for a wasted guess.
+    pub fn max_attempts(self) -> u32 {
+        match self {
+            Difficulty::Easy => 10,
+            Difficulty::Normal => 8,
+            Difficulty::Hard => 10,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!(
+                "{:?} isn't a difficulty: try easy, normal, or hard",
+                s
+            )),
+        }
+    }
+}
+
+/// One game: the secret number, and how many guesses it took so far.
+#[derive(Debug)]
+pub struct GameEngine {
+    secret: u32,
+    attempts: u32,
+    /// `None` for as many attempts as it takes.
+    max_attempts: Option<u32>,
+    won: bool,
+    /// The last guess, and how it compared to the one before.
+    previous: Option<u32>,
+    hint: Option<Hint>,
+}
+
+impl GameEngine {
+    pub fn new(secret: u32) -> GameEngine {
+        GameEngine {
+            secret,
+            attempts: 0,
+            max_attempts: None,
+            won: false,
+            previous: None,
+            hint: None,
+        }
+    }
+
+    /// A game that's lost if the secret number isn't guessed in `max_attempts` guesses.
+    pub fn with_max_attempts(secret: u32, max_attempts: u32) -> GameEngine {
+        GameEngine {
+            max_attempts: Some(max_attempts),
+            ..GameEngine::new(secret)
+        }
+    }
+
+    /// Compares `n` to the secret number. Every guess counts as an attempt, even one that
+    /// was guessed before.
+    ///
+    /// # Panics
+    ///
+    /// If the game is already over.
+    pub fn guess(&mut self, n: u32) -> Outcome {
+        assert!(!self.is_over(), "the game is over");
+        self.attempts += 1;
+        if let Some(previous) = self.previous {
+            let distance = n.abs_diff(self.secret);
+            self.hint = Some(match distance.cmp(&previous.abs_diff(self.secret)) {
+                Ordering::Less => Hint::Warmer,
+                Ordering::Greater => Hint::Colder,
+                Ordering::Equal => Hint::Same,
+            });
+        }
+        self.previous = Some(n);
+        match n.cmp(&self.secret) {
+            Ordering::Less => Outcome::TooSmall,
+            Ordering::Greater => Outcome::TooBig,
+            Ordering::Equal => {
+                self.won = true;
+                Outcome::Correct
+            }
+        }
+    }
+
+    /// Whether the last guess was closer than the one before, or `None` after the first
+    /// guess, when there's nothing to compare it to.
+    pub fn hint(&self) -> Option<Hint> {
+        self.hint
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// How many guesses are left, or `None` if there's no limit.
+    pub fn remaining_attempts(&self) -> Option<u32> {
+        self.max_attempts.map(|max| max - self.attempts)
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.won
+    }
+
+    /// Whether the attempts ran out before the secret number was guessed.
+    pub fn is_lost(&self) -> bool {
+        !self.won && self.remaining_attempts() == Some(0)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.is_lost()
+    }
+
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+}
+
+/// Statistics over the rounds played in one run of the game.
+#[derive(Debug, Default)]
+pub struct Session {
+    rounds: u32,
+    wins: u32,
+    total_attempts: u32,
+    /// The fewest attempts a round was won in.
+    best: Option<u32>,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    /// Adds a round that's over to the statistics.
+    ///
+    /// # Panics
+    ///
+    /// If the round isn't over yet.
+    pub fn record(&mut self, round: &GameEngine) {
+        assert!(round.is_over(), "the round isn't over yet");
+        self.rounds += 1;
+        self.total_attempts += round.attempts();
+        if round.is_won() {
+            self.wins += 1;
+            self.best = Some(
+                self.best
+                    .map_or(round.attempts(), |best| best.min(round.attempts())),
+            );
+        }
+    }
+
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    /// The attempts per round, lost rounds included, or `None` before the first round.
+    pub fn average_attempts(&self) -> Option<f64> {
+        if self.rounds == 0 {
+            None
+        } else {
+            Some(self.total_attempts as f64 / self.rounds as f64)
+        }
+    }
+
+    /// The fewest attempts a round was won in, or `None` if none were won.
+    pub fn best_round(&self) -> Option<u32> {
+        self.best
+    }
+}
+
+/// The summary printed when the player quits.
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Rounds played: {} ({} won)", self.rounds, self.wins)?;
+        if let Some(average) = self.average_attempts() {
+            writeln!(f, "Average attempts: {:.1}", average)?;
+        }
+        match self.best {
+            Some(1) => write!(f, "Best round: 1 attempt"),
+            Some(best) => write!(f, "Best round: {} attempts", best),
+            None => write!(f, "Best round: none won yet"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_compare_to_the_secret() {
+        let mut engine = GameEngine::new(50);
+        assert_eq!(Outcome::TooSmall, engine.guess(10));
+        assert_eq!(Outcome::TooBig, engine.guess(90));
+        assert_eq!(Outcome::Correct, engine.guess(50));
+    }
+
+    #[test]
+    fn every_guess_is_an_attempt() {
+        let mut engine = GameEngine::new(7);
+        assert_eq!(0, engine.attempts());
+        engine.guess(3);
+        engine.guess(3);
+        engine.guess(7);
+        assert_eq!(3, engine.attempts());
+    }
+
+    #[test]
+    fn hints_compare_with_the_guess_before() {
+        let mut engine = GameEngine::new(50);
+        engine.guess(10);
+        assert_eq!(None, engine.hint());
+        engine.guess(70);
+        assert_eq!(Some(Hint::Warmer), engine.hint());
+        engine.guess(20);
+        assert_eq!(Some(Hint::Colder), engine.hint());
+        engine.guess(80);
+        assert_eq!(Some(Hint::Same), engine.hint());
+    }
+
+    #[test]
+    fn running_out_of_attempts_loses() {
+        let mut engine = GameEngine::with_max_attempts(7, 2);
+        assert_eq!(Some(2), engine.remaining_attempts());
+        engine.guess(1);
+        assert_eq!(Some(1), engine.remaining_attempts());
+        assert!(!engine.is_over());
+        engine.guess(2);
+        assert!(engine.is_lost());
+        assert!(!engine.is_won());
+    }
+
+    #[test]
+    fn guessing_with_the_last_attempt_wins() {
+        let mut engine = GameEngine::with_max_attempts(7, 1);
+        assert_eq!(Outcome::Correct, engine.guess(7));
+        assert!(engine.is_won());
+        assert!(!engine.is_lost());
+    }
+
+    #[test]
+    #[should_panic(expected = "the game is over")]
+    fn no_guesses_after_the_game_is_over() {
+        let mut engine = GameEngine::new(7);
+        engine.guess(7);
+        engine.guess(7);
+    }
+
+    fn finished_round(secret: u32, guesses: &[u32]) -> GameEngine {
+        let mut engine = GameEngine::with_max_attempts(secret, 3);
+        for &guess in guesses {
+            engine.guess(guess);
+        }
+        engine
+    }
+
+    #[test]
+    fn a_session_adds_up_its_rounds() {
+        let mut session = Session::new();
+        assert_eq!(None, session.average_attempts());
+
+        session.record(&finished_round(5, &[1, 5]));
+        session.record(&finished_round(5, &[1, 2, 3]));
+        session.record(&finished_round(5, &[5]));
+
+        assert_eq!(3, session.rounds());
+        assert_eq!(2, session.wins());
+        assert_eq!(Some(2.0), session.average_attempts());
+        assert_eq!(Some(1), session.best_round());
+        assert_eq!(
+            "Rounds played: 3 (2 won)\nAverage attempts: 2.0\nBest round: 1 attempt",
+            session.to_string()
+        );
+    }
+
+    #[test]
+    fn difficulties_parse_in_any_case() {
+        assert_eq!(Ok(Difficulty::Hard), "HARD".parse());
+        assert!("medium".parse::<Difficulty>().is_err());
+    }
+}
@@ -1,35 +1,99 @@
-use std::io;
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+use std::sync::mpsc;
+use std::{env, io, process, thread};
 // Bring the `io` input/output library into scope.
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 // The `Rng` trait defines methods that random number generators implement.
-use std::cmp::Ordering;
+use rand::rngs::StdRng;
+
+use guessing_game::input::{self, Command};
+use guessing_game::net::{self, Guesser, Message};
+use guessing_game::solver::{Answer, Solver, Step};
+use guessing_game::{Config, GameEngine, Hint, Network, Outcome, Session};
 
 fn main() {
-    println!("Guess the number!");
+    let config = Config::build(env::args().skip(1), |name| env::var(name).ok()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    });
+
+    println!("Guess the number! Type q to quit.");
+
+    match &config.network {
+        Some(Network::Host(addr)) => return host(&config, addr),
+        Some(Network::Join(addr)) => return join(addr),
+        None if config.computer => return let_the_computer_guess(&config),
+        None => {}
+    }
+
+    // The same seed always gives the same numbers. Without one, the generator is seeded from
+    // the operating system, which is different every time.
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    // Rounds go on until the player types `q`, and then they see how they did.
+    let mut session = Session::new();
+    while let Some(engine) = play_round(&config, &mut rng) {
+        session.record(&engine);
+        println!();
+        println!("New round! Type q to quit.");
+    }
+    println!("{}", session);
+}
 
+/// Plays one round, and returns the engine once it's over, or `None` if the player quit
+/// before that.
+fn play_round(config: &Config, rng: &mut StdRng) -> Option<GameEngine> {
     // This value also inferred to be a `u32` according to the comparison below.
-    let secret_number = rand::thread_rng().gen_range(1..101);
+    let secret_number = rng.gen_range(config.min..=config.max);
+
+    if config.debug {
+        println!("The secret number is: {}", secret_number);
+    }
 
-    println!("The secret number is: {}", secret_number);
+    // The engine knows the rules; `main` only talks to the player.
+    let mut engine = match config.max_attempts {
+        Some(max_attempts) => {
+            println!("It's between {} and {}, and you have {} guesses.", config.min, config.max, max_attempts);
+            GameEngine::with_max_attempts(secret_number, max_attempts)
+        }
+        None => GameEngine::new(secret_number),
+    };
 
     loop {
         println!("Please input your guess.");
 
         let mut guess = String::new();  // Bounded to a new, empty instance of a `String`.
 
-        io::stdin()
+        let bytes_read = io::stdin()
             .read_line(&mut guess) // Pass a mutable reference to `read_line`.
             .expect("Failed to read line");
 
+        // `read_line` returns how many bytes it read, which is only 0 at the end of the input,
+        // like when the player presses Ctrl-D. There won't be any more guesses, so that quits.
+        if bytes_read == 0 {
+            println!();
+            return None;
+        }
+
         // // The `trim` method on a `String` instance will eliminate any whitespace at the beginning and end.
         // // The `parse` method on a strings parses a string into some kind of number.
         // // The `parse` method returns a `Result` type, much as the `read_line` does.
         // let guess: u32 = guess.trim().parse().expect("Please type a number!");  // Shadowing!
 
         // *Handling Invalid Input*
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,  // `_` is a catchall value.
+        // Rather than skipping a line that isn't a guess, tell the player what was wrong with it.
+        // It doesn't count as an attempt.
+        let guess: u32 = match input::parse(&guess, config.min..=config.max) {
+            Ok(Command::Guess(num)) => num,
+            Ok(Command::Quit) => return None,
+            Err(err) => {
+                println!("{}", err);
+                continue;
+            }
         };
 
         // `read_line` here returns a `io::Result` value. An instance of `io::Result` has an `expect`
@@ -41,15 +105,163 @@ fn main() {
 
         println!("You guessed: {}", guess); // The `{}` is a placeholder.
 
-        // The `cmp` method compares two values and can be called on anything that can be compared.
-        // It takes a reference to whatever you want to compare with.
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
+        // `GameEngine::guess` uses the `cmp` method, which compares two values and can be called
+        // on anything that can be compared.
+        match engine.guess(guess) {
+            // In hint mode, the player only hears whether they're getting closer.
+            Outcome::TooSmall | Outcome::TooBig if config.hints => match engine.hint() {
+                Some(Hint::Warmer) => println!("Warmer!"),
+                Some(Hint::Colder) => println!("Colder!"),
+                Some(Hint::Same) => println!("Neither warmer nor colder."),
+                None => println!("Not it. Guess again to find out if you're getting warmer."),
+            },
+            Outcome::TooSmall => println!("Too small!"),
+            Outcome::TooBig => println!("Too big!"),
+            Outcome::Correct => {
+                println!("You win!");
+                println!("Attempts: {}", engine.attempts());
+                return Some(engine);
+            }
+        }
+
+        if engine.is_lost() {
+            println!("You're out of guesses. You lose! The secret number was {}.", secret_number);
+            return Some(engine);
+        }
+        if let Some(remaining) = engine.remaining_attempts() {
+            println!("Guesses left: {}", remaining);
+        }
+    }
+}
+
+/// Reads numbers from `range` until one is valid, or returns `None` if the player quit.
+fn read_number(prompt: &str, range: RangeInclusive<u32>) -> Option<u32> {
+    loop {
+        println!("{}", prompt);
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).expect("Failed to read line") == 0 {
+            println!();
+            return None;
+        }
+        match input::parse(&line, range.clone()) {
+            Ok(Command::Guess(num)) => return Some(num),
+            Ok(Command::Quit) => return None,
+            Err(err) => println!("{}", err),
+        }
+    }
+}
+
+/// Asks for the secret number, and answers another player's guesses over the network.
+fn host(config: &Config, addr: &str) {
+    let prompt = format!("Pick the secret number, between {} and {}.", config.min, config.max);
+    let Some(secret_number) = read_number(&prompt, config.min..=config.max) else {
+        return;
+    };
+
+    let listener = TcpListener::bind(addr).unwrap_or_else(|err| {
+        eprintln!("Couldn't listen on {}: {}", addr, err);
+        process::exit(1);
+    });
+    println!("Waiting for the other player on {}...", addr);
+    let (stream, peer) = listener.accept().unwrap_or_else(|err| {
+        eprintln!("No one could join: {}", err);
+        process::exit(1);
+    });
+    println!("{} joined.", peer);
+
+    let engine = match config.max_attempts {
+        Some(max_attempts) => GameEngine::with_max_attempts(secret_number, max_attempts),
+        None => GameEngine::new(secret_number),
+    };
+
+    // The game runs on a thread of its own, which sends each guess back down a channel as
+    // it comes in. The loop below ends when the thread is done and drops its end.
+    let (sender, receiver) = mpsc::channel();
+    let (min, max) = (config.min, config.max);
+    let game = thread::spawn(move || net::serve(stream, engine, min, max, sender));
+    for (guess, outcome) in receiver {
+        let verdict = match outcome {
+            Outcome::TooSmall => "too small",
+            Outcome::TooBig => "too big",
+            Outcome::Correct => "correct",
+        };
+        println!("They guessed {}: {}", guess, verdict);
+    }
+
+    match game.join().expect("the game's thread panicked") {
+        Ok(engine) if engine.is_won() => println!("They got it in {} attempts.", engine.attempts()),
+        Ok(_) => println!("They're out of guesses. You win!"),
+        Err(err) => eprintln!("The game was cut short: {}", err),
+    }
+}
+
+/// Guesses the number another player is hosting.
+fn join(addr: &str) {
+    let mut guesser = Guesser::join(addr).unwrap_or_else(|err| {
+        eprintln!("Couldn't join {}: {}", addr, err);
+        process::exit(1);
+    });
+    print!("Joined! It's between {} and {}", guesser.min, guesser.max);
+    match guesser.max_attempts {
+        Some(max_attempts) => println!(", and you have {} guesses.", max_attempts),
+        None => println!("."),
+    }
+
+    while let Some(guess) = read_number("Please input your guess.", guesser.min..=guesser.max) {
+        match guesser.guess(guess) {
+            Ok(Message::TooSmall) => println!("Too small!"),
+            Ok(Message::TooBig) => println!("Too big!"),
+            Ok(Message::Correct { attempts }) => {
                 println!("You win!");
-                break;
+                println!("Attempts: {}", attempts);
+                return;
+            }
+            Ok(Message::Lost { secret }) => {
+                println!("You're out of guesses. You lose! The secret number was {}.", secret);
+                return;
+            }
+            Ok(Message::Error(text)) => println!("The host says: {}", text),
+            Ok(other) => println!("The host said something odd: {}", other),
+            Err(err) => {
+                eprintln!("Lost the game: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// The player thinks of a number, and answers the computer's guesses.
+fn let_the_computer_guess(config: &Config) {
+    println!(
+        "Think of a number between {} and {}, and I'll guess it. After each guess, type h if \
+         your number is higher, l if it's lower, or c if I got it.",
+        config.min, config.max
+    );
+
+    let mut solver = Solver::new(config.min..=config.max);
+    loop {
+        println!("Is it {}?", solver.guess());
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).expect("Failed to read line") == 0 || line.trim() == "q" {
+            return;
+        }
+        let answer: Answer = match line.parse() {
+            Ok(answer) => answer,
+            Err(err) => {
+                println!("{}", err);
+                continue;
+            }
+        };
+
+        match solver.answer(answer) {
+            Ok(Step::Guess(_)) => {}
+            Ok(Step::Solved { attempts }) => {
+                println!("Got it in {} guesses!", attempts);
+                return;
             }
+            // The solver ignored the answer, so the same guess is asked again.
+            Err(err) => println!("{}", err),
         }
     }
 }
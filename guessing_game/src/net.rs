@@ -0,0 +1,366 @@
+//! Two players over the network: the host picks the secret number, and the guesser, on
+//! another computer, tries to guess it.
+//!
+//! They talk in lines of text over TCP. The host starts by saying what the range is, and
+//! then answers every guess:
+//!
+//! ```text
+//! host:    HELLO 1 100 8      (the range, and the guesses allowed, or - for no limit)
+//! guesser: GUESS 50
+//! host:    SMALL
+//! guesser: GUESS 75
+//! host:    BIG
+//! guesser: GUESS 60
+//! host:    CORRECT 3          (or LOST 60, with the secret, after the last guess)
+//! ```
+//!
+//! The host answers a line it doesn't understand with `ERROR` and what was wrong. Neither
+//! side waits forever: a read that takes longer than its timeout fails.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use crate::{GameEngine, Outcome};
+
+/// How long the host waits for the guesser to think of a guess.
+pub const GUESS_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long the guesser waits to connect, and for each answer, which the host's computer
+/// makes up right away.
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One line of the protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Hello {
+        min: u32,
+        max: u32,
+        max_attempts: Option<u32>,
+    },
+    Guess(u32),
+    TooSmall,
+    TooBig,
+    Correct {
+        attempts: u32,
+    },
+    Lost {
+        secret: u32,
+    },
+    Error(String),
+}
+
+/// The message as it's sent, without the newline.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Hello {
+                min,
+                max,
+                max_attempts: Some(max_attempts),
+            } => write!(f, "HELLO {} {} {}", min, max, max_attempts),
+            Message::Hello { min, max, .. } => write!(f, "HELLO {} {} -", min, max),
+            Message::Guess(n) => write!(f, "GUESS {}", n),
+            Message::TooSmall => write!(f, "SMALL"),
+            Message::TooBig => write!(f, "BIG"),
+            Message::Correct { attempts } => write!(f, "CORRECT {}", attempts),
+            Message::Lost { secret } => write!(f, "LOST {}", secret),
+            Message::Error(text) => write!(f, "ERROR {}", text),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Message, String> {
+        let line = line.trim_end();
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let numbers: Result<Vec<u32>, _> = rest.split_whitespace().map(str::parse).collect();
+        let message = match (word, numbers.as_deref()) {
+            ("HELLO", _) => match rest.split_whitespace().collect::<Vec<_>>()[..] {
+                [min, max, max_attempts] => Message::Hello {
+                    min: min.parse().map_err(|_| bad(line))?,
+                    max: max.parse().map_err(|_| bad(line))?,
+                    max_attempts: match max_attempts {
+                        "-" => None,
+                        n => Some(n.parse().map_err(|_| bad(line))?),
+                    },
+                },
+                _ => return Err(bad(line)),
+            },
+            ("GUESS", Ok([n])) => Message::Guess(*n),
+            ("SMALL", Ok([])) => Message::TooSmall,
+            ("BIG", Ok([])) => Message::TooBig,
+            ("CORRECT", Ok([attempts])) => Message::Correct {
+                attempts: *attempts,
+            },
+            ("LOST", Ok([secret])) => Message::Lost { secret: *secret },
+            ("ERROR", _) => Message::Error(rest.to_string()),
+            _ => return Err(bad(line)),
+        };
+        Ok(message)
+    }
+}
+
+fn bad(line: &str) -> String {
+    format!("{:?} isn't a message", line)
+}
+
+fn send(stream: &mut TcpStream, message: &Message) -> io::Result<()> {
+    writeln!(stream, "{}", message)?;
+    stream.flush()
+}
+
+/// Reads a line, turning the ways the other side can go quiet into errors that say so.
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    match reader.read_line(&mut line) {
+        Ok(0) => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the other player left",
+        )),
+        Ok(_) => Ok(line),
+        // A read timing out is `WouldBlock` on Unix, and `TimedOut` on Windows.
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the other player took too long",
+            ))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// The host's side of a game, with the guesser on the other end of `stream`. Every guess
+/// is also sent down `guesses`, so the host can watch, and the engine is returned once the
+/// game is over.
+///
+/// It blocks until then, so it's meant to run on a thread of its own.
+pub fn serve(
+    mut stream: TcpStream,
+    mut engine: GameEngine,
+    min: u32,
+    max: u32,
+    guesses: Sender<(u32, Outcome)>,
+) -> io::Result<GameEngine> {
+    stream.set_read_timeout(Some(GUESS_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let hello = Message::Hello {
+        min,
+        max,
+        max_attempts: engine.remaining_attempts(),
+    };
+    send(&mut stream, &hello)?;
+
+    while !engine.is_over() {
+        let line = read_line(&mut reader)?;
+        let reply = match line.parse() {
+            Ok(Message::Guess(n)) if (min..=max).contains(&n) => {
+                let outcome = engine.guess(n);
+                // The host not watching is no reason to stop the game.
+                let _ = guesses.send((n, outcome));
+                match outcome {
+                    Outcome::Correct => Message::Correct {
+                        attempts: engine.attempts(),
+                    },
+                    _ if engine.is_lost() => Message::Lost {
+                        secret: engine.secret(),
+                    },
+                    Outcome::TooSmall => Message::TooSmall,
+                    Outcome::TooBig => Message::TooBig,
+                }
+            }
+            Ok(Message::Guess(n)) => {
+                Message::Error(format!("{} isn't between {} and {}", n, min, max))
+            }
+            Ok(other) => Message::Error(format!("expected a guess, not {}", other)),
+            Err(e) => Message::Error(e),
+        };
+        send(&mut stream, &reply)?;
+    }
+    Ok(engine)
+}
+
+/// The guesser's side of a game.
+#[derive(Debug)]
+pub struct Guesser {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    pub min: u32,
+    pub max: u32,
+    pub max_attempts: Option<u32>,
+}
+
+impl Guesser {
+    /// Connects to the host at `addr`, and waits for it to say what the range is.
+    pub fn join(addr: impl ToSocketAddrs) -> io::Result<Guesser> {
+        let mut last_error = None;
+        let mut connected = None;
+        // A name like `localhost` can stand for a few addresses. The first that answers wins.
+        for addr in addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, REPLY_TIMEOUT) {
+                Ok(stream) => {
+                    connected = Some(stream);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let stream = match (connected, last_error) {
+            (Some(stream), _) => stream,
+            (None, Some(e)) => return Err(e),
+            (None, None) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the address didn't resolve to anything",
+                ))
+            }
+        };
+
+        stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        match receive(&mut reader)? {
+            Message::Hello {
+                min,
+                max,
+                max_attempts,
+            } => Ok(Guesser {
+                stream,
+                reader,
+                min,
+                max,
+                max_attempts,
+            }),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Sends a guess, and returns the host's answer.
+    pub fn guess(&mut self, n: u32) -> io::Result<Message> {
+        send(&mut self.stream, &Message::Guess(n))?;
+        receive(&mut self.reader)
+    }
+}
+
+fn receive(reader: &mut impl BufRead) -> io::Result<Message> {
+    read_line(reader)?
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn unexpected(message: Message) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("didn't expect {}", message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[test]
+    fn messages_read_back_the_way_they_were_written() {
+        let messages = [
+            Message::Hello {
+                min: 1,
+                max: 100,
+                max_attempts: Some(8),
+            },
+            Message::Hello {
+                min: 5,
+                max: 10,
+                max_attempts: None,
+            },
+            Message::Guess(42),
+            Message::TooSmall,
+            Message::TooBig,
+            Message::Correct { attempts: 3 },
+            Message::Lost { secret: 7 },
+            Message::Error("that's no guess".to_string()),
+        ];
+        for message in messages {
+            assert_eq!(Ok(message.clone()), message.to_string().parse());
+        }
+        assert!("GUESS".parse::<Message>().is_err());
+        assert!("GUESS x".parse::<Message>().is_err());
+        assert!("HELLO 1 2".parse::<Message>().is_err());
+        assert!("WHAT".parse::<Message>().is_err());
+    }
+
+    #[test]
+    fn a_game_over_the_loopback() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let host = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve(stream, GameEngine::with_max_attempts(60, 5), 1, 100, sender)
+        });
+
+        let mut guesser = Guesser::join(addr).unwrap();
+        assert_eq!(
+            (1, 100, Some(5)),
+            (guesser.min, guesser.max, guesser.max_attempts)
+        );
+        assert_eq!(Message::TooSmall, guesser.guess(50).unwrap());
+        assert!(matches!(guesser.guess(500).unwrap(), Message::Error(_)));
+        assert_eq!(Message::TooBig, guesser.guess(75).unwrap());
+        assert_eq!(Message::Correct { attempts: 3 }, guesser.guess(60).unwrap());
+
+        let engine = host.join().unwrap().unwrap();
+        assert!(engine.is_won());
+        let seen: Vec<(u32, Outcome)> = receiver.iter().collect();
+        assert_eq!(
+            vec![
+                (50, Outcome::TooSmall),
+                (75, Outcome::TooBig),
+                (60, Outcome::Correct)
+            ],
+            seen
+        );
+    }
+
+    #[test]
+    fn the_last_wrong_guess_loses() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, _receiver) = mpsc::channel();
+        let host = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve(stream, GameEngine::with_max_attempts(60, 1), 1, 100, sender)
+        });
+
+        let mut guesser = Guesser::join(addr).unwrap();
+        assert_eq!(Message::Lost { secret: 60 }, guesser.guess(1).unwrap());
+        assert!(host.join().unwrap().unwrap().is_lost());
+    }
+
+    #[test]
+    fn the_host_notices_the_guesser_leaving() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, _receiver) = mpsc::channel();
+        let host = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve(stream, GameEngine::new(60), 1, 100, sender)
+        });
+
+        drop(Guesser::join(addr).unwrap());
+        let error = host.join().unwrap().unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, error.kind());
+    }
+}
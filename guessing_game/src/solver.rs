@@ -0,0 +1,233 @@
+//! The game the other way around: the player thinks of a number, and the computer guesses
+//! it, told after each guess whether the number is higher or lower.
+//!
+//! Each answer rules out everything on one side of the guess, so guessing in the middle of
+//! what's left halves it every time. That's a binary search, and it finds any number from 1
+//! to 100 within 7 guesses.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// What the player says about a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// The number is higher than the guess.
+    Higher,
+    /// The number is lower.
+    Lower,
+    Correct,
+}
+
+/// `h`, `l`, or `c`, or the whole word.
+impl FromStr for Answer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Answer, String> {
+        match s.trim().to_lowercase().as_str() {
+            "h" | "higher" => Ok(Answer::Higher),
+            "l" | "lower" => Ok(Answer::Lower),
+            "c" | "correct" => Ok(Answer::Correct),
+            "" => Err("Type h if your number is higher, l if it's lower, or c.".to_string()),
+            other => Err(format!("{:?} isn't an answer. Type h, l, or c.", other)),
+        }
+    }
+}
+
+/// What the solver does after an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Guess(u32),
+    Solved { attempts: u32 },
+}
+
+/// An answer that contradicts the ones before it: there's no number left it could be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inconsistent {
+    /// The number is higher than this, by an earlier answer or this one.
+    pub above: Option<u32>,
+    /// The number is lower than this.
+    pub below: Option<u32>,
+    pub min: u32,
+    pub max: u32,
+}
+
+impl fmt::Display for Inconsistent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "That can't be right: ")?;
+        match (self.above, self.below) {
+            (Some(above), Some(below)) => write!(
+                f,
+                "you said it's higher than {} and lower than {}, and nothing is.",
+                above, below
+            ),
+            (Some(above), None) => write!(
+                f,
+                "you said it's higher than {}, but it's at most {}.",
+                above, self.max
+            ),
+            (None, Some(below)) => write!(
+                f,
+                "you said it's lower than {}, but it's at least {}.",
+                below, self.min
+            ),
+            (None, None) => write!(f, "there's no number it could be."),
+        }
+    }
+}
+
+impl std::error::Error for Inconsistent {}
+
+/// Guesses a number from a range, the way the player does in `GameEngine`'s game.
+#[derive(Debug)]
+pub struct Solver {
+    range: RangeInclusive<u32>,
+    /// The numbers it could still be are `low..=high`.
+    low: u32,
+    high: u32,
+    guess: u32,
+    attempts: u32,
+    /// The guesses the answers so far put the number between.
+    above: Option<u32>,
+    below: Option<u32>,
+}
+
+impl Solver {
+    /// # Panics
+    ///
+    /// If `range` is empty.
+    pub fn new(range: RangeInclusive<u32>) -> Solver {
+        let (low, high) = (*range.start(), *range.end());
+        assert!(low <= high, "the range {}..={} is empty", low, high);
+        Solver {
+            range,
+            low,
+            high,
+            guess: middle(low, high),
+            attempts: 1,
+            above: None,
+            below: None,
+        }
+    }
+
+    /// The guess waiting for an answer.
+    pub fn guess(&self) -> u32 {
+        self.guess
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Narrows down what the number could be, and makes the next guess. An answer that
+    /// leaves no number it could be is an error, and is ignored, so the player can answer
+    /// the same guess again.
+    pub fn answer(&mut self, answer: Answer) -> Result<Step, Inconsistent> {
+        let (low, high) = match answer {
+            Answer::Correct => {
+                return Ok(Step::Solved {
+                    attempts: self.attempts,
+                })
+            }
+            Answer::Higher => (self.guess.checked_add(1), Some(self.high)),
+            Answer::Lower => (Some(self.low), self.guess.checked_sub(1)),
+        };
+        match (low, high) {
+            (Some(low), Some(high)) if low <= high => {
+                match answer {
+                    Answer::Higher => self.above = Some(self.guess),
+                    _ => self.below = Some(self.guess),
+                }
+                self.low = low;
+                self.high = high;
+                self.guess = middle(low, high);
+                self.attempts += 1;
+                Ok(Step::Guess(self.guess))
+            }
+            _ => {
+                let (above, below) = match answer {
+                    Answer::Higher => (Some(self.guess), self.below),
+                    _ => (self.above, Some(self.guess)),
+                };
+                Err(Inconsistent {
+                    above,
+                    below,
+                    min: *self.range.start(),
+                    max: *self.range.end(),
+                })
+            }
+        }
+    }
+}
+
+/// Halfway from `low` to `high`, written so it can't overflow.
+fn middle(low: u32, high: u32) -> u32 {
+    low + (high - low) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays against a player thinking of `secret`, and returns how many guesses it took.
+    fn solve(range: RangeInclusive<u32>, secret: u32) -> u32 {
+        let mut solver = Solver::new(range);
+        loop {
+            let answer = match secret.cmp(&solver.guess()) {
+                std::cmp::Ordering::Greater => Answer::Higher,
+                std::cmp::Ordering::Less => Answer::Lower,
+                std::cmp::Ordering::Equal => Answer::Correct,
+            };
+            if let Step::Solved { attempts } = solver.answer(answer).unwrap() {
+                return attempts;
+            }
+        }
+    }
+
+    #[test]
+    fn finds_every_number_within_seven_guesses() {
+        for secret in 1..=100 {
+            assert!(solve(1..=100, secret) <= 7, "{} took too long", secret);
+        }
+        assert_eq!(1, solve(5..=5, 5));
+        assert!(solve(0..=u32::MAX, u32::MAX) <= 33);
+    }
+
+    #[test]
+    fn contradictions_are_caught_and_ignored() {
+        let mut solver = Solver::new(1..=100);
+        assert_eq!(50, solver.guess());
+        assert_eq!(Ok(Step::Guess(25)), solver.answer(Answer::Lower));
+        assert_eq!(Ok(Step::Guess(37)), solver.answer(Answer::Higher));
+        solver.answer(Answer::Higher).unwrap();
+        solver.answer(Answer::Higher).unwrap();
+        solver.answer(Answer::Higher).unwrap();
+        solver.answer(Answer::Higher).unwrap();
+        assert_eq!(49, solver.guess());
+
+        let error = solver.answer(Answer::Higher).unwrap_err();
+        assert_eq!((Some(49), Some(50)), (error.above, error.below));
+        assert_eq!(49, solver.guess());
+        assert_eq!(
+            Ok(Step::Solved { attempts: 7 }),
+            solver.answer(Answer::Correct)
+        );
+    }
+
+    #[test]
+    fn the_ends_of_the_range_are_contradictions_too() {
+        let mut solver = Solver::new(1..=1);
+        let error = solver.answer(Answer::Lower).unwrap_err();
+        assert_eq!(
+            "That can't be right: you said it's lower than 1, but it's at least 1.",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn answers_parse_as_letters_or_words() {
+        assert_eq!(Ok(Answer::Higher), "H\n".parse());
+        assert_eq!(Ok(Answer::Lower), "lower".parse());
+        assert!("x".parse::<Answer>().is_err());
+    }
+}
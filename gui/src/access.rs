@@ -0,0 +1,107 @@
+//! Describing a screen in words, for people who can't see it.
+//!
+//! A screen reader doesn't look at the cells on the terminal. It needs to know what each
+//! widget *is*: its role, like button or checkbox, what it's called, and its value, like
+//! whether the checkbox is checked. Widgets say that through the `role`,
+//! `accessible_label`, and `accessible_value` methods of `Draw`.
+//!
+//! `Screen::dump_accessibility_tree` puts all of that together as an indented outline of
+//! the screen, one line per component:
+//!
+//! ```text
+//! group
+//!   text "Sign up"
+//!   textbox "Your name" = Ferris (focused)
+//!   checkbox "Subscribe" = checked
+//!   button "Send" (disabled)
+//! ```
+//!
+//! It doesn't depend on where things are drawn, which also makes it a handy thing to
+//! compare in tests.
+
+use std::fmt;
+
+use crate::{Component, Renderer, Screen, WidgetId};
+
+/// What kind of thing a widget is. The names are the ones the web's ARIA standard uses,
+/// which is what most screen readers understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Something that only holds other components, like a `Row`.
+    Group,
+    /// Text that's only there to be read.
+    Text,
+    Button,
+    TextBox,
+    CheckBox,
+    Slider,
+    ProgressBar,
+    List,
+    /// A choice of options, like a select box.
+    ComboBox,
+    /// A widget that didn't say what it is.
+    Generic,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Role::Group => "group",
+            Role::Text => "text",
+            Role::Button => "button",
+            Role::TextBox => "textbox",
+            Role::CheckBox => "checkbox",
+            Role::Slider => "slider",
+            Role::ProgressBar => "progressbar",
+            Role::List => "list",
+            Role::ComboBox => "combobox",
+            Role::Generic => "generic",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl<R: Renderer> Screen<R> {
+    /// The screen as an outline, as described in the module documentation. Children are
+    /// indented under their parents. Hidden components are left out, like they are when
+    /// the screen is drawn.
+    pub fn dump_accessibility_tree(&self) -> String {
+        let mut outline = String::new();
+        dump(&self.root, 0, true, self.focused, &mut outline);
+        outline
+    }
+}
+
+fn dump(
+    component: &Component,
+    depth: usize,
+    parent_enabled: bool,
+    focused: Option<WidgetId>,
+    outline: &mut String,
+) {
+    if !component.visible() {
+        return;
+    }
+    let widget = &component.widget;
+    let enabled = component.enabled() && parent_enabled;
+
+    outline.push_str(&"  ".repeat(depth));
+    outline.push_str(&widget.role().to_string());
+    if let Some(label) = widget.accessible_label() {
+        outline.push_str(&format!(" {:?}", label));
+    }
+    if let Some(value) = widget.accessible_value() {
+        outline.push_str(&format!(" = {}", value));
+    }
+    if focused == Some(component.id()) {
+        outline.push_str(" (focused)");
+    }
+    if !enabled {
+        outline.push_str(" (disabled)");
+    }
+    outline.push('\n');
+
+    for child in widget.children() {
+        dump(child, depth + 1, enabled, focused, outline);
+    }
+}
@@ -0,0 +1,55 @@
+//! Widgets that change over time, like a progress bar moving or a cursor blinking.
+//!
+//! Before each frame is drawn, the screen *ticks*: it tells every animated widget how much
+//! time has passed since the last frame, and each one moves its animation on by that much.
+//! Going by the time that passed, instead of by a fixed step per frame, keeps animations at
+//! the same speed however fast the frames actually come.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub trait Animatable {
+    /// Moves the animation on by `delta`, the time since the last tick.
+    fn tick(&mut self, delta: Duration);
+}
+
+/// Paces frames to a number of frames per second.
+#[derive(Debug)]
+pub struct FrameClock {
+    frame: Duration,
+    last: Option<Instant>,
+}
+
+impl FrameClock {
+    /// # Panics
+    ///
+    /// If `fps` is 0.
+    pub fn new(fps: u32) -> FrameClock {
+        assert!(fps > 0, "a frame clock needs at least one frame per second");
+        FrameClock {
+            frame: Duration::from_secs(1) / fps,
+            last: None,
+        }
+    }
+
+    /// How long one frame is meant to take.
+    pub fn frame(&self) -> Duration {
+        self.frame
+    }
+
+    /// Waits until it's time for the next frame, and returns how long it's been since the
+    /// last one. The first frame is right away, with nothing before it, so that's zero.
+    pub fn next_frame(&mut self) -> Duration {
+        if let Some(last) = self.last {
+            let due = last + self.frame;
+            let now = Instant::now();
+            if now < due {
+                thread::sleep(due - now);
+            }
+        }
+        let now = Instant::now();
+        let delta = self.last.map_or(Duration::ZERO, |last| now - last);
+        self.last = Some(now);
+        delta
+    }
+}
@@ -0,0 +1,144 @@
+//! Keeping an application's state in one place, the way the Elm language does it.
+//!
+//! Changing widgets directly from listeners gets hard to follow once a few of them depend
+//! on the same data. An `App` splits that up:
+//!
+//! - the *model* is all of the application's state, in a type of its own;
+//! - widgets don't change the model, they *send messages* saying what happened;
+//! - `update` is the only thing that changes the model, one message at a time;
+//! - `view` builds a new screen from the model after every change.
+//!
+//! So a screen never has to be kept in step with the model by hand: it's always made from
+//! it.
+
+use std::cell::RefCell;
+use std::io::{self, Stdout};
+use std::rc::Rc;
+
+use crate::{Event, Renderer, Screen, TerminalRenderer, WidgetId};
+
+/// Where listeners send their messages. Cloning it gives another handle to the same queue,
+/// so every listener can have its own. The queue is shared, and changed from `Fn`
+/// listeners, so it's an `Rc<RefCell<_>>`.
+pub struct Messages<Msg> {
+    queue: Rc<RefCell<Vec<Msg>>>,
+}
+
+impl<Msg> Clone for Messages<Msg> {
+    fn clone(&self) -> Messages<Msg> {
+        Messages {
+            queue: Rc::clone(&self.queue),
+        }
+    }
+}
+
+impl<Msg: 'static> Messages<Msg> {
+    pub fn send(&self, msg: Msg) {
+        self.queue.borrow_mut().push(msg);
+    }
+
+    /// A listener that sends whatever message `f` makes of each event, if any.
+    pub fn listener(&self, f: impl Fn(&Event) -> Option<Msg> + 'static) -> impl FnMut(&Event) {
+        let messages = self.clone();
+        move |event| {
+            if let Some(msg) = f(event) {
+                messages.send(msg);
+            }
+        }
+    }
+
+    /// A listener that sends `msg` every time its component is clicked.
+    pub fn on_click(&self, msg: Msg) -> impl FnMut(&Event)
+    where
+        Msg: Clone,
+    {
+        self.listener(move |event| matches!(event, Event::Click { .. }).then(|| msg.clone()))
+    }
+
+    fn take(&self) -> Vec<Msg> {
+        self.queue.take()
+    }
+}
+
+/// `R` is the renderer of the screens `view` makes, see `Screen`.
+pub struct App<Model, Msg, R: Renderer = TerminalRenderer<Stdout>> {
+    model: Model,
+    update: fn(&mut Model, Msg),
+    view: fn(&Model, &Messages<Msg>) -> Screen<R>,
+    messages: Messages<Msg>,
+    screen: Screen<R>,
+}
+
+impl<Model, Msg: 'static, R: Renderer> App<Model, Msg, R> {
+    pub fn new(
+        model: Model,
+        update: fn(&mut Model, Msg),
+        view: fn(&Model, &Messages<Msg>) -> Screen<R>,
+    ) -> App<Model, Msg, R> {
+        let messages = Messages {
+            queue: Rc::new(RefCell::new(Vec::new())),
+        };
+        let screen = view(&model, &messages);
+        App {
+            model,
+            update,
+            view,
+            messages,
+            screen,
+        }
+    }
+
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+
+    pub fn screen(&self) -> &Screen<R> {
+        &self.screen
+    }
+
+    /// For changing what only the screen knows about, like which component has the focus.
+    pub fn screen_mut(&mut self) -> &mut Screen<R> {
+        &mut self.screen
+    }
+
+    /// Dispatches `event` to the screen, then runs `update` with every message that sent,
+    /// in order. If there were any, the screen is made again from the new model.
+    pub fn dispatch(&mut self, event: Event) {
+        self.screen.dispatch(event);
+
+        let messages = self.messages.take();
+        if messages.is_empty() {
+            return;
+        }
+        for msg in messages {
+            (self.update)(&mut self.model, msg);
+        }
+        self.rebuild();
+    }
+
+    pub fn run(&mut self) -> io::Result<()> {
+        self.screen.run()
+    }
+
+    /// The new screen's components are new, with new ids, so the focus is carried over by
+    /// position: the component that's where the focused one was gets the focus.
+    fn rebuild(&mut self) {
+        let position = self
+            .screen
+            .focused()
+            .and_then(|focused| ids(&self.screen).iter().position(|id| *id == focused));
+
+        self.screen = (self.view)(&self.model, &self.messages);
+
+        if let Some(id) = position.and_then(|position| ids(&self.screen).get(position).copied()) {
+            self.screen.focus(id);
+        }
+    }
+}
+
+/// The ids of all the components on `screen`, in the order of a walk.
+fn ids<R: Renderer>(screen: &Screen<R>) -> Vec<WidgetId> {
+    let mut ids = Vec::new();
+    screen.root.walk(&mut |component| ids.push(component.id()));
+    ids
+}
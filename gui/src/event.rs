@@ -0,0 +1,27 @@
+/// Something the user did. The `Screen` hands each event to the component that has the
+/// keyboard focus, or for a click, to the component that was clicked; see
+/// `Screen::dispatch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A click on the cell at column `x` of row `y` of the screen.
+    Click { x: u32, y: u32 },
+    /// A key was pressed while the component had the focus.
+    KeyPress(Key),
+    /// The component gained (`true`) or lost (`false`) the keyboard focus.
+    Focus(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    Enter,
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
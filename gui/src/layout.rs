@@ -0,0 +1,367 @@
+//! Containers that place other components.
+//!
+//! Every widget knows its own size. Laying out a screen means going from the top down and
+//! giving each component a rectangle of that size: a `Row` puts its children side by side,
+//! a `Column` one under the other, and `Padding` leaves a margin around its child. Hidden
+//! components don't take up any room in a row or column.
+//!
+//! A `ListView` is a column too tall for the screen: it shows a few rows of it at a time,
+//! and scrolls.
+
+use std::ops::Range;
+use std::slice;
+
+use rectangles::Rectangle;
+
+use crate::{Color, Component, Draw, Event, Key, Renderer, Role, Style};
+
+/// A position and size, in character cells. `x` grows to the right and `y` downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Whether the cell at `x`, `y` is in the rectangle. The check is `rectangles`'s, which
+    /// can't overflow however far right or down the rectangle is.
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        Rectangle {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+        .contains_point(x, y)
+    }
+}
+
+/// Children side by side, left to right, with `spacing` cells between them.
+pub struct Row {
+    pub children: Vec<Component>,
+    pub spacing: u32,
+}
+
+impl Row {
+    pub fn new(children: Vec<Component>) -> Row {
+        Row {
+            children,
+            spacing: 1,
+        }
+    }
+}
+
+impl Draw for Row {
+    fn size(&self) -> (u32, u32) {
+        let visible = self.children.iter().filter(|child| child.visible());
+        let gaps = visible.clone().count().saturating_sub(1) as u32 * self.spacing;
+        visible
+            .map(|child| child.widget.size())
+            .fold((gaps, 0), |(width, height), (w, h)| {
+                (width + w, height.max(h))
+            })
+    }
+
+    fn layout(&mut self, bounds: Rect) {
+        let mut x = bounds.x;
+        for child in self.children.iter_mut().filter(|child| child.visible()) {
+            let (width, height) = child.widget.size();
+            child.layout(Rect::new(x, bounds.y, width, height));
+            x += width + self.spacing;
+        }
+    }
+
+    /// Nothing of its own to draw: `Component::draw` draws the children.
+    fn draw(&self, _bounds: Rect, _renderer: &mut dyn Renderer) {}
+
+    fn children(&self) -> &[Component] {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut [Component] {
+        &mut self.children
+    }
+
+    fn child_list(&mut self) -> Option<&mut Vec<Component>> {
+        Some(&mut self.children)
+    }
+}
+
+/// Children one under the other, top to bottom, with `spacing` cells between them.
+pub struct Column {
+    pub children: Vec<Component>,
+    pub spacing: u32,
+}
+
+impl Column {
+    pub fn new(children: Vec<Component>) -> Column {
+        Column {
+            children,
+            spacing: 0,
+        }
+    }
+}
+
+impl Draw for Column {
+    fn size(&self) -> (u32, u32) {
+        let visible = self.children.iter().filter(|child| child.visible());
+        let gaps = visible.clone().count().saturating_sub(1) as u32 * self.spacing;
+        visible
+            .map(|child| child.widget.size())
+            .fold((0, gaps), |(width, height), (w, h)| {
+                (width.max(w), height + h)
+            })
+    }
+
+    fn layout(&mut self, bounds: Rect) {
+        let mut y = bounds.y;
+        for child in self.children.iter_mut().filter(|child| child.visible()) {
+            let (width, height) = child.widget.size();
+            child.layout(Rect::new(bounds.x, y, width, height));
+            y += height + self.spacing;
+        }
+    }
+
+    /// Nothing of its own to draw: `Component::draw` draws the children.
+    fn draw(&self, _bounds: Rect, _renderer: &mut dyn Renderer) {}
+
+    fn children(&self) -> &[Component] {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut [Component] {
+        &mut self.children
+    }
+
+    fn child_list(&mut self) -> Option<&mut Vec<Component>> {
+        Some(&mut self.children)
+    }
+}
+
+/// A margin of `amount` cells on every side of `child`.
+pub struct Padding {
+    pub child: Component,
+    pub amount: u32,
+}
+
+impl Padding {
+    pub fn new(amount: u32, child: Component) -> Padding {
+        Padding { child, amount }
+    }
+}
+
+impl Draw for Padding {
+    fn size(&self) -> (u32, u32) {
+        let (width, height) = self.child.widget.size();
+        (width + 2 * self.amount, height + 2 * self.amount)
+    }
+
+    fn layout(&mut self, bounds: Rect) {
+        let (width, height) = self.child.widget.size();
+        self.child.layout(Rect::new(
+            bounds.x + self.amount,
+            bounds.y + self.amount,
+            width,
+            height,
+        ));
+    }
+
+    fn draw(&self, _bounds: Rect, _renderer: &mut dyn Renderer) {}
+
+    fn children(&self) -> &[Component] {
+        slice::from_ref(&self.child)
+    }
+
+    fn children_mut(&mut self) -> &mut [Component] {
+        slice::from_mut(&mut self.child)
+    }
+}
+
+/// A border with a title, around `child`.
+pub struct Panel {
+    pub title: String,
+    pub child: Component,
+}
+
+impl Panel {
+    pub fn new(title: &str, child: Component) -> Panel {
+        Panel {
+            title: String::from(title),
+            child,
+        }
+    }
+}
+
+impl Draw for Panel {
+    /// Wide enough for the child or the title, whichever is wider.
+    fn size(&self) -> (u32, u32) {
+        let (width, height) = self.child.widget.size();
+        let title = self.title.chars().count() as u32 + 2;
+        (width.max(title) + 2, height + 2)
+    }
+
+    fn layout(&mut self, bounds: Rect) {
+        let (width, height) = self.child.widget.size();
+        self.child
+            .layout(Rect::new(bounds.x + 1, bounds.y + 1, width, height));
+    }
+
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        renderer.border(bounds, Style::default());
+        let title = format!(" {} ", self.title);
+        renderer.text(bounds.x + 1, bounds.y, &title, Style::default().bold());
+    }
+
+    fn role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessible_label(&self) -> Option<String> {
+        Some(self.title.clone())
+    }
+
+    fn children(&self) -> &[Component] {
+        slice::from_ref(&self.child)
+    }
+
+    fn children_mut(&mut self) -> &mut [Component] {
+        slice::from_mut(&mut self.child)
+    }
+}
+
+/// Items one per row, of which only `height` rows are shown at a time, with a scroll bar on
+/// the right. With the focus, the arrow keys scroll it one row, Page Up and Page Down a
+/// page, and Home and End to the top and the bottom.
+///
+/// Only the items in view are its children. So however many items there are, only those
+/// are laid out, drawn, and handed events, but the others can't be found by their ids
+/// either until they're scrolled into view.
+pub struct ListView {
+    pub items: Vec<Component>,
+    pub width: u32,
+    pub height: u32,
+    /// The index of the item on the top row.
+    offset: usize,
+}
+
+impl ListView {
+    pub fn new(width: u32, height: u32, items: Vec<Component>) -> ListView {
+        ListView {
+            items,
+            width,
+            height,
+            offset: 0,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset.min(self.max_offset())
+    }
+
+    /// Scrolls so the item at `offset` is on the top row, or as close to it as the list
+    /// can get without showing empty rows at the bottom.
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.offset = offset.min(self.max_offset());
+    }
+
+    /// Items can be added and removed at any time, so the offset is checked whenever it's
+    /// used, not only when it's set.
+    fn max_offset(&self) -> usize {
+        self.items.len().saturating_sub(self.height as usize)
+    }
+
+    /// The indexes of the items in view.
+    fn window(&self) -> Range<usize> {
+        let start = self.offset();
+        start..(start + self.height as usize).min(self.items.len())
+    }
+}
+
+impl Draw for ListView {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Every item gets one row, as wide as the list less the scroll bar.
+    fn layout(&mut self, bounds: Rect) {
+        let window = self.window();
+        let width = bounds.width.saturating_sub(1);
+        for (row, item) in self.items[window].iter_mut().enumerate() {
+            let (item_width, _) = item.widget.size();
+            let y = bounds.y + row as u32;
+            item.layout(Rect::new(bounds.x, y, item_width.min(width), 1));
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        let page = self.height.max(1) as usize;
+        match event {
+            Event::KeyPress(Key::Up) => self.scroll_to(self.offset().saturating_sub(1)),
+            Event::KeyPress(Key::Down) => self.scroll_to(self.offset() + 1),
+            Event::KeyPress(Key::PageUp) => self.scroll_to(self.offset().saturating_sub(page)),
+            Event::KeyPress(Key::PageDown) => self.scroll_to(self.offset() + page),
+            Event::KeyPress(Key::Home) => self.scroll_to(0),
+            Event::KeyPress(Key::End) => self.scroll_to(self.max_offset()),
+            _ => {}
+        }
+    }
+
+    /// The scroll bar is a track down the right edge, with a thumb as much of its length
+    /// as the items in view are of all of them, and as far down it as they are. When
+    /// everything fits, or there are no rows to put a track in, there's no scroll bar.
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        let (rows, total) = (bounds.height as usize, self.items.len());
+        if total <= rows || rows == 0 || bounds.width == 0 {
+            return;
+        }
+        let thumb = (rows * rows / total).max(1);
+        let top = self.offset() * (rows - thumb) / self.max_offset();
+        let x = bounds.x + bounds.width - 1;
+        for row in 0..rows {
+            let y = bounds.y + row as u32;
+            if (top..top + thumb).contains(&row) {
+                renderer.text(x, y, "█", Style::color(Color::Cyan));
+            } else {
+                renderer.text(x, y, "│", Style::color(Color::Gray));
+            }
+        }
+    }
+
+    fn role(&self) -> Role {
+        Role::List
+    }
+
+    /// Which items are in view, counting from 1, since only those are its children.
+    fn accessible_value(&self) -> Option<String> {
+        let window = self.window();
+        if window.is_empty() {
+            return Some(String::from("empty"));
+        }
+        let (first, last, total) = (window.start + 1, window.end, self.items.len());
+        Some(format!("items {} to {} of {}", first, last, total))
+    }
+
+    fn children(&self) -> &[Component] {
+        &self.items[self.window()]
+    }
+
+    fn children_mut(&mut self) -> &mut [Component] {
+        let window = self.window();
+        &mut self.items[window]
+    }
+
+    fn child_list(&mut self) -> Option<&mut Vec<Component>> {
+        Some(&mut self.items)
+    }
+}
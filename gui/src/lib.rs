@@ -1,31 +1,1331 @@
-pub trait Draw {
-    fn draw(&self);
+use std::any::Any;
+use std::io::{self, Stdout};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+pub mod access;
+pub mod animate;
+pub mod app;
+pub mod event;
+pub mod layout;
+pub mod loader;
+pub mod render;
+
+pub use access::Role;
+pub use animate::{Animatable, FrameClock};
+pub use event::{Event, Key};
+pub use layout::{Column, ListView, Padding, Panel, Rect, Row};
+pub use render::{Color, Dimmed, DrawOp, Renderer, Style, TerminalRenderer, TestRenderer};
+
+/// `Draw: Any` lets code that knows which concrete type a widget is get it back from a
+/// `Box<dyn Draw>`, see `Component::widget_mut`. Every type without borrowed data is `Any`,
+/// so this asks nothing more of widgets.
+pub trait Draw: Any {
+    /// How many cells wide and high the widget is.
+    fn size(&self) -> (u32, u32);
+
+    /// Places the widget at `bounds`. Containers override this to place their children;
+    /// other widgets have nothing to do, since they're drawn wherever they're told to.
+    fn layout(&mut self, _bounds: Rect) {}
+
+    /// Draws the widget at `bounds` with `renderer`.
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer);
+
+    /// Updates the widget's own state for `event`, like a checkbox toggling when it's
+    /// clicked. Widgets that don't react to anything can leave this out.
+    fn handle_event(&mut self, _event: &Event) {}
+
+    /// The components inside this one. Only containers have any.
+    fn children(&self) -> &[Component] {
+        &[]
+    }
+
+    fn children_mut(&mut self) -> &mut [Component] {
+        &mut []
+    }
+
+    /// Containers whose children can be added and removed return them here, for
+    /// `Screen::insert` and `Screen::remove`. Containers with a fixed number of children,
+    /// like `Padding`, don't.
+    fn child_list(&mut self) -> Option<&mut Vec<Component>> {
+        None
+    }
+
+    /// Whether a click at `x`, `y` lands on the widget, drawn at `bounds`. Widgets that
+    /// draw outside their bounds, like an open select box, override this to take clicks
+    /// there too.
+    fn covers(&self, bounds: Rect, x: u32, y: u32) -> bool {
+        bounds.contains(x, y)
+    }
+
+    /// Widgets that animate return themselves here, for `Screen::tick` to find them. A
+    /// method is needed because a `dyn Draw` can't be asked directly whether its type also
+    /// implements `Animatable`.
+    fn animatable(&mut self) -> Option<&mut dyn Animatable> {
+        None
+    }
+
+    /// Whether the widget wants to be drawn over the others for now, like a select box
+    /// while its options are showing. Raised widgets are on `OVERLAY` or higher.
+    fn raised(&self) -> bool {
+        false
+    }
+
+    /// What kind of thing the widget is, for screen readers; see the `access` module.
+    /// Unless they say otherwise, containers are groups.
+    fn role(&self) -> Role {
+        if self.children().is_empty() {
+            Role::Generic
+        } else {
+            Role::Group
+        }
+    }
+
+    /// What the widget is called, like the label of a button.
+    fn accessible_label(&self) -> Option<String> {
+        None
+    }
+
+    /// What the widget is set to, like whether a checkbox is checked.
+    fn accessible_value(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The layer raised widgets are moved to. Components are on layer 0 unless they're given
+/// another one with `Component::with_layer`.
+pub const OVERLAY: u32 = 1;
+
+/// Identifies a component for as long as the program runs. Every component gets a new one
+/// when it's made, so two components never share an id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WidgetId(u64);
+
+impl WidgetId {
+    fn next() -> WidgetId {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        WidgetId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
 }
 
-/// The vector is of type `Box<dyn Draw>`, which is a trait object; it's a stand-in for
-/// any type inside a `Box` that implements the `Draw` trait.
-pub struct Screen {
-    pub components: Vec<Box<dyn Draw>>,
+/// A listener is a closure called with every event its component gets. It's `FnMut` so it
+/// can change what it captured, like a click counter.
+pub type Listener = Box<dyn FnMut(&Event)>;
+
+/// A widget on a screen, together with the listeners the application registered on it.
+///
+/// `widget` is of type `Box<dyn Draw>`, which is a trait object; it's a stand-in for any
+/// type inside a `Box` that implements the `Draw` trait. When the widget is a container,
+/// its children are components too, so a screen is a tree of components.
+pub struct Component {
+    pub widget: Box<dyn Draw>,
+    id: WidgetId,
+    listeners: Vec<Listener>,
+    bounds: Rect,
+    layer: u32,
+    enabled: bool,
+    visible: bool,
 }
 
+impl Component {
+    pub fn new(widget: impl Draw + 'static) -> Component {
+        Component::from_box(Box::new(widget))
+    }
+
+    /// For a widget that's already boxed, like one whose type is only known at runtime.
+    pub fn from_box(widget: Box<dyn Draw>) -> Component {
+        Component {
+            widget,
+            id: WidgetId::next(),
+            listeners: Vec::new(),
+            bounds: Rect::default(),
+            layer: 0,
+            enabled: true,
+            visible: true,
+        }
+    }
+
+    /// Puts the component on `layer`. Higher layers are drawn over lower ones, and while
+    /// anything is on a layer above the focused component, the focus can't stay there; see
+    /// `Screen::dispatch`. A component's children are on its layer too, unless they're
+    /// given a higher one.
+    pub fn with_layer(mut self, layer: u32) -> Component {
+        self.layer = layer;
+        self
+    }
+
+    pub fn set_layer(&mut self, layer: u32) {
+        self.layer = layer;
+    }
+
+    /// The layer the component is on, not counting what it gets from its parent.
+    pub fn layer(&self) -> u32 {
+        if self.widget.raised() {
+            self.layer.max(OVERLAY)
+        } else {
+            self.layer
+        }
+    }
+
+    /// Disabled components are drawn dimmed and ignore events, so they can't get the focus
+    /// and clicks on them are dropped. Disabling a container disables everything inside it.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Hidden components take no room in a `Row` or `Column`, aren't drawn, and can't be
+    /// clicked or focused. Hiding a container hides everything inside it.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn id(&self) -> WidgetId {
+        self.id
+    }
+
+    /// The widget as a `T`, if that's what it is. `Any::downcast_mut` checks at runtime
+    /// whether the trait object holds a `T`; `as &mut dyn Any` turns the `dyn Draw` into
+    /// the `dyn Any` it's built on.
+    pub fn widget_mut<T: Draw>(&mut self) -> Option<&mut T> {
+        (self.widget.as_mut() as &mut dyn Any).downcast_mut::<T>()
+    }
+
+    /// Where the last layout pass put the component.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    pub fn layout(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+        self.widget.layout(bounds);
+    }
+
+    /// Draws the widget, without its children: the screen draws those, in layer order.
+    pub fn draw(&self, renderer: &mut dyn Renderer) {
+        self.widget.draw(self.bounds, renderer);
+    }
+
+    /// Registers `listener`, to be called after the widget has handled each event.
+    pub fn on_event(mut self, listener: impl FnMut(&Event) + 'static) -> Component {
+        self.listeners.push(Box::new(listener));
+        self
+    }
+
+    /// Hands `event` to the widget, then to the listeners, then the same way to each of the
+    /// children, so an event for a container reaches everything inside it. Clicks are the
+    /// exception: they're meant for the one component that was clicked, so they aren't
+    /// passed on. Disabled and hidden components ignore events, and so does what's in them.
+    pub fn handle_event(&mut self, event: &Event) {
+        if !self.enabled || !self.visible {
+            return;
+        }
+        self.widget.handle_event(event);
+        for listener in self.listeners.iter_mut() {
+            listener(event);
+        }
+        if let Event::Click { .. } = event {
+            return;
+        }
+        for child in self.widget.children_mut() {
+            child.handle_event(event);
+        }
+    }
+
+    /// Calls `f` with this component and everything inside it, parents before their
+    /// children.
+    pub fn walk(&self, f: &mut dyn FnMut(&Component)) {
+        f(self);
+        for child in self.widget.children() {
+            child.walk(f);
+        }
+    }
+
+    /// This component and everything inside it, each with the layer it ends up on, from the
+    /// bottom layer to the top. On the same layer, parents come before their children.
+    /// Hidden components, and everything inside them, are left out.
+    pub fn layered(&self) -> Vec<(u32, &Component)> {
+        self.layered_enabled()
+            .into_iter()
+            .map(|(layer, _, component)| (layer, component))
+            .collect()
+    }
+
+    /// Like `layered`, together with whether each component is enabled, which it isn't if
+    /// anything it's inside is disabled.
+    fn layered_enabled(&self) -> Vec<(u32, bool, &Component)> {
+        let mut layered = Vec::new();
+        self.collect_layers(0, true, &mut layered);
+        // `sort_by_key` is stable, so the order of a walk is kept within each layer.
+        layered.sort_by_key(|(layer, _, _)| *layer);
+        layered
+    }
+
+    fn collect_layers<'a>(
+        &'a self,
+        parent_layer: u32,
+        parent_enabled: bool,
+        layered: &mut Vec<(u32, bool, &'a Component)>,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let layer = self.layer().max(parent_layer);
+        let enabled = self.enabled && parent_enabled;
+        layered.push((layer, enabled, self));
+        for child in self.widget.children() {
+            child.collect_layers(layer, enabled, layered);
+        }
+    }
+
+    pub fn walk_mut(&mut self, f: &mut dyn FnMut(&mut Component)) {
+        f(self);
+        for child in self.widget.children_mut() {
+            child.walk_mut(f);
+        }
+    }
+
+    /// The number of components in the tree under this one, this one included.
+    pub fn len(&self) -> usize {
+        let mut count = 0;
+        self.walk(&mut |_| count += 1);
+        count
+    }
+
+    /// There's always at least this component, so a tree is never empty.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The component with `id`, if it's this one or in the tree under it.
+    pub fn find(&self, id: WidgetId) -> Option<&Component> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.widget.children().iter().find_map(|child| child.find(id))
+    }
+
+    pub fn find_mut(&mut self, id: WidgetId) -> Option<&mut Component> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.widget
+            .children_mut()
+            .iter_mut()
+            .find_map(|child| child.find_mut(id))
+    }
+
+    /// Takes the component with `id` out of the tree under this one.
+    fn remove(&mut self, id: WidgetId) -> Option<Component> {
+        if let Some(list) = self.widget.child_list() {
+            if let Some(position) = list.iter().position(|child| child.id == id) {
+                return Some(list.remove(position));
+            }
+        }
+        self.widget
+            .children_mut()
+            .iter_mut()
+            .find_map(|child| child.remove(id))
+    }
+}
+
+/// A tree of components, with `root` at the top. Each component is found by its id, so the
+/// application can change the tree after it's built.
+///
+/// The screen draws itself with a renderer of type `R`. Unless it's told otherwise, that's
+/// a `TerminalRenderer` printing to the standard output; tests use a `TestRenderer`
+/// instead, to check what was drawn.
+pub struct Screen<R: Renderer = TerminalRenderer<Stdout>> {
+    pub root: Component,
+    focused: Option<WidgetId>,
+    renderer: R,
+}
+
+/// The constructors make screens with the default renderer. A generic `new` would leave
+/// `Screen::new(components)` without a type for `R`, since Rust doesn't fall back to a
+/// type parameter's default when inferring types; `with_renderer` changes it afterwards.
 impl Screen {
-    pub fn run(&self) {
-        for component in self.components.iter() {
-            component.draw();
+    /// A screen with `components` one under the other in a `Column`, which is the root.
+    /// The first component, if any, has the focus.
+    pub fn new(components: Vec<Component>) -> Screen {
+        let first = components.first().map(Component::id);
+        let mut screen = Screen::with_root(Component::new(Column::new(components)));
+        if let Some(id) = first {
+            screen.focus(id);
+        }
+        screen
+    }
+
+    /// A screen with `root` at the top, and nothing focused.
+    pub fn with_root(root: Component) -> Screen {
+        Screen {
+            root,
+            focused: None,
+            renderer: TerminalRenderer::stdout(),
         }
     }
 }
 
+impl<R: Renderer> Screen<R> {
+    /// The same screen, drawn with `renderer` from now on.
+    pub fn with_renderer<S: Renderer>(self, renderer: S) -> Screen<S> {
+        Screen {
+            root: self.root,
+            focused: self.focused,
+            renderer,
+        }
+    }
+
+    pub fn renderer(&self) -> &R {
+        &self.renderer
+    }
+
+    pub fn renderer_mut(&mut self) -> &mut R {
+        &mut self.renderer
+    }
+
+    /// Moves every animation on the screen on by `delta`.
+    pub fn tick(&mut self, delta: Duration) {
+        self.root.walk_mut(&mut |component| {
+            if let Some(animated) = component.widget.animatable() {
+                animated.tick(delta);
+            }
+        });
+    }
+
+    /// Shows `frames` frames, paced by `clock`: before each one is drawn, the animations
+    /// move on by the time since the one before.
+    pub fn animate(&mut self, clock: &mut FrameClock, frames: u32) -> io::Result<()> {
+        for _ in 0..frames {
+            let delta = clock.next_frame();
+            self.tick(delta);
+            self.run()?;
+        }
+        Ok(())
+    }
+
+    /// Lays the components out, draws them, and shows the result. Disabled components are
+    /// drawn through `Dimmed`.
+    pub fn run(&mut self) -> io::Result<()> {
+        self.layout();
+        for (_, enabled, component) in self.root.layered_enabled() {
+            if enabled {
+                component.draw(&mut self.renderer);
+            } else {
+                component.draw(&mut Dimmed(&mut self.renderer));
+            }
+        }
+        self.renderer.present()
+    }
+
+    /// Places the root in the top left corner, at its own size. The containers in the tree
+    /// then place their children.
+    pub fn layout(&mut self) {
+        let (width, height) = self.root.widget.size();
+        self.root.layout(Rect::new(0, 0, width, height));
+    }
+
+    pub fn get(&self, id: WidgetId) -> Option<&Component> {
+        self.root.find(id)
+    }
+
+    pub fn get_mut(&mut self, id: WidgetId) -> Option<&mut Component> {
+        self.root.find_mut(id)
+    }
+
+    /// Adds `component` as the last child of the container with id `parent`, and returns
+    /// the new child's id. If there's no such container, or its children can't be added to,
+    /// `component` is given back.
+    pub fn insert(&mut self, parent: WidgetId, component: Component) -> Result<WidgetId, Component> {
+        let list = self
+            .get_mut(parent)
+            .and_then(|parent| parent.widget.child_list());
+        match list {
+            Some(list) => {
+                let id = component.id;
+                list.push(component);
+                Ok(id)
+            }
+            None => Err(component),
+        }
+    }
+
+    /// Takes the component with `id`, and everything inside it, off the screen. The root
+    /// can't be removed. If the focus was in what's removed, nothing has it any more.
+    pub fn remove(&mut self, id: WidgetId) -> Option<Component> {
+        let removed = self.root.remove(id)?;
+        if let Some(focused) = self.focused {
+            if removed.find(focused).is_some() {
+                self.focused = None;
+            }
+        }
+        Some(removed)
+    }
+
+    pub fn focused(&self) -> Option<WidgetId> {
+        self.focused
+    }
+
+    /// Moves the focus to the component with `id`, telling the one that had it that it
+    /// lost it. Focusing the component that has the focus, or one that's disabled or
+    /// hidden, does nothing. While something is modal (see `dispatch`), the focus can't
+    /// leave the top layer, and focusing a component below it does nothing.
+    ///
+    /// # Panics
+    ///
+    /// If there's no component with `id` on the screen.
+    pub fn focus(&mut self, id: WidgetId) {
+        assert!(self.get(id).is_some(), "no component with {:?}", id);
+        if self.focused == Some(id) || !self.usable(id) {
+            return;
+        }
+        let top = self.top_layer();
+        let focused_layer = self.focused.and_then(|focused| self.layer_of(focused));
+        if self.layer_of(id) < Some(top) && focused_layer == Some(top) {
+            return;
+        }
+        if let Some(previous) = self.focused.and_then(|previous| self.get_mut(previous)) {
+            previous.handle_event(&Event::Focus(false));
+        }
+        self.focused = Some(id);
+        self.component_mut(id).handle_event(&Event::Focus(true));
+    }
+
+    /// Hands `event` to the focused component, which passes it on to its children. With
+    /// nothing focused, or when the focused component is scrolled out of a `ListView`, the
+    /// event is dropped.
+    ///
+    /// Something on a layer above the focused component, like a dialog, is *modal*: the
+    /// rest of the screen can't be used until it's gone. So first the focus moves to the
+    /// first component on the top layer, if it isn't there already.
+    ///
+    /// A click then moves the focus to the component under it, see `hit_test`, and goes
+    /// to that component only. Clicks on nothing, or on something a modal component is
+    /// keeping the focus from, are dropped.
+    pub fn dispatch(&mut self, event: Event) {
+        if let Some(id) = self.modal_focus() {
+            self.focus(id);
+        }
+
+        if let Event::Click { x, y } = event {
+            self.layout();
+            let Some(id) = self.hit_test(x, y) else {
+                return;
+            };
+            self.focus(id);
+            if self.focused == Some(id) {
+                self.component_mut(id).handle_event(&event);
+            }
+            return;
+        }
+
+        if let Some(focused) = self.focused.and_then(|focused| self.get_mut(focused)) {
+            focused.handle_event(&event);
+        }
+    }
+
+    /// The component at `x`, `y`: of the components there, the one drawn last, so the one
+    /// on top. That's a child rather than its parent, and something on a higher layer
+    /// rather than what's under it. Positions are those of the last layout.
+    pub fn hit_test(&self, x: u32, y: u32) -> Option<WidgetId> {
+        self.root
+            .layered()
+            .into_iter()
+            .rev()
+            .find(|(_, component)| component.widget.covers(component.bounds, x, y))
+            .map(|(_, component)| component.id)
+    }
+
+    /// Where the focus has to go before an event is dispatched, if it can't stay where it is.
+    fn modal_focus(&self) -> Option<WidgetId> {
+        let top = self.top_layer();
+        let focused_layer = self.focused.and_then(|focused| self.layer_of(focused));
+        if top == 0 || focused_layer == Some(top) {
+            return None;
+        }
+        self.root
+            .layered_enabled()
+            .into_iter()
+            .find(|(layer, enabled, _)| *layer == top && *enabled)
+            .map(|(_, _, component)| component.id)
+    }
+
+    /// Whether the component with `id` can get the focus: it's visible and enabled, and so
+    /// is everything it's inside.
+    fn usable(&self, id: WidgetId) -> bool {
+        self.root
+            .layered_enabled()
+            .into_iter()
+            .any(|(_, enabled, component)| component.id == id && enabled)
+    }
+
+    fn top_layer(&self) -> u32 {
+        self.root.layered().last().map_or(0, |(layer, _)| *layer)
+    }
+
+    /// The layer the component with `id` ends up on, counting what it gets from its parents.
+    fn layer_of(&self, id: WidgetId) -> Option<u32> {
+        self.root
+            .layered()
+            .into_iter()
+            .find(|(_, component)| component.id == id)
+            .map(|(layer, _)| layer)
+    }
+
+    fn component_mut(&mut self, id: WidgetId) -> &mut Component {
+        self.get_mut(id)
+            .unwrap_or_else(|| panic!("no component with {:?}", id))
+    }
+}
+
 pub struct Button {
     pub width: u32,
     pub height: u32,
     pub label: String,
 }
 
+/// A button at least three rows high gets a border, with the label in the middle. Lower ones
+/// have no room for that, so they're drawn in brackets.
 impl Draw for Button {
-    fn draw(&self) {
-        // code to actually draw a button
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        let style = Style::default().bold();
+        if bounds.height < 3 {
+            let text = clip(&format!("[ {} ]", self.label), bounds.width);
+            renderer.text(bounds.x, bounds.y, &text, style);
+            return;
+        }
+        renderer.border(bounds, Style::default());
+        let label = clip(&self.label, bounds.width - 2);
+        let left = (bounds.width - 2 - label.chars().count() as u32) / 2;
+        renderer.text(bounds.x + 1 + left, bounds.y + bounds.height / 2, &label, style);
+    }
+
+    fn role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessible_label(&self) -> Option<String> {
+        Some(self.label.clone())
+    }
+}
+
+/// The first characters of `text` that fit in `width` cells.
+fn clip(text: &str, width: u32) -> String {
+    text.chars().take(width as usize).collect()
+}
+
+/// A single line of text the user can type into, in a border. `cursor` is the character
+/// index new text is inserted at.
+///
+/// While it has the focus, the cursor blinks, which makes it `Animatable`.
+pub struct TextField {
+    pub width: u32,
+    pub height: u32,
+    pub text: String,
+    pub placeholder: String,
+    pub cursor: usize,
+    focused: bool,
+    /// How far into a blink the cursor is, from zero to twice `BLINK`.
+    blink: Duration,
+}
+
+/// How long the cursor is shown, and then hidden, when it blinks.
+const BLINK: Duration = Duration::from_millis(500);
+
+impl TextField {
+    pub fn new(width: u32, placeholder: &str) -> TextField {
+        TextField {
+            width,
+            height: 3,
+            text: String::new(),
+            placeholder: String::from(placeholder),
+            cursor: 0,
+            focused: false,
+            blink: Duration::ZERO,
+        }
+    }
+
+    fn cursor_shown(&self) -> bool {
+        self.focused && self.blink < BLINK
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_offset(self.cursor);
+        self.text.insert(at, c);
+        self.cursor += 1;
+    }
+
+    /// Deletes the character before the cursor, like the Backspace key.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let at = self.byte_offset(self.cursor);
+            self.text.remove(at);
+        }
+    }
+
+    /// `cursor` counts characters, but a `String` is indexed by bytes, and a character can
+    /// take more than one byte.
+    fn byte_offset(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(offset, _)| offset)
+    }
+}
+
+impl Draw for TextField {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Every key press starts the blink over, so the cursor is always showing while typing.
+    fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::KeyPress(Key::Char(c)) => self.insert(*c),
+            Event::KeyPress(Key::Backspace) => self.backspace(),
+            Event::KeyPress(Key::Left) => self.cursor = self.cursor.saturating_sub(1),
+            Event::KeyPress(Key::Right) => {
+                self.cursor = (self.cursor + 1).min(self.text.chars().count())
+            }
+            Event::Focus(focused) => self.focused = *focused,
+            _ => {}
+        }
+        if let Event::KeyPress(_) | Event::Focus(_) = event {
+            self.blink = Duration::ZERO;
+        }
+    }
+
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        renderer.border(bounds, Style::default());
+        let inside = bounds.width.saturating_sub(2);
+        if self.text.is_empty() {
+            let placeholder = clip(&self.placeholder, inside);
+            renderer.text(bounds.x + 1, bounds.y + 1, &placeholder, Style::color(Color::Gray));
+        } else {
+            renderer.text(bounds.x + 1, bounds.y + 1, &clip(&self.text, inside), Style::default());
+        }
+
+        if self.cursor_shown() && (self.cursor as u32) < inside {
+            let under = self.text.chars().nth(self.cursor).unwrap_or(' ');
+            let x = bounds.x + 1 + self.cursor as u32;
+            renderer.text(x, bounds.y + 1, &under.to_string(), Style::default().reverse());
+        }
+    }
+
+    fn animatable(&mut self) -> Option<&mut dyn Animatable> {
+        Some(self)
+    }
+
+    fn role(&self) -> Role {
+        Role::TextBox
+    }
+
+    /// The placeholder says what goes in the field, so it's the label.
+    fn accessible_label(&self) -> Option<String> {
+        Some(self.placeholder.clone())
+    }
+
+    fn accessible_value(&self) -> Option<String> {
+        Some(self.text.clone())
+    }
+}
+
+impl Animatable for TextField {
+    fn tick(&mut self, delta: Duration) {
+        let period = BLINK * 2;
+        self.blink = Duration::from_nanos(
+            ((self.blink + delta).as_nanos() % period.as_nanos()) as u64,
+        );
+    }
+}
+
+pub struct Checkbox {
+    pub width: u32,
+    pub height: u32,
+    pub label: String,
+    pub checked: bool,
+}
+
+impl Checkbox {
+    pub fn new(label: &str, checked: bool) -> Checkbox {
+        Checkbox {
+            width: label.chars().count() as u32 + 4,
+            height: 1,
+            label: String::from(label),
+            checked,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.checked = !self.checked;
+    }
+}
+
+impl Draw for Checkbox {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if let Event::Click { .. } | Event::KeyPress(Key::Char(' ')) = event {
+            self.toggle();
+        }
+    }
+
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        let mark = if self.checked { "x" } else { " " };
+        renderer.text(bounds.x, bounds.y, "[ ] ", Style::default());
+        renderer.text(bounds.x + 1, bounds.y, mark, Style::color(Color::Green).bold());
+        let label = clip(&self.label, bounds.width.saturating_sub(4));
+        renderer.text(bounds.x + 4, bounds.y, &label, Style::default());
+    }
+
+    fn role(&self) -> Role {
+        Role::CheckBox
+    }
+
+    fn accessible_label(&self) -> Option<String> {
+        Some(self.label.clone())
+    }
+
+    fn accessible_value(&self) -> Option<String> {
+        let value = if self.checked { "checked" } else { "unchecked" };
+        Some(String::from(value))
+    }
+}
+
+/// Text that's only there to be read.
+pub struct Label {
+    pub width: u32,
+    pub height: u32,
+    pub text: String,
+}
+
+impl Label {
+    pub fn new(text: &str) -> Label {
+        Label {
+            width: text.chars().count() as u32,
+            height: 1,
+            text: String::from(text),
+        }
+    }
+}
+
+impl Draw for Label {
+    /// At least as wide as the text, which may have changed since the label was made.
+    fn size(&self) -> (u32, u32) {
+        (self.width.max(self.text.chars().count() as u32), self.height)
+    }
+
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        renderer.text(bounds.x, bounds.y, &clip(&self.text, bounds.width), Style::default());
+    }
+
+    fn role(&self) -> Role {
+        Role::Text
+    }
+
+    fn accessible_label(&self) -> Option<String> {
+        Some(self.text.clone())
+    }
+}
+
+/// Picks a whole number from `min` to `max`, inclusive.
+pub struct Slider {
+    pub width: u32,
+    pub height: u32,
+    pub min: i32,
+    pub max: i32,
+    value: i32,
+}
+
+impl Slider {
+    pub fn new(width: u32, min: i32, max: i32) -> Slider {
+        assert!(min <= max, "Slider min {} is greater than max {}", min, max);
+        Slider {
+            width,
+            height: 1,
+            min,
+            max,
+            value: min,
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Values outside the slider's range are moved to its nearest end.
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    /// How far along the slider the value is, from 0.0 at `min` to 1.0 at `max`.
+    pub fn fraction(&self) -> f64 {
+        if self.min == self.max {
+            0.0
+        } else {
+            // In `f64`, since `max - min` doesn't fit in an `i32` for the widest ranges.
+            (self.value as f64 - self.min as f64) / (self.max as f64 - self.min as f64)
+        }
     }
 }
 
+impl Draw for Slider {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::KeyPress(Key::Left) => self.set_value(self.value.saturating_sub(1)),
+            Event::KeyPress(Key::Right) => self.set_value(self.value.saturating_add(1)),
+            _ => {}
+        }
+    }
 
+    /// A track as wide as the slider, with a knob where the value is.
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        if bounds.width == 0 {
+            return;
+        }
+        let track: String = (0..bounds.width).map(|_| '─').collect();
+        renderer.text(bounds.x, bounds.y, &track, Style::color(Color::Gray));
+        let knob = (self.fraction() * (bounds.width - 1) as f64).round() as u32;
+        renderer.text(bounds.x + knob, bounds.y, "●", Style::color(Color::Cyan).bold());
+    }
+
+    fn role(&self) -> Role {
+        Role::Slider
+    }
+
+    fn accessible_value(&self) -> Option<String> {
+        Some(format!("{} of {} to {}", self.value, self.min, self.max))
+    }
+}
+
+/// Shows how far along something is, from 0.0 to 1.0. When the progress changes, the bar
+/// doesn't jump there but fills up to it over time, at `speed` of its length per second.
+pub struct ProgressBar {
+    pub width: u32,
+    pub height: u32,
+    pub speed: f64,
+    shown: f64,
+    progress: f64,
+}
+
+impl ProgressBar {
+    pub fn new(width: u32) -> ProgressBar {
+        ProgressBar {
+            width,
+            height: 1,
+            speed: 0.5,
+            shown: 0.0,
+            progress: 0.0,
+        }
+    }
+
+    pub fn progress(&self) -> f64 {
+        self.progress
+    }
+
+    /// Values outside 0.0 to 1.0 are moved to the nearest end.
+    pub fn set_progress(&mut self, progress: f64) {
+        self.progress = progress.clamp(0.0, 1.0);
+    }
+
+    /// How full the bar is drawn right now, which trails `progress` while it animates.
+    pub fn shown(&self) -> f64 {
+        self.shown
+    }
+}
+
+impl Draw for ProgressBar {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        let filled = (self.shown * bounds.width as f64).round() as u32;
+        let bar: String = (0..bounds.width)
+            .map(|i| if i < filled { '█' } else { '░' })
+            .collect();
+        renderer.text(bounds.x, bounds.y, &bar, Style::color(Color::Green));
+    }
+
+    fn animatable(&mut self) -> Option<&mut dyn Animatable> {
+        Some(self)
+    }
+
+    fn role(&self) -> Role {
+        Role::ProgressBar
+    }
+
+    /// The progress it's heading to, not what's shown while it gets there.
+    fn accessible_value(&self) -> Option<String> {
+        Some(format!("{}%", (self.progress * 100.0).round()))
+    }
+}
+
+impl Animatable for ProgressBar {
+    fn tick(&mut self, delta: Duration) {
+        let step = self.speed * delta.as_secs_f64();
+        self.shown = if self.shown < self.progress {
+            (self.shown + step).min(self.progress)
+        } else {
+            (self.shown - step).max(self.progress)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn texts_at(screen: &Screen<TestRenderer>) -> Vec<(u32, u32, &str)> {
+        screen
+            .renderer()
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                DrawOp::Text { x, y, text, .. } => Some((*x, *y, text.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn components_are_drawn_where_the_layout_puts_them() {
+        let mut screen = Screen::new(vec![
+            Component::new(Label::new("Name")),
+            Component::new(Row::new(vec![
+                Component::new(Label::new("a")),
+                Component::new(Label::new("b")),
+            ])),
+        ])
+        .with_renderer(TestRenderer::new());
+        screen.run().unwrap();
+
+        assert_eq!(vec![(0, 0, "Name"), (0, 1, "a"), (2, 1, "b")], texts_at(&screen));
+        assert_eq!(Some(&DrawOp::Present), screen.renderer().ops.last());
+    }
+
+    #[test]
+    fn clicking_a_checkbox_checks_it() {
+        let mut screen = Screen::new(vec![Component::new(Checkbox::new("Subscribe", false))])
+            .with_renderer(TestRenderer::new());
+        screen.dispatch(Event::Click { x: 5, y: 0 });
+        screen.run().unwrap();
+
+        assert_eq!(vec!["[ ] ", "x", "Subscribe"], screen.renderer().texts());
+    }
+
+    #[test]
+    fn clicks_go_to_the_component_under_them() {
+        let clicks = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&clicks);
+        let label = Component::new(Label::new("Name"));
+        let button = Component::new(Button {
+            width: 6,
+            height: 1,
+            label: String::from("Go"),
+        })
+        .on_event(move |event| {
+            if let Event::Click { .. } = event {
+                counter.set(counter.get() + 1);
+            }
+        });
+        let (label_id, button_id) = (label.id(), button.id());
+        let mut screen = Screen::new(vec![label, button]).with_renderer(TestRenderer::new());
+        screen.layout();
+
+        assert_eq!(Some(label_id), screen.hit_test(3, 0));
+        assert_eq!(Some(button_id), screen.hit_test(5, 1));
+        // Next to the label, but still in the column around it.
+        assert_eq!(Some(screen.root.id()), screen.hit_test(5, 0));
+        assert_eq!(None, screen.hit_test(0, 2));
+
+        screen.dispatch(Event::Click { x: 1, y: 1 });
+        assert_eq!(Some(button_id), screen.focused());
+        assert_eq!(1, clicks.get());
+        // A click on the column isn't passed on to the button in it.
+        screen.dispatch(Event::Click { x: 5, y: 0 });
+        assert_eq!(1, clicks.get());
+    }
+
+    #[test]
+    fn hidden_components_take_no_room_and_disabled_ones_ignore_clicks() {
+        let mut hint = Component::new(Label::new("Optional"));
+        hint.set_visible(false);
+        let mut subscribe = Component::new(Checkbox::new("Subscribe", false));
+        subscribe.set_enabled(false);
+        let subscribe_id = subscribe.id();
+        let mut screen = Screen::new(vec![hint, subscribe]).with_renderer(TestRenderer::new());
+
+        screen.dispatch(Event::Click { x: 5, y: 0 });
+        assert_eq!(None, screen.focused());
+        screen.run().unwrap();
+        assert_eq!(vec![(0, 0, "[ ] "), (1, 0, " "), (4, 0, "Subscribe")], texts_at(&screen));
+        let dimmed = screen.renderer().ops.iter().all(|op| match op {
+            DrawOp::Text { style, .. } => style.dim,
+            _ => true,
+        });
+        assert!(dimmed);
+
+        screen.get_mut(subscribe_id).unwrap().set_enabled(true);
+        screen.dispatch(Event::Click { x: 5, y: 0 });
+        assert_eq!(Some(subscribe_id), screen.focused());
+    }
+
+    #[test]
+    fn a_list_view_only_shows_the_items_in_view() {
+        let items = (0..100)
+            .map(|i| Component::new(Label::new(&format!("Item {}", i))))
+            .collect();
+        let mut screen = Screen::new(vec![Component::new(ListView::new(12, 3, items))])
+            .with_renderer(TestRenderer::new());
+        screen.run().unwrap();
+        let drawn = texts_at(&screen);
+        // The list draws its scroll bar, then the items draw themselves. Three rows of a
+        // hundred items make a one-row thumb, at the top.
+        assert_eq!((11, 0, "█"), drawn[0]);
+        assert_eq!(&[(0, 0, "Item 0"), (0, 1, "Item 1"), (0, 2, "Item 2")], &drawn[3..]);
+        // The root, the list, and the three items in view.
+        assert_eq!(5, screen.root.len());
+
+        screen.dispatch(Event::KeyPress(Key::PageDown));
+        screen.dispatch(Event::KeyPress(Key::Down));
+        screen.renderer_mut().ops.clear();
+        screen.run().unwrap();
+        assert_eq!((0, 0, "Item 4"), texts_at(&screen)[3]);
+
+        screen.dispatch(Event::KeyPress(Key::End));
+        screen.renderer_mut().ops.clear();
+        screen.run().unwrap();
+        assert_eq!((11, 2, "█"), texts_at(&screen)[2]);
+        assert_eq!((0, 0, "Item 97"), texts_at(&screen)[3]);
+    }
+
+    #[test]
+    fn rects_at_the_edge_of_the_range_hold_points() {
+        let rect = Rect::new(u32::MAX - 2, 0, 10, 1);
+
+        assert!(rect.contains(u32::MAX - 2, 0));
+        assert!(rect.contains(u32::MAX, 0));
+        assert!(!rect.contains(u32::MAX - 3, 0));
+        assert!(!rect.contains(u32::MAX, 1));
+    }
+
+    #[test]
+    fn sliders_can_cover_every_i32() {
+        let mut slider = Slider::new(10, i32::MIN, i32::MAX);
+        assert_eq!(0.0, slider.fraction());
+        slider.set_value(i32::MAX);
+        assert_eq!(1.0, slider.fraction());
+        slider.set_value(0);
+        assert!((slider.fraction() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_list_view_with_no_rows_draws_nothing() {
+        let items = vec![Component::new(Label::new("Item"))];
+        let mut screen = Screen::new(vec![Component::new(ListView::new(12, 0, items))])
+            .with_renderer(TestRenderer::new());
+        screen.run().unwrap();
+
+        assert!(screen.renderer().texts().is_empty());
+    }
+
+    #[test]
+    fn the_accessibility_tree_outlines_the_screen() {
+        let mut name = TextField::new(20, "Your name");
+        "Ferris".chars().for_each(|c| name.insert(c));
+        let mut send = Component::new(Button {
+            width: 10,
+            height: 1,
+            label: String::from("Send"),
+        });
+        send.set_enabled(false);
+        let mut hint = Component::new(Label::new("Optional"));
+        hint.set_visible(false);
+        let screen = Screen::new(vec![
+            Component::new(Label::new("Sign up")),
+            Component::new(name),
+            Component::new(Panel::new(
+                "More",
+                Component::new(Row::new(vec![
+                    Component::new(Checkbox::new("Subscribe", true)),
+                    hint,
+                ])),
+            )),
+            send,
+        ]);
+
+        let expected = "\
+group
+  text \"Sign up\" (focused)
+  textbox \"Your name\" = Ferris
+  group \"More\"
+    group
+      checkbox \"Subscribe\" = checked
+  button \"Send\" (disabled)
+";
+        assert_eq!(expected, screen.dump_accessibility_tree());
+    }
+
+    #[test]
+    fn higher_layers_are_drawn_last() {
+        let mut screen = Screen::new(vec![
+            Component::new(Label::new("dialog")).with_layer(OVERLAY),
+            Component::new(Label::new("page")),
+        ])
+        .with_renderer(TestRenderer::new());
+        screen.run().unwrap();
+
+        assert_eq!(vec!["page", "dialog"], screen.renderer().texts());
+    }
+
+    #[test]
+    fn children_are_on_their_parents_layer_and_disabled_with_it() {
+        let mut dialog = Component::new(Column::new(vec![Component::new(Label::new("Sure?"))]))
+            .with_layer(OVERLAY);
+        dialog.set_enabled(false);
+        let screen = Screen::new(vec![Component::new(Label::new("page")), dialog]);
+
+        let layers: Vec<(u32, bool)> = screen
+            .root
+            .layered_enabled()
+            .into_iter()
+            .map(|(layer, enabled, _)| (layer, enabled))
+            .collect();
+        assert_eq!(vec![(0, true), (0, true), (1, false), (1, false)], layers);
+    }
+
+    /// A page with a checkbox, under a dialog whose first button is disabled.
+    fn page_and_dialog() -> (Screen<TestRenderer>, WidgetId, WidgetId, WidgetId) {
+        let subscribe = Component::new(Checkbox::new("Subscribe", false));
+        let mut cancel = Component::new(Label::new("Cancel")).with_layer(OVERLAY);
+        cancel.set_enabled(false);
+        let ok = Component::new(Label::new("OK")).with_layer(OVERLAY);
+        let ids = (subscribe.id(), cancel.id(), ok.id());
+        let screen = Screen::new(vec![subscribe, cancel, ok]).with_renderer(TestRenderer::new());
+        (screen, ids.0, ids.1, ids.2)
+    }
+
+    #[test]
+    fn a_dialog_keeps_the_focus_on_its_layer() {
+        let (mut screen, subscribe_id, _, ok_id) = page_and_dialog();
+        assert_eq!(Some(subscribe_id), screen.focused());
+        assert_eq!(Some(ok_id), screen.modal_focus());
+
+        // The first event moves the focus to the first enabled component of the dialog.
+        screen.dispatch(Event::KeyPress(Key::Down));
+        assert_eq!(Some(ok_id), screen.focused());
+        assert_eq!(None, screen.modal_focus());
+
+        screen.focus(subscribe_id);
+        assert_eq!(Some(ok_id), screen.focused());
+    }
+
+    #[test]
+    fn clicks_below_a_dialog_are_dropped() {
+        let (mut screen, subscribe_id, cancel_id, ok_id) = page_and_dialog();
+        // Nothing in the dialog has had the focus yet.
+        assert_eq!(Some(subscribe_id), screen.focused());
+
+        screen.dispatch(Event::Click { x: 5, y: 0 });
+        assert_eq!(Some(ok_id), screen.focused());
+        let subscribe = screen.get_mut(subscribe_id).unwrap();
+        assert!(!subscribe.widget_mut::<Checkbox>().unwrap().checked);
+
+        // Once the dialog is gone, the page can be used again.
+        screen.remove(cancel_id);
+        screen.remove(ok_id);
+        screen.dispatch(Event::Click { x: 5, y: 0 });
+        assert_eq!(Some(subscribe_id), screen.focused());
+        let subscribe = screen.get_mut(subscribe_id).unwrap();
+        assert!(subscribe.widget_mut::<Checkbox>().unwrap().checked);
+    }
+
+    #[test]
+    fn ticks_move_animations_on() {
+        let bar = Component::new(ProgressBar::new(10));
+        let id = bar.id();
+        let mut screen = Screen::new(vec![bar]).with_renderer(TestRenderer::new());
+        let bar = screen.get_mut(id).unwrap().widget_mut::<ProgressBar>().unwrap();
+        bar.set_progress(0.8);
+
+        screen.tick(Duration::from_secs(1));
+        let bar = screen.get_mut(id).unwrap().widget_mut::<ProgressBar>().unwrap();
+        assert_eq!(0.5, bar.shown());
+        screen.tick(Duration::from_secs(1));
+        screen.run().unwrap();
+        assert_eq!(vec!["████████░░"], screen.renderer().texts());
+    }
+
+    #[test]
+    fn only_lists_of_children_can_be_added_to() {
+        let label = Component::new(Label::new("Name"));
+        let panel = Component::new(Panel::new("More", Component::new(Label::new("inside"))));
+        let (label_id, panel_id) = (label.id(), panel.id());
+        let mut screen = Screen::new(vec![label, panel]).with_renderer(TestRenderer::new());
+        let root_id = screen.root.id();
+
+        let added = screen.insert(root_id, Component::new(Label::new("Added"))).ok().unwrap();
+        assert!(screen.get(added).is_some());
+        assert_eq!(5, screen.root.len());
+
+        // A panel holds exactly one child, and a label none, so both give the component back.
+        let extra = Component::new(Label::new("Extra"));
+        let extra_id = extra.id();
+        let extra = screen.insert(panel_id, extra).unwrap_err();
+        assert_eq!(extra_id, extra.id());
+        let extra = screen.insert(label_id, extra).unwrap_err();
+        assert_eq!(extra_id, extra.id());
+        assert_eq!(5, screen.root.len());
+    }
+
+    #[test]
+    fn the_root_cant_be_removed() {
+        let mut screen = Screen::new(vec![Component::new(Label::new("Name"))])
+            .with_renderer(TestRenderer::new());
+        let root_id = screen.root.id();
+
+        assert!(screen.remove(root_id).is_none());
+        assert_eq!(2, screen.root.len());
+    }
+
+    #[test]
+    fn removing_what_has_the_focus_clears_it() {
+        let subscribe = Component::new(Checkbox::new("Subscribe", false));
+        let name = Component::new(TextField::new(10, "Name"));
+        let name_id = name.id();
+        let row = Component::new(Row::new(vec![Component::new(Label::new("Name")), name]));
+        let (subscribe_id, row_id) = (subscribe.id(), row.id());
+        let mut screen = Screen::new(vec![subscribe, row]).with_renderer(TestRenderer::new());
+        screen.focus(name_id);
+
+        // Removing something else leaves the focus where it is.
+        assert!(screen.remove(subscribe_id).is_some());
+        assert_eq!(Some(name_id), screen.focused());
+
+        let removed = screen.remove(row_id).unwrap();
+        assert_eq!(3, removed.len());
+        assert_eq!(None, screen.focused());
+        assert!(screen.get(name_id).is_none());
+    }
+}
@@ -0,0 +1,552 @@
+//! Building a screen from a description in a file, so a layout can be tried out without
+//! recompiling.
+//!
+//! Descriptions use a small part of TOML: one `[[widget]]` table per widget, in the order
+//! they go on the screen, one under the other. Its `type` says which kind of widget it is,
+//! and the rest of its keys say how to make it:
+//!
+//! ```toml
+//! [[widget]]
+//! type = "label"
+//! text = "Sign up"
+//!
+//! [[widget]]
+//! type = "button"
+//! label = "OK"
+//! width = 50
+//! height = 10
+//! ```
+//!
+//! Values can be strings, whole numbers, `true` or `false`, or lists of those on one line.
+//!
+//! Which types there are is up to a `Registry`, which maps each type name to a function
+//! that makes that kind of widget. Applications can register their own widgets next to the
+//! built-in ones.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{Button, Checkbox, Component, Draw, Label, Screen, Slider, TextField};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    List(Vec<Value>),
+}
+
+/// The keys of one `[[widget]]` table. The getters return a message saying what's wrong if
+/// a key is missing or has a value of the wrong kind; the loader adds the line number.
+#[derive(Debug, Default)]
+pub struct Fields {
+    values: HashMap<String, Value>,
+}
+
+impl Fields {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    pub fn string(&self, key: &str) -> Result<&str, String> {
+        match self.get(key) {
+            Some(Value::String(s)) => Ok(s),
+            Some(_) => Err(format!("{} should be a string", key)),
+            None => Err(format!("{} is missing", key)),
+        }
+    }
+
+    /// Like `string`, with `default` if `key` isn't there.
+    pub fn string_or<'a>(&'a self, key: &str, default: &'a str) -> Result<&'a str, String> {
+        match self.get(key) {
+            None => Ok(default),
+            Some(_) => self.string(key),
+        }
+    }
+
+    /// A number that fits in a `u32`, or `default` if `key` isn't there.
+    pub fn u32_or(&self, key: &str, default: u32) -> Result<u32, String> {
+        match self.get(key) {
+            Some(Value::Integer(n)) => {
+                u32::try_from(*n).map_err(|_| format!("{} = {} is out of range", key, n))
+            }
+            Some(_) => Err(format!("{} should be a whole number", key)),
+            None => Ok(default),
+        }
+    }
+
+    pub fn i32_or(&self, key: &str, default: i32) -> Result<i32, String> {
+        match self.get(key) {
+            Some(Value::Integer(n)) => {
+                i32::try_from(*n).map_err(|_| format!("{} = {} is out of range", key, n))
+            }
+            Some(_) => Err(format!("{} should be a whole number", key)),
+            None => Ok(default),
+        }
+    }
+
+    pub fn bool_or(&self, key: &str, default: bool) -> Result<bool, String> {
+        match self.get(key) {
+            Some(Value::Boolean(b)) => Ok(*b),
+            Some(_) => Err(format!("{} should be true or false", key)),
+            None => Ok(default),
+        }
+    }
+
+    pub fn strings(&self, key: &str) -> Result<Vec<String>, String> {
+        let message = || format!("{} should be a list of strings", key);
+        match self.get(key) {
+            Some(Value::List(values)) => values
+                .iter()
+                .map(|value| match value {
+                    Value::String(s) => Ok(s.clone()),
+                    _ => Err(message()),
+                })
+                .collect(),
+            Some(_) => Err(message()),
+            None => Err(format!("{} is missing", key)),
+        }
+    }
+}
+
+/// Makes a widget from the keys of its table.
+pub type Constructor = fn(&Fields) -> Result<Box<dyn Draw>, String>;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    /// Something wrong on line `line` (counting from 1) of the description.
+    Invalid {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+pub struct Registry {
+    constructors: HashMap<String, Constructor>,
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
+
+impl Registry {
+    /// A registry of the widgets this crate comes with: `label`, `button`, `textfield`,
+    /// `checkbox`, and `slider`.
+    pub fn new() -> Registry {
+        let mut registry = Registry {
+            constructors: HashMap::new(),
+        };
+        registry.register("label", |fields| {
+            Ok(Box::new(Label::new(fields.string("text")?)))
+        });
+        registry.register("button", |fields| {
+            let label = fields.string("label")?;
+            Ok(Box::new(Button {
+                width: fields.u32_or("width", label.chars().count() as u32 + 4)?,
+                height: fields.u32_or("height", 1)?,
+                label: String::from(label),
+            }))
+        });
+        registry.register("textfield", |fields| {
+            let placeholder = fields.string_or("placeholder", "")?;
+            let mut field = TextField::new(fields.u32_or("width", 20)?, placeholder);
+            fields
+                .string_or("text", "")?
+                .chars()
+                .for_each(|c| field.insert(c));
+            Ok(Box::new(field))
+        });
+        registry.register("checkbox", |fields| {
+            let checked = fields.bool_or("checked", false)?;
+            Ok(Box::new(Checkbox::new(fields.string("label")?, checked)))
+        });
+        registry.register("slider", |fields| {
+            let (min, max) = (fields.i32_or("min", 0)?, fields.i32_or("max", 10)?);
+            if min > max {
+                return Err(format!("min {} is greater than max {}", min, max));
+            }
+            let mut slider = Slider::new(fields.u32_or("width", 11)?, min, max);
+            slider.set_value(fields.i32_or("value", min)?);
+            Ok(Box::new(slider))
+        });
+        registry
+    }
+
+    /// Makes widgets of type `name` with `constructor`, instead of whatever did before.
+    pub fn register(&mut self, name: &str, constructor: Constructor) {
+        self.constructors.insert(String::from(name), constructor);
+    }
+
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<Screen, LoadError> {
+        self.load(&fs::read_to_string(path)?)
+    }
+
+    pub fn load(&self, description: &str) -> Result<Screen, LoadError> {
+        let mut components = Vec::new();
+        for (line, fields) in parse(description)? {
+            let invalid = |message| LoadError::Invalid { line, message };
+            let name = fields.string("type").map_err(invalid)?;
+            let constructor = self
+                .constructors
+                .get(name)
+                .ok_or_else(|| invalid(format!("unknown widget type {:?}", name)))?;
+            let widget = constructor(&fields).map_err(invalid)?;
+            components.push(Component::from_box(widget));
+        }
+        Ok(Screen::new(components))
+    }
+}
+
+/// The tables in `description`, each with the line its `[[widget]]` header is on.
+fn parse(description: &str) -> Result<Vec<(usize, Fields)>, LoadError> {
+    let mut tables: Vec<(usize, Fields)> = Vec::new();
+
+    for (index, line) in description.lines().enumerate() {
+        let number = index + 1;
+        let invalid = |message: String| LoadError::Invalid {
+            line: number,
+            message,
+        };
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') && !line.contains('=') {
+            if line != "[[widget]]" {
+                return Err(invalid(format!("expected [[widget]], found {}", line)));
+            }
+            tables.push((number, Fields::default()));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("expected key = value, found {}", line)))?;
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(invalid(format!("invalid key {:?}", key)));
+        }
+        let value = parse_value(value.trim()).map_err(invalid)?;
+        let (_, fields) = tables
+            .last_mut()
+            .ok_or_else(|| invalid(String::from("keys have to be in a [[widget]] table")))?;
+        if fields.values.insert(String::from(key), value).is_some() {
+            return Err(invalid(format!("{} is given twice", key)));
+        }
+    }
+
+    Ok(tables)
+}
+
+/// `line` up to a `#` that isn't inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..offset],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    let mut chars = text.char_indices().peekable();
+    let value = value(text, &mut chars)?;
+    match chars.next() {
+        None => Ok(value),
+        Some((offset, _)) => Err(format!("unexpected {:?}", &text[offset..])),
+    }
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+fn value(text: &str, chars: &mut Chars) -> Result<Value, String> {
+    skip_whitespace(chars);
+    match chars.peek().copied() {
+        Some((_, '"')) => {
+            chars.next();
+            string(chars).map(Value::String)
+        }
+        Some((_, '[')) => {
+            chars.next();
+            list(text, chars).map(Value::List)
+        }
+        Some((start, _)) => {
+            let mut end = text.len();
+            while let Some(&(offset, c)) = chars.peek() {
+                if c == ',' || c == ']' || c.is_whitespace() {
+                    end = offset;
+                    break;
+                }
+                chars.next();
+            }
+            match &text[start..end] {
+                "true" => Ok(Value::Boolean(true)),
+                "false" => Ok(Value::Boolean(false)),
+                word => word
+                    .replace('_', "")
+                    .parse()
+                    .map(Value::Integer)
+                    .map_err(|_| format!("invalid value {:?}", word)),
+            }
+        }
+        None => Err(String::from("missing value")),
+    }
+}
+
+/// The rest of a string whose opening quote has been read.
+fn string(chars: &mut Chars) -> Result<String, String> {
+    let mut s = String::new();
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => return Ok(s),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => s.push('\n'),
+                Some((_, 't')) => s.push('\t'),
+                Some((_, c @ ('"' | '\\'))) => s.push(c),
+                Some((_, c)) => return Err(format!("unknown escape \\{}", c)),
+                None => break,
+            },
+            _ => s.push(c),
+        }
+    }
+    Err(String::from("unterminated string"))
+}
+
+/// The rest of a list whose `[` has been read.
+fn list(text: &str, chars: &mut Chars) -> Result<Vec<Value>, String> {
+    let mut values = Vec::new();
+    loop {
+        skip_whitespace(chars);
+        if let Some((_, ']')) = chars.peek() {
+            chars.next();
+            return Ok(values);
+        }
+        values.push(value(text, chars)?);
+        skip_whitespace(chars);
+        match chars.next() {
+            Some((_, ',')) => {}
+            Some((_, ']')) => return Ok(values),
+            Some((_, c)) => return Err(format!("expected , or ] in list, found {:?}", c)),
+            None => return Err(String::from("unterminated list")),
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(result: Result<Screen, LoadError>) -> (usize, String) {
+        match result {
+            Err(LoadError::Invalid { line, message }) => (line, message),
+            Err(e) => panic!("expected an invalid description, got {}", e),
+            Ok(_) => panic!("expected an invalid description, got a screen"),
+        }
+    }
+
+    #[test]
+    fn tables_are_parsed_in_order_with_their_keys() {
+        let description = r#"
+# A comment, then a blank line.
+
+[[widget]]
+type = "slider"   # trailing comment
+min = -5
+max = 1_000
+wide = true
+options = ["a", 2, [false]]
+
+[[widget]]
+type = "label"
+"#;
+        let tables = parse(description).unwrap();
+
+        assert_eq!(
+            vec![4, 11],
+            tables.iter().map(|(line, _)| *line).collect::<Vec<_>>()
+        );
+        let fields = &tables[0].1;
+        assert_eq!(Ok("slider"), fields.string("type"));
+        assert_eq!(Ok(-5), fields.i32_or("min", 0));
+        assert_eq!(Ok(1000), fields.u32_or("max", 0));
+        assert_eq!(Ok(true), fields.bool_or("wide", false));
+        assert_eq!(
+            Some(&Value::List(vec![
+                Value::String(String::from("a")),
+                Value::Integer(2),
+                Value::List(vec![Value::Boolean(false)]),
+            ])),
+            fields.get("options")
+        );
+        assert_eq!(Ok(7), fields.u32_or("width", 7));
+    }
+
+    #[test]
+    fn strings_have_escapes_and_can_hold_a_hash() {
+        let tables = parse(
+            r#"[[widget]]
+text = "say \"hi\" # not a comment\n\tand \\ more"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok("say \"hi\" # not a comment\n\tand \\ more"),
+            tables[0].1.string("text")
+        );
+
+        for (value, message) in [
+            (r#""\q""#, "unknown escape \\q"),
+            (r#""open"#, "unterminated string"),
+            ("[1, 2", "unterminated list"),
+            ("[1 2]", "expected , or ] in list, found '2'"),
+            ("maybe", "invalid value \"maybe\""),
+            ("1]", "unexpected \"]\""),
+        ] {
+            assert_eq!(Err(String::from(message)), parse_value(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn malformed_lines_are_errors_with_their_line_number() {
+        let registry = Registry::new();
+        for (description, line, message) in [
+            ("text = \"x\"", 1, "keys have to be in a [[widget]] table"),
+            (
+                "[[widget]]\n\n[widgets]",
+                3,
+                "expected [[widget]], found [widgets]",
+            ),
+            (
+                "[[widget]]\njust words",
+                2,
+                "expected key = value, found just words",
+            ),
+            ("[[widget]]\nbad key = 1", 2, "invalid key \"bad key\""),
+            ("[[widget]]\na = 1\na = 2", 3, "a is given twice"),
+        ] {
+            assert_eq!(
+                (line, String::from(message)),
+                invalid(registry.load(description)),
+                "{}",
+                description
+            );
+        }
+    }
+
+    #[test]
+    fn widgets_are_made_by_the_registry() {
+        let screen = Registry::new()
+            .load(
+                r#"
+[[widget]]
+type = "label"
+text = "Sign up"
+
+[[widget]]
+type = "textfield"
+placeholder = "Your name"
+text = "Ferris"
+
+[[widget]]
+type = "checkbox"
+label = "Subscribe"
+checked = true
+"#,
+            )
+            .unwrap();
+
+        assert_eq!(4, screen.root.len());
+        let expected = "\
+group
+  text \"Sign up\" (focused)
+  textbox \"Your name\" = Ferris
+  checkbox \"Subscribe\" = checked
+";
+        assert_eq!(expected, screen.dump_accessibility_tree());
+    }
+
+    #[test]
+    fn unknown_types_and_bad_fields_are_errors_on_the_table_line() {
+        let registry = Registry::new();
+        let load = |description: &str| invalid(registry.load(description));
+
+        assert_eq!(
+            (2, String::from("unknown widget type \"dial\"")),
+            load("\n[[widget]]\ntype = \"dial\"")
+        );
+        assert_eq!(
+            (1, String::from("type is missing")),
+            load("[[widget]]\ntext = \"x\"")
+        );
+        assert_eq!(
+            (1, String::from("label is missing")),
+            load("[[widget]]\ntype = \"button\"")
+        );
+        assert_eq!(
+            (1, String::from("width = -1 is out of range")),
+            load("[[widget]]\ntype = \"button\"\nlabel = \"OK\"\nwidth = -1")
+        );
+        assert_eq!(
+            (1, String::from("min 5 is greater than max 1")),
+            load("[[widget]]\ntype = \"slider\"\nmin = 5\nmax = 1")
+        );
+        assert_eq!(
+            (1, String::from("text should be a string")),
+            load("[[widget]]\ntype = \"textfield\"\ntext = 42")
+        );
+        assert_eq!(
+            (1, String::from("placeholder should be a string")),
+            load("[[widget]]\ntype = \"textfield\"\nplaceholder = [\"a\"]")
+        );
+    }
+
+    #[test]
+    fn applications_can_register_their_own_widgets() {
+        let mut registry = Registry::new();
+        registry.register("heading", |fields| {
+            Ok(Box::new(Label::new(&fields.string("text")?.to_uppercase())))
+        });
+        let screen = registry
+            .load("[[widget]]\ntype = \"heading\"\ntext = \"Welcome\"")
+            .unwrap();
+        assert!(screen.dump_accessibility_tree().contains("\"WELCOME\""));
+    }
+}
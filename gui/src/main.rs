@@ -1,6 +1,10 @@
 #![allow(unused_doc_comments)]
 
-use gui::{Draw, Button, Screen};
+use std::env;
+
+use gui::app::{App, Messages};
+use gui::loader::Registry;
+use gui::{Draw, Button, Checkbox, Color, Column, Component, Event, FrameClock, Key, Label, ListView, Padding, Panel, ProgressBar, Rect, Renderer, Role, Row, Screen, Slider, Style, TextField};
 
 fn main() {
     /// # Using Trait Objects That Allow for Values of Different Types
@@ -38,26 +42,178 @@ fn main() {
     /// ## Implementing the Trait
     ///
     /// Now we'll add some types that implement the `Draw` trait.
-    let screen = Screen {
-        components: vec![
-            Box::new(SelectBox {
-                width: 75,
-                height: 10,
-                options: vec![
-                    String::from("Yes"),
-                    String::from("Maybe"),
-                    String::from("No"),
-                ],
-            }),
-            Box::new(Button {
-                width: 50,
-                height: 10,
-                label: String::from("OK"),
-            }),
-        ]
-    };
+    let mut screen = Screen::new(vec![
+        Component::new(SelectBox::new(
+            75,
+            10,
+            vec![
+                String::from("Yes"),
+                String::from("Maybe"),
+                String::from("No"),
+            ],
+        )),
+        Component::new(Button {
+            width: 50,
+            height: 10,
+            label: String::from("OK"),
+        }),
+    ]);
+
+    screen.run().expect("couldn't write to the terminal");
+
+    /// The library's own widgets keep the state a real form needs, and events change it.
+    /// A listener is a closure, so it can keep state of its own too, like this click count.
+    let mut volume = Slider::new(11, 0, 10);
+    volume.set_value(7);
+    let mut clicks = 0;
+    /// Every component has an id, to find it again once it's on the screen.
+    let title = Component::new(Label::new("Sign up"));
+    let name = Component::new(TextField::new(20, "Your name"));
+    let subscribe = Component::new(Checkbox::new("Subscribe", false));
+    let volume = Component::new(volume);
+    let mut send = Component::new(Button {
+        width: 10,
+        height: 1,
+        label: String::from("Send"),
+    })
+    .on_event(move |event| {
+        if let Event::Click { .. } = event {
+            clicks += 1;
+            println!("Send clicked {} time(s)", clicks);
+        }
+    });
+    /// Send can't be used until there's a name: it's drawn dimmed, and clicks on it are
+    /// dropped.
+    send.set_enabled(false);
+    let (title_id, name_id, volume_id, send_id) = (title.id(), name.id(), volume.id(), send.id());
+    let mut screen = Screen::new(vec![title, name, subscribe, volume, send]);
+    screen.run().expect("couldn't write to the terminal");
+    screen.dispatch(Event::Click { x: 3, y: 6 });
+
+    screen.focus(name_id);
+    for c in "Ferris".chars() {
+        screen.dispatch(Event::KeyPress(Key::Char(c)));
+    }
+    if let Some(send) = screen.get_mut(send_id) {
+        send.set_enabled(true);
+    }
+    /// A click goes to whatever is drawn where it lands, and gives that the focus. Under
+    /// the title (row 0) and the three rows of the name field, the checkbox is on row 4.
+    screen.dispatch(Event::Click { x: 1, y: 4 });
+    screen.focus(volume_id);
+    screen.dispatch(Event::KeyPress(Key::Right));
+    screen.dispatch(Event::Click { x: 3, y: 6 });
+    screen.dispatch(Event::Click { x: 3, y: 6 });
+    assert_eq!(Some(send_id), screen.focused());
+
+    /// The application can still change the screen: here the title says who signed up, the
+    /// volume slider goes, and a note is added at the end.
+    if let Some(title) = screen.get_mut(title_id).and_then(|title| title.widget_mut::<Label>()) {
+        title.text = String::from("Signed up: Ferris");
+    }
+    screen.remove(volume_id);
+    let root = screen.root.id();
+    if screen.insert(root, Component::new(Label::new("Thanks!"))).is_err() {
+        println!("The root can't take more children");
+    }
+    screen.run().expect("couldn't write to the terminal");
+
+    /// What a screen reader would be told about the same screen.
+    print!("{}", screen.dump_accessibility_tree());
+
+    /// Containers decide where their children go, from the sizes the children report.
+    let button = |label: &str| Component::new(Button {
+        width: label.len() as u32 + 4,
+        height: 1,
+        label: String::from(label),
+    });
+    let mut screen = Screen::new(vec![Component::new(Padding::new(
+        2,
+        Component::new(Column::new(vec![
+            Component::new(Label::new("Save changes?")),
+            Component::new(Row::new(vec![button("Save"), button("Discard"), button("Cancel")])),
+        ])),
+    ))]);
+
+    screen.run().expect("couldn't write to the terminal");
 
-    screen.run();
+    /// Containers nest, so a select box can sit in a panel next to other panels.
+    let mut screen = Screen::with_root(Component::new(Row::new(vec![
+        Component::new(Panel::new(
+            "Answer",
+            Component::new(SelectBox::new(12, 3, vec![String::from("Yes"), String::from("No")])),
+        )),
+        Component::new(Panel::new("Notes", Component::new(TextField::new(16, "Anything else?")))),
+    ])));
+    screen.run().expect("couldn't write to the terminal");
+
+    /// An open select box is raised onto the overlay layer, so its options are drawn over
+    /// what's under it, and clicks there go to it, not to the Apply button on row 5.
+    let colors = Component::new(SelectBox::new(
+        14,
+        3,
+        vec![String::from("Red"), String::from("Green"), String::from("Blue")],
+    ));
+    let apply = Component::new(Button {
+        width: 9,
+        height: 1,
+        label: String::from("Apply"),
+    })
+    .on_event(|_| println!("Applied!"));
+    let mut screen = Screen::new(vec![
+        colors,
+        Component::new(Label::new("Pick a color for the banner.")),
+        Component::new(Label::new("It shows on every page.")),
+        apply,
+    ]);
+    screen.dispatch(Event::Click { x: 1, y: 1 });
+    screen.run().expect("couldn't write to the terminal");
+    screen.dispatch(Event::Click { x: 3, y: 5 });
+    screen.run().expect("couldn't write to the terminal");
+
+    /// With an `App`, widgets only send messages, `update` changes the model, and the screen
+    /// is made again from the model by `view`. The keyboard shortcuts are on the root,
+    /// which has the focus, so every key press reaches them.
+    let mut app = App::new(Counter { count: 0 }, update, view);
+    for key in ['+', '+', '+', '-'] {
+        app.dispatch(Event::KeyPress(Key::Char(key)));
+    }
+    app.run().expect("couldn't write to the terminal");
+
+    /// Animated widgets move on between frames. At 20 frames per second, a bar filling at
+    /// 2.5 lengths a second gets an eighth fuller every frame.
+    let mut download = ProgressBar::new(16);
+    download.speed = 2.5;
+    download.set_progress(0.5);
+    let mut screen = Screen::new(vec![Component::new(download)]);
+    screen
+        .animate(&mut FrameClock::new(20), 5)
+        .expect("couldn't write to the terminal");
+
+    /// A `ListView` shows a few of its items at a time, however many there are, and scrolls
+    /// with the keyboard while it has the focus.
+    let lines = (1..=1000)
+        .map(|n| Component::new(Label::new(&format!("Line {}", n))))
+        .collect();
+    let mut screen = Screen::new(vec![Component::new(ListView::new(16, 4, lines))]);
+    screen.run().expect("couldn't write to the terminal");
+    screen.dispatch(Event::KeyPress(Key::PageDown));
+    screen.run().expect("couldn't write to the terminal");
+    screen.dispatch(Event::KeyPress(Key::End));
+    screen.run().expect("couldn't write to the terminal");
+
+    /// Screens can also be described in a file, given as the first argument. Our own
+    /// `SelectBox` is registered so descriptions can use it like the built-in widgets.
+    if let Some(path) = env::args().nth(1) {
+        let mut registry = Registry::new();
+        registry.register("selectbox", |fields| {
+            Ok(Box::new(SelectBox::new(fields.u32_or("width", 20)?, 3, fields.strings("options")?)))
+        });
+        match registry.load_file(&path) {
+            Ok(mut screen) => screen.run().expect("couldn't write to the terminal"),
+            Err(e) => eprintln!("Couldn't load {}: {}", path, e),
+        }
+    }
 
     /// The advantage of using trait objects and Rust's type system to write code similar
     /// to code using duck typing is that we never have to check whether a value implements
@@ -65,9 +221,9 @@ fn main() {
     /// a method but we call it anyway. Rust won't compile our code if the values don't implement
     /// the traits that the trait objects need.
     /// ```rust
-    /// let screen = Screen {
-    ///     components: vec![Box::new(String::from("Hi"))]  // Compiler error!
-    /// };
+    /// let mut screen = Screen::new(vec![
+    ///     Component::new(String::from("Hi")),  // Compiler error!
+    /// ]);
     ///
     /// screen.run();
     /// ```
@@ -90,15 +246,151 @@ fn main() {
     ()
 }
 
+struct Counter {
+    count: i32,
+}
+
+#[derive(Clone)]
+enum CounterMsg {
+    Increment,
+    Decrement,
+    Reset,
+}
+
+fn update(counter: &mut Counter, msg: CounterMsg) {
+    match msg {
+        CounterMsg::Increment => counter.count += 1,
+        CounterMsg::Decrement => counter.count -= 1,
+        CounterMsg::Reset => counter.count = 0,
+    }
+}
+
+fn view(counter: &Counter, messages: &Messages<CounterMsg>) -> Screen {
+    let button = |label: &str, msg| {
+        Component::new(Button {
+            width: label.len() as u32 + 4,
+            height: 1,
+            label: String::from(label),
+        })
+        .on_event(messages.on_click(msg))
+    };
+    let shortcuts = messages.listener(|event| match event {
+        Event::KeyPress(Key::Char('+')) => Some(CounterMsg::Increment),
+        Event::KeyPress(Key::Char('-')) => Some(CounterMsg::Decrement),
+        Event::KeyPress(Key::Char('0')) => Some(CounterMsg::Reset),
+        _ => None,
+    });
+    let root = Component::new(Column::new(vec![
+        Component::new(Label::new(&format!("Count: {}", counter.count))),
+        Component::new(Row::new(vec![
+            button("-", CounterMsg::Decrement),
+            button("+", CounterMsg::Increment),
+            button("Reset", CounterMsg::Reset),
+        ])),
+    ]))
+    .on_event(shortcuts);
+    let root_id = root.id();
+    let mut screen = Screen::with_root(root);
+    screen.focus(root_id);
+    screen
+}
+
 /// If someone using our library decides to implement a `SelectBox` struct:
 struct SelectBox {
     width: u32,
     height: u32,
     options: Vec<String>,
+    selected: usize,
+    open: bool,
+    /// Where the last layout put it, to know which option a click is on.
+    bounds: Rect,
 }
 
+impl SelectBox {
+    fn new(width: u32, height: u32, options: Vec<String>) -> SelectBox {
+        SelectBox {
+            width,
+            height,
+            options,
+            selected: 0,
+            open: false,
+            bounds: Rect::default(),
+        }
+    }
+
+    /// Where the options are shown when it's open: right under it, in a border.
+    fn list(&self, bounds: Rect) -> Rect {
+        Rect::new(bounds.x, bounds.y + bounds.height, bounds.width, self.options.len() as u32 + 2)
+    }
+}
+
+/// Clicking it or pressing Enter shows the options below it, over whatever is there. Then
+/// the up and down arrows pick one, or a click on one picks it.
 impl Draw for SelectBox {
-    fn draw(&self) {
-        // code to actually draw a select box
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn layout(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn covers(&self, bounds: Rect, x: u32, y: u32) -> bool {
+        bounds.contains(x, y) || (self.open && self.list(bounds).contains(x, y))
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        // The options, inside the list's border. A click on the border closes the list.
+        let list = self.list(self.bounds);
+        let options = Rect::new(list.x + 1, list.y + 1, list.width.saturating_sub(2), list.height - 2);
+        match event {
+            Event::Click { x, y } if self.open && options.contains(*x, *y) => {
+                self.selected = (y - options.y) as usize;
+                self.open = false;
+            }
+            Event::Click { .. } | Event::KeyPress(Key::Enter) => self.open = !self.open,
+            Event::KeyPress(Key::Up) if self.open => self.selected = self.selected.saturating_sub(1),
+            Event::KeyPress(Key::Down) if self.open => {
+                self.selected = (self.selected + 1).min(self.options.len().saturating_sub(1))
+            }
+            Event::Focus(false) => self.open = false,
+            _ => {}
+        }
+    }
+
+    fn raised(&self) -> bool {
+        self.open
+    }
+
+    fn role(&self) -> Role {
+        Role::ComboBox
+    }
+
+    fn accessible_value(&self) -> Option<String> {
+        self.options.get(self.selected).cloned()
+    }
+
+    /// The selected option, with an arrow to show there are more.
+    fn draw(&self, bounds: Rect, renderer: &mut dyn Renderer) {
+        renderer.border(bounds, Style::default());
+        let selected = self.options.get(self.selected).map_or("", |option| option.as_str());
+        renderer.text(bounds.x + 1, bounds.y + 1, selected, Style::default());
+        renderer.text(bounds.x + bounds.width - 2, bounds.y + 1, "▾", Style::color(Color::Cyan));
+        if !self.open {
+            return;
+        }
+
+        let list = self.list(bounds);
+        let blank: String = " ".repeat(bounds.width.saturating_sub(2) as usize);
+        renderer.border(list, Style::default());
+        for (i, option) in self.options.iter().enumerate() {
+            let y = list.y + 1 + i as u32;
+            renderer.text(list.x + 1, y, &blank, Style::default());
+            if i == self.selected {
+                renderer.text(list.x + 1, y, option, Style::color(Color::Cyan).bold());
+            } else {
+                renderer.text(list.x + 1, y, option, Style::default());
+            }
+        }
     }
 }
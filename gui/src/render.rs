@@ -0,0 +1,258 @@
+//! Turning widgets into something to look at.
+//!
+//! Widgets don't write to the terminal themselves. They describe what they look like to a
+//! `Renderer`, as text and boxes at positions on a grid of character cells, and the
+//! renderer decides how to show that. `TerminalRenderer` collects a whole frame and then
+//! prints it with Unicode box-drawing characters and ANSI colors. `TestRenderer` only
+//! writes down what it was asked to draw, so tests can check it.
+
+use std::io::{self, Write};
+
+use crate::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+    /// Whatever color the terminal uses for text.
+    #[default]
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    /// The ANSI "Select Graphic Rendition" code that sets this foreground color.
+    fn sgr(self) -> u8 {
+        match self {
+            Color::Default => 39,
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::Gray => 90,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub color: Color,
+    pub bold: bool,
+    /// Swaps the text and background colors, like a block cursor.
+    pub reverse: bool,
+    /// Fainter than normal, for things that can't be used right now.
+    pub dim: bool,
+}
+
+impl Style {
+    pub fn color(color: Color) -> Style {
+        Style {
+            color,
+            ..Style::default()
+        }
+    }
+
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    pub fn reverse(mut self) -> Style {
+        self.reverse = true;
+        self
+    }
+
+    pub fn dim(mut self) -> Style {
+        self.dim = true;
+        self
+    }
+}
+
+pub trait Renderer {
+    /// Writes `text` on one line, starting at column `x` of row `y`.
+    fn text(&mut self, x: u32, y: u32, text: &str, style: Style);
+
+    /// Draws the border of `bounds`. Rectangles less than two cells wide or high have no
+    /// room for a border, and are left alone.
+    fn border(&mut self, bounds: Rect, style: Style);
+
+    /// Shows everything drawn since the last call, and starts a new frame.
+    fn present(&mut self) -> io::Result<()>;
+}
+
+/// A `Renderer` that prints frames to `out`, normally the standard output.
+pub struct TerminalRenderer<W: Write> {
+    out: W,
+    /// The frame being drawn, as rows of cells. Rows grow as things are drawn further right,
+    /// and more are added as things are drawn further down.
+    cells: Vec<Vec<(char, Style)>>,
+}
+
+impl TerminalRenderer<io::Stdout> {
+    pub fn stdout() -> TerminalRenderer<io::Stdout> {
+        TerminalRenderer::new(io::stdout())
+    }
+}
+
+impl<W: Write> TerminalRenderer<W> {
+    pub fn new(out: W) -> TerminalRenderer<W> {
+        TerminalRenderer {
+            out,
+            cells: Vec::new(),
+        }
+    }
+
+    fn put(&mut self, x: u32, y: u32, c: char, style: Style) {
+        let (x, y) = (x as usize, y as usize);
+        if self.cells.len() <= y {
+            self.cells.resize(y + 1, Vec::new());
+        }
+        let row = &mut self.cells[y];
+        if row.len() <= x {
+            row.resize(x + 1, (' ', Style::default()));
+        }
+        row[x] = (c, style);
+    }
+}
+
+impl<W: Write> Renderer for TerminalRenderer<W> {
+    fn text(&mut self, x: u32, y: u32, text: &str, style: Style) {
+        for (i, c) in text.chars().enumerate() {
+            self.put(x + i as u32, y, c, style);
+        }
+    }
+
+    fn border(&mut self, bounds: Rect, style: Style) {
+        if bounds.width < 2 || bounds.height < 2 {
+            return;
+        }
+        let right = bounds.x + bounds.width - 1;
+        let bottom = bounds.y + bounds.height - 1;
+        for x in bounds.x + 1..right {
+            self.put(x, bounds.y, '─', style);
+            self.put(x, bottom, '─', style);
+        }
+        for y in bounds.y + 1..bottom {
+            self.put(bounds.x, y, '│', style);
+            self.put(right, y, '│', style);
+        }
+        self.put(bounds.x, bounds.y, '┌', style);
+        self.put(right, bounds.y, '┐', style);
+        self.put(bounds.x, bottom, '└', style);
+        self.put(right, bottom, '┘', style);
+    }
+
+    /// Only switches styles where they change, and resets them at the end of every line so
+    /// a color never leaks into whatever the terminal prints next.
+    fn present(&mut self) -> io::Result<()> {
+        for row in self.cells.drain(..) {
+            let mut current = Style::default();
+            let mut line = String::new();
+            for (c, style) in row {
+                if style != current {
+                    let bold = if style.bold { ";1" } else { "" };
+                    let dim = if style.dim { ";2" } else { "" };
+                    let reverse = if style.reverse { ";7" } else { "" };
+                    let sgr = format!("\x1b[0;{}{}{}{}m", style.color.sgr(), bold, dim, reverse);
+                    line.push_str(&sgr);
+                    current = style;
+                }
+                line.push(c);
+            }
+            if current != Style::default() {
+                line.push_str("\x1b[0m");
+            }
+            writeln!(self.out, "{}", line.trim_end())?;
+        }
+        self.out.flush()
+    }
+}
+
+/// Passes everything on to another renderer, dimmed. Disabled components are drawn through
+/// one, so widgets don't need to know whether they're enabled to look it.
+pub struct Dimmed<'a>(pub &'a mut dyn Renderer);
+
+impl Renderer for Dimmed<'_> {
+    fn text(&mut self, x: u32, y: u32, text: &str, style: Style) {
+        self.0.text(x, y, text, style.dim());
+    }
+
+    fn border(&mut self, bounds: Rect, style: Style) {
+        self.0.border(bounds, style.dim());
+    }
+
+    fn present(&mut self) -> io::Result<()> {
+        self.0.present()
+    }
+}
+
+/// One call to a `Renderer`, as recorded by `TestRenderer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrawOp {
+    Text {
+        x: u32,
+        y: u32,
+        text: String,
+        style: Style,
+    },
+    Border {
+        bounds: Rect,
+        style: Style,
+    },
+    Present,
+}
+
+/// A `Renderer` for tests: instead of drawing anything, it keeps a list of the calls made
+/// to it, like the `MockMessenger` in the `refcell` crate keeps the messages it was sent.
+/// The `Renderer` methods take `&mut self`, so unlike there, no `RefCell` is needed.
+#[derive(Debug, Default)]
+pub struct TestRenderer {
+    pub ops: Vec<DrawOp>,
+}
+
+impl TestRenderer {
+    pub fn new() -> TestRenderer {
+        TestRenderer::default()
+    }
+
+    /// The text drawn so far, without positions or styles, in the order it was drawn.
+    pub fn texts(&self) -> Vec<&str> {
+        self.ops
+            .iter()
+            .filter_map(|op| match op {
+                DrawOp::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Renderer for TestRenderer {
+    fn text(&mut self, x: u32, y: u32, text: &str, style: Style) {
+        self.ops.push(DrawOp::Text {
+            x,
+            y,
+            text: String::from(text),
+            style,
+        });
+    }
+
+    fn border(&mut self, bounds: Rect, style: Style) {
+        self.ops.push(DrawOp::Border { bounds, style });
+    }
+
+    fn present(&mut self) -> io::Result<()> {
+        self.ops.push(DrawOp::Present);
+        Ok(())
+    }
+}
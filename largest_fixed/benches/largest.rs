@@ -0,0 +1,80 @@
+// Compares `largest_n` (a bounded heap) against sorting the whole vector and
+// taking the first `k` elements. The built-in `#[bench]` attribute is still
+// unstable, so this is a plain program that times each strategy with `Instant`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use largest_fixed::{largest_n, largest_ref, min_max};
+
+const RUNS: u32 = 10;
+
+// A small linear congruential generator keeps the input reproducible without
+// pulling in the `rand` crate.
+fn numbers(len: usize) -> Vec<u64> {
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            state >> 33
+        })
+        .collect()
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+// Like `time`, but `setup` makes a fresh input for every run, and only `f` is
+// timed, so an input that `f` consumes can be built without counting against it.
+fn time_batched<T, S: FnMut() -> T, F: FnMut(T)>(mut setup: S, mut f: F) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..RUNS {
+        let input = setup();
+        let start = Instant::now();
+        f(input);
+        total += start.elapsed();
+    }
+    total / RUNS
+}
+
+fn main() {
+    for len in [10_000, 1_000_000] {
+        let list = numbers(len);
+
+        for k in [10, 1_000] {
+            let heap = time(|| {
+                black_box(largest_n(black_box(&list), k));
+            });
+            let sort = time_batched(
+                || black_box(&list).clone(),
+                |mut sorted| {
+                    sorted.sort_unstable_by(|a, b| b.cmp(a));
+                    sorted.truncate(k);
+                    black_box(sorted);
+                },
+            );
+
+            println!(
+                "n = {:>9}, k = {:>5}: largest_n {:>12?}  sort-then-take {:>12?}",
+                len, k, heap, sort
+            );
+        }
+
+        let single = time(|| {
+            black_box(largest_ref(black_box(&list)));
+        });
+        let both = time(|| {
+            black_box(min_max(black_box(&list)));
+        });
+        println!(
+            "n = {:>9}: largest_ref {:>12?}  min_max {:>12?}",
+            len, single, both
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+
+// If we don't want to restrict the `largest` function to the types that implement
+// the `Copy` trait, we could specify that `T` has the trait bound `Clone` instead
+// of `Copy`.
+pub fn largest<T: PartialOrd + Clone>(list: &[T]) -> T {
+    let mut largest = list[0].clone();
+
+    for item in list {
+        if *item > largest {
+            largest = item.clone();
+        }
+    }
+
+    largest
+}
+
+// We could also implement `largest` by having the function return a reference to
+// a `T` value in the slice. If we change the return type to `&T` instead of `T`,
+// thereby changing the body of the function to return a reference, we wouldn't
+// need the `Clone` or `Copy` trait bounds and we could avoid heap allocations.
+// Returning an `Option` also lets us handle an empty slice without panicking.
+pub fn largest_ref<T: PartialOrd>(list: &[T]) -> Option<&T> {
+    let mut iter = list.iter();
+    let mut largest = iter.next()?;
+
+    for item in iter {
+        if item > largest {
+            largest = item;
+        }
+    }
+
+    Some(largest)
+}
+
+// Returns the position of the largest element instead of the element itself. When
+// several elements tie, the first one wins.
+pub fn largest_index<T: PartialOrd>(list: &[T]) -> Option<usize> {
+    let mut largest: Option<usize> = None;
+
+    for (i, item) in list.iter().enumerate() {
+        match largest {
+            Some(j) if *item <= list[j] => {}
+            _ => largest = Some(i),
+        }
+    }
+
+    largest
+}
+
+// Returns the `k` largest elements, largest first.
+//
+// Sorting the whole slice costs O(n log n). Instead we keep a min-heap that never
+// holds more than `k` elements: the smallest of the current top `k` sits at the
+// root, so every new element only has to beat that one. This costs O(n log k).
+// `BinaryHeap` is a max-heap, so wrapping the items in `Reverse` turns it into a
+// min-heap. It needs a total order, hence the `Ord` bound.
+pub fn largest_n<T: Ord>(list: &[T], k: usize) -> Vec<&T> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+
+    for item in list {
+        if heap.len() < k {
+            heap.push(Reverse(item));
+        } else if let Some(Reverse(smallest)) = heap.peek() {
+            if item > *smallest {
+                heap.pop();
+                heap.push(Reverse(item));
+            }
+        }
+    }
+
+    // Sorting `Reverse` values ascending puts the largest items first.
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(item)| item)
+        .collect()
+}
+
+// Finds the smallest and the largest element in a single pass over the slice.
+pub fn min_max<T: PartialOrd>(list: &[T]) -> Option<(&T, &T)> {
+    let mut iter = list.iter();
+    let first = iter.next()?;
+    let (mut min, mut max) = (first, first);
+
+    for item in iter {
+        if item < min {
+            min = item;
+        } else if item > max {
+            max = item;
+        }
+    }
+
+    Some((min, max))
+}
+
+pub struct Pair<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Pair<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    pub fn swap(self) -> Pair<T> {
+        Pair::new(self.y, self.x)
+    }
+
+    // Like `Option::map`, this consumes the pair and applies `f` to both members.
+    // The members may change type, e.g. from `&str` to `usize`.
+    pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> Pair<U> {
+        Pair::new(f(self.x), f(self.y))
+    }
+
+    // Pairs up the members of two pairs: `(x, other.x)` and `(y, other.y)`.
+    pub fn zip<U>(self, other: Pair<U>) -> Pair<(T, U)> {
+        Pair::new((self.x, other.x), (self.y, other.y))
+    }
+}
+
+// `unzip` is the inverse of `zip`, so it only exists for pairs of tuples.
+impl<A, B> Pair<(A, B)> {
+    pub fn unzip(self) -> (Pair<A>, Pair<B>) {
+        let (x1, x2) = self.x;
+        let (y1, y2) = self.y;
+        (Pair::new(x1, y1), Pair::new(x2, y2))
+    }
+}
+
+// With only `PartialOrd`, two values may not be comparable at all (think
+// `f64::NAN`), so these methods return `None` rather than guessing. When the
+// members are equal, `x` is returned.
+impl<T: PartialOrd> Pair<T> {
+    pub fn min(&self) -> Option<&T> {
+        match self.x.partial_cmp(&self.y)? {
+            Ordering::Greater => Some(&self.y),
+            Ordering::Less | Ordering::Equal => Some(&self.x),
+        }
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        match self.x.partial_cmp(&self.y)? {
+            Ordering::Less => Some(&self.y),
+            Ordering::Greater | Ordering::Equal => Some(&self.x),
+        }
+    }
+}
+
+// In this `impl` block, `Pair<T>` only implements the `cmp_display` method if
+// its inner type `T` implements the `PartialOrd` trait that enables comparison
+// *and* the `Display` trait that enables printing.
+//
+// A plain `self.x >= self.y` is `false` whenever either member is NaN, which
+// would silently report `y` as the largest. Matching on `partial_cmp` makes the
+// incomparable case explicit.
+impl<T: Display + PartialOrd> Pair<T> {
+    pub fn cmp_display(&self) {
+        match self.x.partial_cmp(&self.y) {
+            Some(Ordering::Greater | Ordering::Equal) => {
+                println!("The largest member is x = {}", self.x)
+            }
+            Some(Ordering::Less) => println!("The largest member is y = {}", self.y),
+            None => println!("x = {} and y = {} can't be compared", self.x, self.y),
+        }
+    }
+}
+
+// Types that implement `Ord` are always comparable, so this variant has no
+// incomparable case to handle.
+impl<T: Display + Ord> Pair<T> {
+    pub fn cmp_display_ord(&self) {
+        match self.x.cmp(&self.y) {
+            Ordering::Greater | Ordering::Equal => {
+                println!("The largest member is x = {}", self.x)
+            }
+            Ordering::Less => println!("The largest member is y = {}", self.y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_and_index_variants_agree() {
+        let list = vec![34, 50, 25, 100, 65, 100];
+
+        assert_eq!(Some(&100), largest_ref(&list));
+        assert_eq!(Some(3), largest_index(&list));
+        assert_eq!(None, largest_ref::<i32>(&[]));
+        assert_eq!(None, largest_index::<i32>(&[]));
+    }
+
+    #[test]
+    fn largest_n_matches_sort_then_take() {
+        let list = vec![5, 1, 9, 3, 9, 7, 2, 8];
+
+        let mut sorted = list.clone();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        let expected: Vec<&i32> = sorted.iter().take(3).collect();
+
+        assert_eq!(expected, largest_n(&list, 3));
+        assert_eq!(list.len(), largest_n(&list, 100).len());
+        assert!(largest_n(&list, 0).is_empty());
+    }
+
+    #[test]
+    fn min_max_in_one_pass() {
+        assert_eq!(Some((&'a', &'y')), min_max(&['y', 'm', 'a', 'q']));
+        assert_eq!(Some((&1.5, &1.5)), min_max(&[1.5]));
+        assert_eq!(None, min_max::<u8>(&[]));
+    }
+
+    #[test]
+    fn pair_min_max_refuse_to_compare_nan() {
+        let pair = Pair::new(2.5, 1.5);
+        assert_eq!(Some(&1.5), pair.min());
+        assert_eq!(Some(&2.5), pair.max());
+
+        let pair = Pair::new(1.0, f64::NAN);
+        assert_eq!(None, pair.min());
+        assert_eq!(None, pair.max());
+    }
+
+    #[test]
+    fn pair_combinators() {
+        let pair = Pair::new("a", "bcd").map(str::len).swap();
+        assert_eq!((3, 1), (pair.x, pair.y));
+
+        let (left, right) = Pair::new(1, 2).zip(Pair::new('x', 'y')).unzip();
+        assert_eq!((1, 2), (left.x, left.y));
+        assert_eq!(('x', 'y'), (right.x, right.y));
+    }
+}
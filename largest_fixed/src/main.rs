@@ -17,36 +17,8 @@
 //     largest
 // }
 
-// If we don't want to restrict the `largest` function to the types that implement
-// the `Copy` trait, we could specify that `T` has the trait bound `Clone` instead
-// of `Copy`.
-fn largest<T: PartialOrd + Clone>(list: &[T]) -> T {
-    let mut largest = list[0].clone();
-
-    for item in list {
-        if *item > largest {
-            largest = item.clone();
-        }
-    }
-
-    largest
-}
-
-// We could also implement `largest` by having the function return a reference to
-// a `T` value in the slice. If we change the return type to `&T` instead of `T`,
-// thereby changing the body of the function to return a reference, we wouldn't
-// need the `Clone` or `Copy` trait bounds and we could avoid heap allocations.
-// fn largest<T: PartialOrd>(list: &[T]) -> &T {
-//     let mut largest = &list[0];
-//
-//     for item in list {
-//         if item > largest {
-//             largest = item;
-//         }
-//     }
-//
-//     largest
-// }
+// `largest` and its variants live in the library half of this crate (*src/lib.rs*).
+use largest_fixed::{largest, largest_index, largest_n, largest_ref, min_max};
 
 fn main() {
     let number_list = vec![34, 50, 25, 100, 65];
@@ -59,41 +31,34 @@ fn main() {
     let result = largest(&char_list);
     println!("The largest char is {}", result);
 
+    // The variants in *src/lib.rs* borrow instead of cloning and handle empty slices.
+    println!("The largest number by reference is {:?}", largest_ref(&number_list));
+    println!("The largest number is at index {:?}", largest_index(&number_list));
+    println!("The three largest numbers are {:?}", largest_n(&number_list, 3));
+    println!("The smallest and largest chars are {:?}", min_max(&char_list));
+
     // Because the standard library has this blanket implementation, we can call
     // the `to_string` method defined by the `ToString` trait on any type that
     // implements the `Display` trait.
-    let s = 3.to_string();
-
+    let _s = 3.to_string();
+
+    // `f64` is only `PartialOrd`: NaN can't be compared, and `cmp_display` says so
+    // instead of silently picking `y`.
+    Pair::new(1.0, f64::NAN).cmp_display();
+    println!("The smaller member is {:?}", Pair::new(2.5, 1.5).min());
+
+    let (names, ages) = Pair::new("Ferris", "Corro")
+        .zip(Pair::new(7, 3))
+        .swap()
+        .unzip();
+    println!("{} is {}, {} is {}", names.x, ages.x, names.y, ages.y);
+    ages.map(|age| age * 2).cmp_display_ord();
 }
 
 // Using Trait Bounds to Conditionally Implement Methods
-// We can implement methods conditionally for types that implement the
-// specified traits.
-use std::fmt::Display;
-
-struct Pair<T> {
-    x: T,
-    y: T,
-}
-
-impl<T> Pair<T> {
-    fn new(x: T, y: T) -> Self {
-        Self { x, y }
-    }
-}
-
-// In this `impl` block, `Pair<T>` only implements the `cmp_display` method if
-// its inner type `T` implements the `PartialOrd` trait that enables comparison
-// *and* the `Display` trait that enables printing.
-impl<T: Display + PartialOrd> Pair<T> {
-    fn cmp_display(&self) {
-        if self.x >= self.y {
-            println!("The largest member is x = {}", self.x);
-        } else {
-            println!("The largest member is y = {}", self.y);
-        }
-    }
-}
+// `Pair<T>` lives in *src/lib.rs*; its methods are implemented conditionally for
+// types that implement the specified traits.
+use largest_fixed::Pair;
 
 // We can also conditionally implement a trait for any type that implements another trait.
 // Implementations of a trait on any type that satisfies the trait bounds are called
@@ -42,6 +42,83 @@ impl Rectangle {
     }
 }
 
+// A trait collects the behavior that different shapes share, the same way the `gui`
+// crate's `Draw` trait lets `Screen` hold buttons and select boxes side by side.
+// All measurements are `f64` so shapes with whole and fractional sides can be mixed.
+trait Shape {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn scale(&mut self, factor: f64);
+}
+
+// `Rectangle` already has an inherent `area` method; calling `rect.area()` picks the
+// inherent one, while calls through a `dyn Shape` use this implementation.
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        f64::from(self.width) * f64::from(self.height)
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (f64::from(self.width) + f64::from(self.height))
+    }
+
+    // The sides are whole numbers, so the scaled sides are rounded.
+    fn scale(&mut self, factor: f64) {
+        self.width = (f64::from(self.width) * factor).round() as u32;
+        self.height = (f64::from(self.height) * factor).round() as u32;
+    }
+}
+
+#[derive(Debug)]
+struct Circle {
+    radius: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * std::f64::consts::PI * self.radius
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.radius *= factor;
+    }
+}
+
+// A triangle described by the lengths of its three sides.
+#[derive(Debug)]
+struct Triangle {
+    a: f64,
+    b: f64,
+    c: f64,
+}
+
+impl Shape for Triangle {
+    // Heron's formula: the area follows from the side lengths alone.
+    fn area(&self) -> f64 {
+        let s = self.perimeter() / 2.0;
+        (s * (s - self.a) * (s - self.b) * (s - self.c)).sqrt()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.a + self.b + self.c
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.a *= factor;
+        self.b *= factor;
+        self.c *= factor;
+    }
+}
+
+// The slice holds trait objects, so every element can be a different concrete shape.
+fn total_area(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
 fn main() {
     // *Methods* are similar to functions: we declare them with the `fn` keyword
     // and a name.
@@ -93,4 +170,20 @@ fn main() {
 
     // Multiple `impl` Blocks
     // Each struct is allowed to have multiple `impl` blocks.
+
+    // Shapes as Trait Objects
+    let mut shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Rectangle::square(3)),
+        Box::new(Circle { radius: 1.0 }),
+        Box::new(Triangle { a: 3.0, b: 4.0, c: 5.0 }),
+    ];
+    println!("Total area: {:.2}", total_area(&shapes));
+
+    for shape in shapes.iter_mut() {
+        shape.scale(2.0);
+    }
+    println!("Total area after doubling: {:.2}", total_area(&shapes));
+
+    let perimeters: Vec<f64> = shapes.iter().map(|shape| shape.perimeter()).collect();
+    println!("Perimeters: {:.2?}", perimeters);
 }
\ No newline at end of file
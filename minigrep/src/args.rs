@@ -0,0 +1,281 @@
+//! Splitting the command line into options and positional arguments.
+//!
+//! Every option has a long name and may have a one-letter short name. The
+//! usual spellings are understood:
+//! * `--max-count 5`, `--max-count=5`, `-m 5`, and `-m5`
+//! * several short flags in one argument, like `-is` for `-i -s`
+//! * `--` ends the options, so `minigrep -- -v` searches for `-v`
+//! * `-` on its own is a positional argument, meaning standard input
+
+use std::collections::VecDeque;
+use std::iter::Peekable;
+
+/// Describes one command line option, for both parsing and `--help`.
+pub struct OptionSpec {
+    pub long: &'static str,
+    pub short: Option<char>,
+    /// The name of the option's value in `--help`, or `None` for a flag.
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+}
+
+const fn flag(long: &'static str, short: Option<char>, help: &'static str) -> OptionSpec {
+    OptionSpec {
+        long,
+        short,
+        value: None,
+        help,
+    }
+}
+
+const fn valued(
+    long: &'static str,
+    short: Option<char>,
+    value: &'static str,
+    help: &'static str,
+) -> OptionSpec {
+    OptionSpec {
+        long,
+        short,
+        value: Some(value),
+        help,
+    }
+}
+
+/// Every option minigrep understands, in the order `--help` lists them.
+pub const OPTIONS: &[OptionSpec] = &[
+    flag("help", Some('h'), "Print this help and exit"),
+    flag("version", Some('V'), "Print the version and exit"),
+    flag("ignore-case", Some('i'), "Match without regard to letter case"),
+    flag("smart-case", Some('s'), "Ignore case unless the query has an uppercase letter"),
+    flag("case-sensitive", None, "Match letter case exactly (the default)"),
+    flag("regex", None, "Treat the query as a regular expression"),
+    valued("fuzzy", None, "EDITS", "Also match text up to EDITS typos away from the query"),
+    flag("all-of", None, "Take every pattern up to the next option; lines must match all"),
+    valued("max-count", Some('m'), "NUM", "Stop each file after NUM matching lines"),
+    valued("after-context", Some('A'), "NUM", "Print NUM lines after each match"),
+    valued("before-context", Some('B'), "NUM", "Print NUM lines before each match"),
+    valued("context", Some('C'), "NUM", "Print NUM lines before and after each match"),
+    flag("quiet", Some('q'), "Print nothing; exit with status 0 if any line matched, 1 if not"),
+    valued("output", Some('o'), "FILE", "Write results to FILE instead of standard output"),
+    flag("progress", None, "Show the file being searched on standard error"),
+    flag("no-progress", None, "Don't show progress, even on a terminal"),
+    valued("sort", None, "ORDER", "Print files by path or by match count; collects results first"),
+    flag("unique", None, "Print each distinct matching line only once"),
+    valued("format", None, "FORMAT", "Print results as plain (the default), json, or grouped"),
+    flag("column", None, "Print path:line:column: before each match"),
+    flag("color", None, "Highlight matches"),
+    flag("no-color", None, "Don't highlight matches"),
+    valued("encoding", None, "CODEC", "Decode files as utf-8, utf-16le, utf-16be, or latin1"),
+    valued("include", None, "GLOB", "Only search files matching GLOB in directories and patterns"),
+    valued("exclude", None, "GLOB", "Skip files and directories matching GLOB"),
+    valued("max-depth", None, "NUM", "Descend at most NUM levels into directories"),
+    flag("no-ignore", None, "Don't skip paths listed in .gitignore and .ignore"),
+    flag("follow-symlinks", None, "Enter symbolically linked directories"),
+    flag("follow", Some('f'), "Keep printing matching lines as the file grows"),
+];
+
+/// One parsed piece of the command line.
+#[derive(Debug, PartialEq)]
+pub enum Arg {
+    /// An option by its long name, with its value if it takes one.
+    Option(&'static str, Option<String>),
+    Positional(String),
+}
+
+pub struct Parser<I: Iterator<Item=String>> {
+    args: Peekable<I>,
+    /// The rest of a group of short flags like `-is`.
+    shorts: VecDeque<char>,
+    /// Set once we've passed `--`.
+    positional_only: bool,
+}
+
+impl<I: Iterator<Item=String>> Parser<I> {
+    /// `args` shouldn't include the program name.
+    pub fn new(args: I) -> Parser<I> {
+        Parser {
+            args: args.peekable(),
+            shorts: VecDeque::new(),
+            positional_only: false,
+        }
+    }
+
+    /// Takes the arguments that follow, up to the next one that looks like an
+    /// option (or `--`), as plain values. This is how `--all-of` gets its list.
+    pub fn values(&mut self) -> Vec<String> {
+        let mut values = Vec::new();
+        if self.positional_only || !self.shorts.is_empty() {
+            return values;
+        }
+        while let Some(value) = self.args.next_if(|arg| !arg.starts_with('-') || arg == "-") {
+            values.push(value);
+        }
+        values
+    }
+
+    fn short(&mut self, name: char) -> Result<Arg, String> {
+        let spec = OPTIONS
+            .iter()
+            .find(|spec| spec.short == Some(name))
+            .ok_or_else(|| format!("unknown flag -{}", name))?;
+
+        if spec.value.is_none() {
+            return Ok(Arg::Option(spec.long, None));
+        }
+
+        // The value is the rest of this argument (`-m5`) or the next one (`-m 5`).
+        let value = if self.shorts.is_empty() {
+            self.args.next()
+        } else {
+            Some(self.shorts.drain(..).collect())
+        };
+        match value {
+            Some(value) => Ok(Arg::Option(spec.long, Some(value))),
+            None => Err(format!("-{} expects a value", name)),
+        }
+    }
+
+    fn long(&mut self, arg: &str) -> Result<Arg, String> {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg, None),
+        };
+        let spec = OPTIONS
+            .iter()
+            .find(|spec| spec.long == name)
+            .ok_or_else(|| format!("unknown flag --{}", name))?;
+
+        match (spec.value, inline) {
+            (None, None) => Ok(Arg::Option(spec.long, None)),
+            (None, Some(_)) => Err(format!("--{} doesn't take a value", name)),
+            (Some(_), Some(value)) => Ok(Arg::Option(spec.long, Some(value))),
+            (Some(_), None) => match self.args.next() {
+                Some(value) => Ok(Arg::Option(spec.long, Some(value))),
+                None => Err(format!("--{} expects a value", name)),
+            },
+        }
+    }
+}
+
+impl<I: Iterator<Item=String>> Iterator for Parser<I> {
+    type Item = Result<Arg, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(name) = self.shorts.pop_front() {
+            return Some(self.short(name));
+        }
+
+        let arg = self.args.next()?;
+        if self.positional_only || arg == "-" || !arg.starts_with('-') {
+            return Some(Ok(Arg::Positional(arg)));
+        }
+        if arg == "--" {
+            self.positional_only = true;
+            return self.next();
+        }
+
+        match arg.strip_prefix("--") {
+            Some(long) => Some(self.long(long)),
+            None => {
+                self.shorts.extend(arg.chars().skip(1));
+                self.next()
+            }
+        }
+    }
+}
+
+/// The text printed by `--help`.
+pub fn usage() -> String {
+    let mut usage = String::from(
+        "Usage: minigrep [OPTIONS] QUERY [FILE]...\n\
+         \x20      minigrep [OPTIONS] --all-of PATTERN... [--] [FILE]...\n\
+         \x20      minigrep bench [--lines N] [--runs N] [--seed N]\n\
+         \n\
+         Prints the lines of each FILE that contain QUERY. A FILE may be a\n\
+         directory to search recursively or a glob pattern like 'src/**/*.rs'.\n\
+         With no FILE, or when FILE is -, reads standard input.\n\
+         \n\
+         Options:\n",
+    );
+
+    let names: Vec<String> = OPTIONS
+        .iter()
+        .map(|spec| {
+            let short = spec.short.map_or(String::from("    "), |c| format!("-{}, ", c));
+            let value = spec.value.map_or(String::new(), |value| format!(" {}", value));
+            format!("{}--{}{}", short, spec.long, value)
+        })
+        .collect();
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+
+    for (name, spec) in names.iter().zip(OPTIONS) {
+        usage.push_str(&format!("  {:width$}  {}\n", name, spec.help, width = width));
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Vec<Arg>, String> {
+        Parser::new(args.iter().map(|arg| arg.to_string())).collect()
+    }
+
+    fn option(long: &'static str, value: Option<&str>) -> Arg {
+        Arg::Option(long, value.map(String::from))
+    }
+
+    #[test]
+    fn long_and_short_spellings() {
+        let expected = vec![option("max-count", Some("5"))];
+        for args in [&["--max-count", "5"][..], &["--max-count=5"], &["-m", "5"], &["-m5"]] {
+            assert_eq!(Ok(&expected), parse(args).as_ref());
+        }
+    }
+
+    #[test]
+    fn grouped_short_flags() {
+        assert_eq!(
+            Ok(vec![option("ignore-case", None), option("context", Some("2"))]),
+            parse(&["-iC2"])
+        );
+    }
+
+    #[test]
+    fn double_dash_ends_options() {
+        assert_eq!(
+            Ok(vec![
+                option("regex", None),
+                Arg::Positional(String::from("-v")),
+                Arg::Positional(String::from("--help")),
+            ]),
+            parse(&["--regex", "--", "-v", "--help"])
+        );
+        assert_eq!(Ok(vec![Arg::Positional(String::from("-"))]), parse(&["-"]));
+    }
+
+    #[test]
+    fn descriptive_errors() {
+        assert_eq!(Err(String::from("unknown flag --foo")), parse(&["--foo"]));
+        assert_eq!(Err(String::from("unknown flag -x")), parse(&["-ix"]));
+        assert_eq!(Err(String::from("--max-count expects a value")), parse(&["--max-count"]));
+        assert_eq!(Err(String::from("--regex doesn't take a value")), parse(&["--regex=yes"]));
+    }
+
+    #[test]
+    fn values_stop_at_the_next_option() {
+        let mut parser = Parser::new(["a", "b", "--", "file"].iter().map(|arg| arg.to_string()));
+        assert_eq!(vec!["a", "b"], parser.values());
+        assert_eq!(Some(Ok(Arg::Positional(String::from("file")))), parser.next());
+    }
+
+    #[test]
+    fn usage_lists_every_option() {
+        let usage = usage();
+        for spec in OPTIONS {
+            assert!(usage.contains(&format!("--{}", spec.long)));
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! `minigrep bench`: times each search strategy on generated input.
+//!
+//! The input is reproducible (a fixed seed feeds a small linear congruential
+//! generator), so numbers from before and after a change can be compared.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{match_iter, Config, ConfigBuilder};
+
+/// The word every strategy searches for. `generate` mixes it into the text in
+/// several capitalizations so the case-insensitive searches find more.
+pub const NEEDLE: &str = "needle";
+
+const WORDS: &[&str] = &[
+    "safe",
+    "fast",
+    "productive",
+    "pick",
+    "three",
+    "duct",
+    "tape",
+    "rust",
+    "trust",
+    "frog",
+    "bog",
+    "public",
+    "june",
+    "nobody",
+    "dreary",
+    "somebody",
+    "admiring",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchOptions {
+    /// How many lines of input to generate.
+    pub lines: usize,
+    /// How many times each strategy runs; the mean is reported.
+    pub runs: u32,
+    pub seed: u64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> BenchOptions {
+        BenchOptions {
+            lines: 100_000,
+            runs: 10,
+            seed: 0x2545_F491_4F6C_DD1D,
+        }
+    }
+}
+
+impl BenchOptions {
+    /// Parses the arguments after `bench`: `--lines N`, `--runs N`, and
+    /// `--seed N`.
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<BenchOptions, String> {
+        let mut options = BenchOptions::default();
+
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{} expects a number", arg))?;
+            let number = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("{} expects a number, not {}", arg, value))
+            };
+
+            match arg.as_str() {
+                "--lines" => options.lines = number()? as usize,
+                "--runs" => options.runs = number()?.max(1) as u32,
+                "--seed" => options.seed = number()?,
+                _ => return Err(format!("unknown bench option {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Generates `lines` lines of five to twelve words each. One word in eight is
+/// `NEEDLE`: mostly in lowercase, sometimes capitalized or in all caps.
+pub fn generate(lines: usize, seed: u64) -> String {
+    let mut state = seed;
+    let mut next = move |bound: usize| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as usize % bound
+    };
+
+    let mut text = String::new();
+    for _ in 0..lines {
+        let words = 5 + next(8);
+        for i in 0..words {
+            if i > 0 {
+                text.push(' ');
+            }
+            let word = match next(64) {
+                0..=5 => "needle",
+                6 => "Needle",
+                7 => "NEEDLE",
+                _ => WORDS[next(WORDS.len())],
+            };
+            text.push_str(word);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+struct Timing {
+    name: &'static str,
+    matches: usize,
+    mean: Duration,
+}
+
+fn time<F: FnMut() -> usize>(name: &'static str, runs: u32, mut search: F) -> Timing {
+    let mut matches = 0;
+    let start = Instant::now();
+    for _ in 0..runs {
+        matches = black_box(search());
+    }
+    Timing {
+        name,
+        matches,
+        mean: start.elapsed() / runs,
+    }
+}
+
+/// Counts the lines `config` matches, the way every strategy is timed: lazily,
+/// through `match_iter`, so none of them pays for collecting what it found.
+fn count(config: &Config, contents: &str) -> usize {
+    match_iter(config, contents).map_or(0, |matches| matches.count())
+}
+
+/// Runs every strategy on the same input and prints a table comparing them.
+pub fn run(options: &BenchOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = generate(options.lines, options.seed);
+    let contents = contents.as_str();
+    // Built, and the pattern checked, once here so the timed closures don't pay
+    // for it.
+    let strategies = [
+        ("sensitive", ConfigBuilder::new().query(NEEDLE).build()?),
+        (
+            "insensitive",
+            ConfigBuilder::new()
+                .query(NEEDLE)
+                .ignore_case(true)
+                .build()?,
+        ),
+        (
+            "regex",
+            ConfigBuilder::new()
+                .query(&format!("\\b{}\\b", NEEDLE))
+                .regex(true)
+                .build()?,
+        ),
+        (
+            "multi-pattern",
+            ConfigBuilder::new().query(NEEDLE).all_of("safe").build()?,
+        ),
+    ];
+
+    let runs = options.runs;
+    let timings: Vec<Timing> = strategies
+        .iter()
+        .map(|(name, config)| time(name, runs, || count(config, contents)))
+        .collect();
+
+    let megabytes = contents.len() as f64 / 1_000_000.0;
+    println!(
+        "{} lines, {:.1} MB, mean of {} runs",
+        options.lines, megabytes, runs
+    );
+    println!(
+        "{:<14} {:>8} {:>12} {:>10} {:>9}",
+        "strategy", "matches", "time", "MB/s", "relative"
+    );
+
+    let fastest = timings.iter().map(|t| t.mean).min().unwrap_or_default();
+    for timing in &timings {
+        let seconds = timing.mean.as_secs_f64();
+        println!(
+            "{:<14} {:>8} {:>12.3?} {:>10.1} {:>8.2}x",
+            timing.name,
+            timing.matches,
+            timing.mean,
+            megabytes / seconds.max(f64::EPSILON),
+            seconds / fastest.as_secs_f64().max(f64::EPSILON),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{search_iter, search_matches_case_insensitive};
+
+    #[test]
+    fn generated_input_is_reproducible() {
+        let text = generate(200, 7);
+        assert_eq!(text, generate(200, 7));
+        assert_ne!(text, generate(200, 8));
+        assert_eq!(200, text.lines().count());
+        assert!(search_iter(NEEDLE, &text).count() > 0);
+        assert!(
+            search_matches_case_insensitive(NEEDLE, &text).len()
+                > search_iter(NEEDLE, &text).count()
+        );
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = ["--lines", "50", "--runs", "3"]
+            .iter()
+            .map(|arg| arg.to_string());
+        let options = BenchOptions::parse(args).unwrap();
+        assert_eq!(50, options.lines);
+        assert_eq!(3, options.runs);
+
+        let args = ["--lines"].iter().map(|arg| arg.to_string());
+        assert!(BenchOptions::parse(args).is_err());
+        let args = ["--fast", "1"].iter().map(|arg| arg.to_string());
+        assert!(BenchOptions::parse(args).is_err());
+    }
+}
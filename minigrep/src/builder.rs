@@ -0,0 +1,494 @@
+//! Building a `Config` in code, for programs that use minigrep as a library.
+//!
+//! ```rust,ignore
+//! let config = ConfigBuilder::new()
+//!     .query("duct")
+//!     .path("src/**/*.txt")
+//!     .ignore_case(true)
+//!     .context(1)
+//!     .build()?;
+//! minigrep::run(config)?;
+//! ```
+//!
+//! Nothing here reads the command line, the environment, or `.minigreprc`
+//! files; `Command::new` does that and then fills in a builder itself.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::regex::{self, Regex};
+use crate::{glob, walk, Case, Config, Encoding, Format, SortBy, STDIN_FILENAME};
+
+/// Why `ConfigBuilder::build` couldn't make a `Config`.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// No query was set.
+    MissingQuery,
+    /// The query (or an `all_of` pattern) isn't a valid regular expression.
+    InvalidRegex(regex::Error),
+    /// A directory or glob pattern couldn't be walked.
+    Walk { path: String, source: io::Error },
+    /// A directory or glob pattern turned up no files at all.
+    NoFilesMatch(String),
+    /// `follow` needs a single real file to watch.
+    FollowNeedsOneFile,
+    /// `fuzzy` allows at least as many edits as a pattern has characters, so
+    /// every line would match.
+    FuzzyMatchesEverything { pattern: String, max_edits: usize },
+    /// Two options were set that don't work together.
+    Incompatible(&'static str, &'static str),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::MissingQuery => write!(f, "Didn't get a query string"),
+            ConfigError::InvalidRegex(e) => write!(f, "Invalid regular expression: {}", e),
+            ConfigError::Walk { path, source } => write!(f, "{}: {}", path, source),
+            ConfigError::NoFilesMatch(path) => write!(f, "No files match {}", path),
+            ConfigError::FollowNeedsOneFile => write!(f, "--follow needs exactly one file name"),
+            ConfigError::FuzzyMatchesEverything { pattern, max_edits } => write!(
+                f,
+                "--fuzzy {} would match every line, since {:?} has only {} characters",
+                max_edits,
+                pattern,
+                pattern.chars().count()
+            ),
+            ConfigError::Incompatible(a, b) => write!(f, "{} can't be used with {}", a, b),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::InvalidRegex(e) => Some(e),
+            ConfigError::Walk { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the options for a search; `build` checks them and expands the paths.
+///
+/// Everything but the query has a default: case-sensitive substring search of
+/// standard input, with no context, no color, and ignore files respected.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    query: Option<String>,
+    all_of: Vec<String>,
+    paths: Vec<String>,
+    case: Case,
+    regex: bool,
+    fuzzy: Option<usize>,
+    before_context: usize,
+    after_context: usize,
+    color: bool,
+    format: Format,
+    sort: Option<SortBy>,
+    unique: bool,
+    output: Option<String>,
+    progress: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    max_depth: Option<usize>,
+    no_ignore: bool,
+    follow_symlinks: bool,
+    encoding: Option<Encoding>,
+    max_count: Option<usize>,
+    column: bool,
+    quiet: bool,
+    follow: bool,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> ConfigBuilder {
+        ConfigBuilder {
+            query: None,
+            all_of: Vec::new(),
+            paths: Vec::new(),
+            case: Case::Sensitive,
+            regex: false,
+            fuzzy: None,
+            before_context: 0,
+            after_context: 0,
+            color: false,
+            format: Format::Plain,
+            sort: None,
+            unique: false,
+            output: None,
+            progress: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            max_depth: None,
+            no_ignore: false,
+            follow_symlinks: false,
+            encoding: None,
+            max_count: None,
+            column: false,
+            quiet: false,
+            follow: false,
+        }
+    }
+}
+
+impl ConfigBuilder {
+    pub fn new() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    pub fn query(mut self, query: &str) -> ConfigBuilder {
+        self.query = Some(query.to_string());
+        self
+    }
+
+    /// Adds a pattern that matching lines must contain as well as the query.
+    pub fn all_of(mut self, pattern: &str) -> ConfigBuilder {
+        self.all_of.push(pattern.to_string());
+        self
+    }
+
+    /// Adds a file, directory, or glob pattern to search. `-` means standard
+    /// input, which is also what gets searched if no path is added.
+    pub fn path(mut self, path: &str) -> ConfigBuilder {
+        self.paths.push(path.to_string());
+        self
+    }
+
+    pub fn case(mut self, case: Case) -> ConfigBuilder {
+        self.case = case;
+        self
+    }
+
+    /// Shorthand for `case(Case::Insensitive)` or `case(Case::Sensitive)`.
+    pub fn ignore_case(self, ignore_case: bool) -> ConfigBuilder {
+        self.case(if ignore_case {
+            Case::Insensitive
+        } else {
+            Case::Sensitive
+        })
+    }
+
+    pub fn regex(mut self, regex: bool) -> ConfigBuilder {
+        self.regex = regex;
+        self
+    }
+
+    /// Also matches text up to `max_edits` inserted, deleted, or changed
+    /// characters away from the query, to find misspellings. Only plain
+    /// queries can be fuzzy, not regular expressions.
+    pub fn fuzzy(mut self, max_edits: usize) -> ConfigBuilder {
+        self.fuzzy = Some(max_edits);
+        self
+    }
+
+    /// Sets the lines of context both before and after each match.
+    pub fn context(self, lines: usize) -> ConfigBuilder {
+        self.before_context(lines).after_context(lines)
+    }
+
+    pub fn before_context(mut self, lines: usize) -> ConfigBuilder {
+        self.before_context = lines;
+        self
+    }
+
+    pub fn after_context(mut self, lines: usize) -> ConfigBuilder {
+        self.after_context = lines;
+        self
+    }
+
+    pub fn color(mut self, color: bool) -> ConfigBuilder {
+        self.color = color;
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> ConfigBuilder {
+        self.format = format;
+        self
+    }
+
+    /// Prints the files in this order. All the matches are collected first,
+    /// so this can't be combined with context lines or `follow`.
+    pub fn sort(mut self, sort: SortBy) -> ConfigBuilder {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// Prints each distinct matching line only once, the first time it's found.
+    /// Like `sort`, this can't be combined with context lines or `follow`.
+    pub fn unique(mut self, unique: bool) -> ConfigBuilder {
+        self.unique = unique;
+        self
+    }
+
+    /// Writes the results to the file at `path`, replacing anything in it,
+    /// instead of to standard output.
+    pub fn output(mut self, path: &str) -> ConfigBuilder {
+        self.output = Some(path.to_string());
+        self
+    }
+
+    /// Reports which file is being searched on standard error, when there's
+    /// more than one.
+    pub fn progress(mut self, progress: bool) -> ConfigBuilder {
+        self.progress = progress;
+        self
+    }
+
+    /// Adds a glob pattern for the files to keep when expanding directories
+    /// and glob patterns. With none, every file is kept.
+    pub fn include(mut self, pattern: &str) -> ConfigBuilder {
+        self.include.push(pattern.to_string());
+        self
+    }
+
+    /// Adds a glob pattern for files to leave out of directory and glob
+    /// expansion. A directory that matches isn't entered.
+    pub fn exclude(mut self, pattern: &str) -> ConfigBuilder {
+        self.exclude.push(pattern.to_string());
+        self
+    }
+
+    /// Limits how deep directories are searched: files directly in a directory
+    /// named on the command line are at depth 1.
+    pub fn max_depth(mut self, max_depth: usize) -> ConfigBuilder {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn no_ignore(mut self, no_ignore: bool) -> ConfigBuilder {
+        self.no_ignore = no_ignore;
+        self
+    }
+
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> ConfigBuilder {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: Encoding) -> ConfigBuilder {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    pub fn max_count(mut self, max_count: usize) -> ConfigBuilder {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    pub fn column(mut self, column: bool) -> ConfigBuilder {
+        self.column = column;
+        self
+    }
+
+    pub fn quiet(mut self, quiet: bool) -> ConfigBuilder {
+        self.quiet = quiet;
+        self
+    }
+
+    pub fn follow(mut self, follow: bool) -> ConfigBuilder {
+        self.follow = follow;
+        self
+    }
+
+    /// Checks the options and makes the `Config`.
+    ///
+    /// Directories and glob patterns like `src/**/*.rs` are expanded here, so
+    /// this reads the file system. Directories and patterns skip whatever
+    /// `.gitignore` and `.ignore` files list, unless `no_ignore` is set.
+    /// Inclusions, exclusions, and the depth limit only apply to the expanded
+    /// names; a file named explicitly is always searched.
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let query = self.query.ok_or(ConfigError::MissingQuery)?;
+
+        // Compile the patterns once up front so a malformed one is reported as a
+        // configuration problem instead of failing halfway through a search.
+        if self.regex {
+            for pattern in std::iter::once(&query).chain(&self.all_of) {
+                Regex::new(pattern).map_err(ConfigError::InvalidRegex)?;
+            }
+        }
+
+        let walk_options = walk::Options {
+            max_depth: self.max_depth,
+            respect_ignore: !self.no_ignore,
+            follow_symlinks: self.follow_symlinks,
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+        };
+        let mut filenames = Vec::new();
+        for path in &self.paths {
+            let expanded = if glob::is_glob(path) {
+                glob::expand(path, &walk_options)
+            } else if Path::new(path).is_dir() {
+                walk::files_with(Path::new(path), &walk_options)
+            } else {
+                filenames.push(path.clone());
+                continue;
+            };
+
+            let expanded = expanded.map_err(|source| ConfigError::Walk {
+                path: path.clone(),
+                source,
+            })?;
+            if expanded.is_empty() {
+                return Err(ConfigError::NoFilesMatch(path.clone()));
+            }
+            filenames.extend(expanded.iter().map(|path| path.display().to_string()));
+        }
+
+        // Without a file name we read standard input, so `cat log | minigrep error`
+        // works the same as `cat log | minigrep error -`.
+        if self.paths.is_empty() {
+            filenames.push(String::from(STDIN_FILENAME));
+        }
+
+        if let Some(max_edits) = self.fuzzy {
+            if self.regex {
+                return Err(ConfigError::Incompatible("--fuzzy", "--regex"));
+            }
+            let pattern = std::iter::once(&query)
+                .chain(&self.all_of)
+                .find(|pattern| pattern.chars().count() <= max_edits);
+            if let Some(pattern) = pattern {
+                return Err(ConfigError::FuzzyMatchesEverything {
+                    pattern: pattern.clone(),
+                    max_edits,
+                });
+            }
+        }
+
+        if self.sort.is_some() || self.unique {
+            let option = if self.sort.is_some() { "--sort" } else { "--unique" };
+            if self.before_context > 0 || self.after_context > 0 {
+                return Err(ConfigError::Incompatible(option, "context lines"));
+            }
+            if self.follow {
+                return Err(ConfigError::Incompatible(option, "--follow"));
+            }
+        }
+
+        if self.follow && (filenames.len() != 1 || filenames[0] == STDIN_FILENAME) {
+            return Err(ConfigError::FollowNeedsOneFile);
+        }
+
+        let ignore_case = match self.case {
+            Case::Insensitive => true,
+            Case::Smart => !std::iter::once(&query)
+                .chain(&self.all_of)
+                .any(|pattern| has_uppercase(pattern, self.regex)),
+            Case::Sensitive => false,
+        };
+
+        Ok(Config {
+            query,
+            all_of: self.all_of,
+            follow: self.follow,
+            column: self.column,
+            quiet: self.quiet,
+            max_count: self.max_count,
+            filenames,
+            ignore_case,
+            regex: self.regex,
+            fuzzy: self.fuzzy,
+            before_context: self.before_context,
+            after_context: self.after_context,
+            color: self.color,
+            format: self.format,
+            sort: self.sort,
+            unique: self.unique,
+            output: self.output,
+            progress: self.progress,
+            include: self.include,
+            exclude: self.exclude,
+            max_depth: self.max_depth,
+            no_ignore: self.no_ignore,
+            follow_symlinks: self.follow_symlinks,
+            encoding: self.encoding,
+        })
+    }
+}
+
+/// Returns `true` if `query` contains an uppercase letter. In a regular
+/// expression, escapes such as `\W` and `\S` are syntax, not letters, so the
+/// character after a backslash doesn't count.
+fn has_uppercase(query: &str, regex: bool) -> bool {
+    let mut chars = query.chars();
+
+    while let Some(c) = chars.next() {
+        if regex && c == '\\' {
+            chars.next();
+        } else if c.is_uppercase() {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_search_standard_input() {
+        let config = ConfigBuilder::new().query("duct").build().unwrap();
+        assert_eq!("duct", config.query);
+        assert_eq!(vec![String::from(STDIN_FILENAME)], config.filenames);
+        assert!(!config.ignore_case);
+        assert_eq!((0, 0), (config.before_context, config.after_context));
+    }
+
+    #[test]
+    fn setters_fill_in_the_config() {
+        let config = ConfigBuilder::new()
+            .query("Duct")
+            .path("poem.txt")
+            .case(Case::Smart)
+            .context(2)
+            .after_context(3)
+            .max_count(1)
+            .build()
+            .unwrap();
+
+        assert_eq!(vec![String::from("poem.txt")], config.filenames);
+        assert!(!config.ignore_case);
+        assert_eq!((2, 3), (config.before_context, config.after_context));
+        assert_eq!(Some(1), config.max_count);
+    }
+
+    #[test]
+    fn build_reports_what_is_wrong() {
+        assert!(matches!(
+            ConfigBuilder::new().build(),
+            Err(ConfigError::MissingQuery)
+        ));
+        assert!(matches!(
+            ConfigBuilder::new().query("(").regex(true).build(),
+            Err(ConfigError::InvalidRegex(_))
+        ));
+        assert!(matches!(
+            ConfigBuilder::new().query("x").follow(true).build(),
+            Err(ConfigError::FollowNeedsOneFile)
+        ));
+        assert_eq!(
+            "--unique can't be used with context lines",
+            ConfigBuilder::new()
+                .query("x")
+                .unique(true)
+                .context(1)
+                .build()
+                .unwrap_err()
+                .to_string()
+        );
+
+        let error = ConfigBuilder::new()
+            .query("x")
+            .path("no-such-dir/*.rs")
+            .build()
+            .unwrap_err();
+        assert_eq!("No files match no-such-dir/*.rs", error.to_string());
+    }
+}
@@ -0,0 +1,151 @@
+//! Context lines around matches, like grep's `-A`, `-B`, and `-C` options.
+
+use std::collections::VecDeque;
+
+/// One line of output from `search_with_context`.
+#[derive(Debug, PartialEq)]
+pub enum ContextLine<'a> {
+    /// A line that matched. `number` counts from 1.
+    Match { number: usize, text: &'a str },
+    /// A line printed only because it's near a match.
+    Context { number: usize, text: &'a str },
+    /// Printed as `--` between groups of lines that aren't adjacent.
+    Separator,
+}
+
+/// Returns the matching lines of `contents` together with up to `before` lines
+/// of context before each match and `after` lines after it.
+///
+/// When the context of two matches overlaps or touches, the lines are printed
+/// once as a single group; otherwise the groups are separated by
+/// `ContextLine::Separator`. Without any context, there are no separators.
+pub fn search_with_context<'a, F>(
+    contents: &'a str,
+    is_match: F,
+    before: usize,
+    after: usize,
+) -> Vec<ContextLine<'a>>
+where
+    F: Fn(&str) -> bool,
+{
+    search_with_context_max(contents, is_match, before, after, None)
+}
+
+/// Like `search_with_context`, but stops after `max_count` matches, once the
+/// "after" context of the last one has been printed, like grep's `-m`.
+pub fn search_with_context_max<'a, F>(
+    contents: &'a str,
+    is_match: F,
+    before: usize,
+    after: usize,
+    max_count: Option<usize>,
+) -> Vec<ContextLine<'a>>
+where
+    F: Fn(&str) -> bool,
+{
+    let max_count = max_count.unwrap_or(usize::MAX);
+    let mut matches = 0;
+    let mut results = Vec::new();
+    // The most recent lines that haven't been printed yet, in case the next line
+    // matches and they turn out to be "before" context. It never holds more than
+    // `before` lines, so it works as a ring buffer.
+    let mut previous: VecDeque<(usize, &str)> = VecDeque::with_capacity(before + 1);
+    let mut after_remaining = 0;
+    let mut last_printed: Option<usize> = None;
+
+    for (i, text) in contents.lines().enumerate() {
+        let number = i + 1;
+
+        if matches == max_count {
+            if after_remaining == 0 {
+                break;
+            }
+            results.push(ContextLine::Context { number, text });
+            after_remaining -= 1;
+        } else if is_match(text) {
+            let first = previous.front().map_or(number, |&(n, _)| n);
+            let has_context = before > 0 || after > 0;
+            if has_context && last_printed.is_some_and(|last| first > last + 1) {
+                results.push(ContextLine::Separator);
+            }
+
+            results.extend(
+                previous
+                    .drain(..)
+                    .map(|(number, text)| ContextLine::Context { number, text }),
+            );
+            results.push(ContextLine::Match { number, text });
+            matches += 1;
+            last_printed = Some(number);
+            after_remaining = after;
+        } else if after_remaining > 0 {
+            results.push(ContextLine::Context { number, text });
+            last_printed = Some(number);
+            after_remaining -= 1;
+        } else {
+            previous.push_back((number, text));
+            if previous.len() > before {
+                previous.pop_front();
+            }
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENTS: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight";
+
+    fn render(lines: &[ContextLine]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| match line {
+                ContextLine::Match { number, text } => format!("{}:{}", number, text),
+                ContextLine::Context { number, text } => format!("{}-{}", number, text),
+                ContextLine::Separator => String::from("--"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_context_is_just_the_matches() {
+        let lines = search_with_context(CONTENTS, |line| line.contains('v'), 0, 0);
+        assert_eq!(vec!["5:five", "7:seven"], render(&lines));
+    }
+
+    #[test]
+    fn separate_groups_get_a_separator() {
+        let lines = search_with_context(CONTENTS, |line| line == "two" || line == "seven", 0, 1);
+        assert_eq!(
+            vec!["2:two", "3-three", "--", "7:seven", "8-eight"],
+            render(&lines)
+        );
+    }
+
+    #[test]
+    fn overlapping_context_is_merged() {
+        let lines = search_with_context(CONTENTS, |line| line == "three" || line == "six", 1, 1);
+        assert_eq!(
+            vec!["2-two", "3:three", "4-four", "5-five", "6:six", "7-seven"],
+            render(&lines)
+        );
+    }
+
+    #[test]
+    fn max_count_keeps_the_last_after_context() {
+        let lines = search_with_context_max(CONTENTS, |line| line.contains('e'), 0, 2, Some(2));
+        assert_eq!(vec!["1:one", "2-two", "3:three", "4-four", "5-five"], render(&lines));
+    }
+
+    #[test]
+    fn before_context_is_limited_by_the_start_of_the_input() {
+        let lines = search_with_context(CONTENTS, |line| line == "two" || line == "eight", 3, 0);
+        assert_eq!(
+            vec!["1-one", "2:two", "--", "5-five", "6-six", "7-seven", "8:eight"],
+            render(&lines)
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Turning the raw bytes of a file into UTF-8 text.
+//!
+//! Rust strings are always UTF-8, but files on disk aren't. Without a hint from
+//! the user we look for a byte order mark (BOM) first, then try UTF-8, and fall
+//! back to latin-1, where every byte is a character, so decoding never fails.
+
+use std::str::FromStr;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1: each byte is the Unicode code point with the same value.
+    Latin1,
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    /// Parses a codec name like `utf-8` or `latin1`, ignoring case.
+    fn from_str(name: &str) -> Result<Encoding, String> {
+        match name.to_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "utf-16le" | "utf16le" => Ok(Encoding::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(Encoding::Utf16Be),
+            "latin1" | "latin-1" | "iso-8859-1" => Ok(Encoding::Latin1),
+            _ => Err(format!("unknown encoding {}", name)),
+        }
+    }
+}
+
+/// Guesses the encoding of `bytes`: a BOM wins, then valid UTF-8, then latin-1.
+pub fn detect(bytes: &[u8]) -> Encoding {
+    if bytes.starts_with(UTF16LE_BOM) {
+        Encoding::Utf16Le
+    } else if bytes.starts_with(UTF16BE_BOM) {
+        Encoding::Utf16Be
+    } else if std::str::from_utf8(bytes).is_ok() {
+        Encoding::Utf8
+    } else {
+        Encoding::Latin1
+    }
+}
+
+/// Decodes `bytes`, detecting the encoding unless one is given. A BOM matching
+/// the encoding is dropped so it doesn't end up in the first line.
+///
+/// Only a forced UTF-8 decode can fail; malformed UTF-16 becomes U+FFFD
+/// replacement characters instead.
+pub fn decode(bytes: &[u8], encoding: Option<Encoding>) -> Result<String, String> {
+    let encoding = encoding.unwrap_or_else(|| detect(bytes));
+
+    match encoding {
+        Encoding::Utf8 => {
+            let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+            String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())
+        }
+        Encoding::Utf16Le => Ok(decode_utf16(
+            bytes.strip_prefix(UTF16LE_BOM).unwrap_or(bytes),
+            u16::from_le_bytes,
+        )),
+        Encoding::Utf16Be => Ok(decode_utf16(
+            bytes.strip_prefix(UTF16BE_BOM).unwrap_or(bytes),
+            u16::from_be_bytes,
+        )),
+        Encoding::Latin1 => Ok(bytes.iter().map(|&b| char::from(b)).collect()),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    // A trailing odd byte can't be a whole code unit; it decodes as U+FFFD.
+    let units = bytes.chunks(2).map(|pair| match pair {
+        &[a, b] => unit([a, b]),
+        _ => 0xFFFD,
+    });
+
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        let mut bytes = UTF16LE_BOM.to_vec();
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn detects_boms_utf8_and_latin1() {
+        assert_eq!(Encoding::Utf16Le, detect(&utf16le("hi")));
+        assert_eq!(Encoding::Utf16Be, detect(&[0xFE, 0xFF, 0, b'h']));
+        assert_eq!(Encoding::Utf8, detect("café".as_bytes()));
+        assert_eq!(Encoding::Latin1, detect(b"caf\xE9"));
+    }
+
+    #[test]
+    fn decodes_to_utf8_without_the_bom() {
+        assert_eq!(
+            Ok(String::from("safe, fast\n")),
+            decode(&utf16le("safe, fast\n"), None)
+        );
+        assert_eq!(Ok(String::from("b")), decode(&[0xFE, 0xFF, 0, b'b'], None));
+        assert_eq!(Ok(String::from("café")), decode(b"caf\xE9", None));
+        assert_eq!(Ok(String::from("x")), decode(b"\xEF\xBB\xBFx", None));
+    }
+
+    #[test]
+    fn forced_encoding_overrides_detection() {
+        // Valid UTF-8, but read byte by byte.
+        assert_eq!(
+            Ok(String::from("cafÃ©")),
+            decode("café".as_bytes(), Some(Encoding::Latin1))
+        );
+        assert!(decode(b"caf\xE9", Some(Encoding::Utf8)).is_err());
+    }
+
+    #[test]
+    fn parses_encoding_names() {
+        assert_eq!(Ok(Encoding::Utf16Be), "UTF-16BE".parse());
+        assert_eq!(Ok(Encoding::Latin1), "iso-8859-1".parse());
+        assert!("ebcdic".parse::<Encoding>().is_err());
+    }
+}
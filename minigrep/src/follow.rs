@@ -0,0 +1,229 @@
+//! Watching a file for new lines, like `tail -f`.
+//!
+//! There's no portable file notification API in the standard library, so a
+//! `Follower` is polled: each call to `poll` reads whatever was appended since
+//! the last one.
+
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::encoding::{self, Encoding};
+
+/// How long `run` waits between polls when a file is followed.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct Follower {
+    path: PathBuf,
+    file: File,
+    /// Where the next read starts, to notice when the file gets shorter.
+    position: u64,
+    /// The start of a line whose newline hasn't been written yet.
+    pending: Vec<u8>,
+    line_number: usize,
+    /// The encoding given with `--encoding`, if any.
+    forced: Option<Encoding>,
+    /// How the file being read is decoded: `forced`, or a UTF-16 BOM's once the
+    /// start of the file is in. `None` decodes each line on its own, as UTF-8
+    /// when it's valid and latin-1 when it isn't.
+    encoding: Option<Encoding>,
+}
+
+impl Follower {
+    /// Opens `path`, to be decoded with `encoding`, or detected like
+    /// `encoding::decode` does without one. The first `poll` returns the lines
+    /// already in the file.
+    pub fn open(path: &Path, encoding: Option<Encoding>) -> io::Result<Follower> {
+        Ok(Follower {
+            path: path.to_path_buf(),
+            file: File::open(path)?,
+            position: 0,
+            pending: Vec::new(),
+            line_number: 0,
+            forced: encoding,
+            encoding,
+        })
+    }
+
+    /// Returns the complete lines written since the last call, numbered from
+    /// the start of the file.
+    ///
+    /// If the file was truncated, reading starts over from the top. If it was
+    /// rotated, that is, the path now names a different file, the rest of the
+    /// old file is read first and then the new one from the top. While the path
+    /// doesn't exist (in the middle of a rotation), we keep the old file.
+    ///
+    /// A line that isn't valid in the forced encoding is an `InvalidData` error,
+    /// as it would be for a file that's searched once.
+    pub fn poll(&mut self) -> io::Result<Vec<(usize, String)>> {
+        let mut lines = self.read_new_lines()?;
+
+        let on_disk = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(lines),
+            Err(e) => return Err(e),
+        };
+
+        if !same_file(&on_disk, &self.file.metadata()?) {
+            self.file = File::open(&self.path)?;
+            self.restart();
+            lines.extend(self.read_new_lines()?);
+        } else if on_disk.len() < self.position {
+            self.file.seek(SeekFrom::Start(0))?;
+            self.restart();
+            lines.extend(self.read_new_lines()?);
+        }
+
+        Ok(lines)
+    }
+
+    fn restart(&mut self) {
+        self.position = 0;
+        self.pending.clear();
+        self.line_number = 0;
+        self.encoding = self.forced;
+    }
+
+    fn read_new_lines(&mut self) -> io::Result<Vec<(usize, String)>> {
+        let mut bytes = Vec::new();
+        self.position += self.file.read_to_end(&mut bytes)? as u64;
+        self.pending.extend(bytes);
+
+        // Until the first line is complete, the start of the file is still all
+        // in `pending`, so a BOM can be looked for there.
+        if self.line_number == 0 && self.forced.is_none() {
+            self.encoding = match encoding::detect(&self.pending) {
+                utf16 @ (Encoding::Utf16Le | Encoding::Utf16Be) => Some(utf16),
+                _ => None,
+            };
+        }
+
+        let mut lines = Vec::new();
+        while let Some((end, newline)) = line_end(&self.pending, self.encoding) {
+            let line: Vec<u8> = self.pending.drain(..end + newline).collect();
+            let line = encoding::decode(&line[..end], self.encoding).map_err(|e| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: {}", self.path.display(), e),
+                )
+            })?;
+            self.line_number += 1;
+            lines.push((self.line_number, line.trim_end_matches('\r').to_string()));
+        }
+
+        Ok(lines)
+    }
+}
+
+/// Where the first line in `bytes` ends, and how many bytes the newline after it
+/// takes, if there's a whole line.
+fn line_end(bytes: &[u8], encoding: Option<Encoding>) -> Option<(usize, usize)> {
+    let newline = match encoding {
+        Some(Encoding::Utf16Le) => [b'\n', 0],
+        Some(Encoding::Utf16Be) => [0, b'\n'],
+        _ => return bytes.iter().position(|&b| b == b'\n').map(|end| (end, 1)),
+    };
+    bytes
+        .chunks_exact(2)
+        .position(|unit| unit == newline)
+        .map(|unit| (unit * 2, 2))
+}
+
+/// Whether two sets of metadata describe the same file. Only Unix gives us an
+/// identity to compare (device and inode); elsewhere rotation looks like an
+/// ordinary truncation or append.
+#[cfg(unix)]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &fs::Metadata, _b: &fs::Metadata) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    fn lines(follower: &mut Follower) -> Vec<(usize, String)> {
+        follower.poll().unwrap()
+    }
+
+    fn line(number: usize, text: &str) -> (usize, String) {
+        (number, text.to_string())
+    }
+
+    #[test]
+    fn appends_truncation_and_rotation() {
+        let dir = env::temp_dir().join(format!("minigrep-follow-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.log");
+        fs::write(&path, "one\ntw").unwrap();
+
+        let mut follower = Follower::open(&path, None).unwrap();
+        assert_eq!(vec![line(1, "one")], lines(&mut follower));
+        assert!(lines(&mut follower).is_empty());
+
+        // The rest of a half-written line arrives.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"o\nthree\n").unwrap();
+        assert_eq!(vec![line(2, "two"), line(3, "three")], lines(&mut follower));
+
+        // Truncated in place.
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(vec![line(1, "new")], lines(&mut follower));
+
+        // Rotated: the old file is moved away and a new one takes its place.
+        file.write_all(b"last\n").unwrap();
+        fs::rename(&path, dir.join("app.log.1")).unwrap();
+        fs::write(&path, "fresh\n").unwrap();
+        assert_eq!(vec![line(2, "last"), line(1, "fresh")], lines(&mut follower));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn lines_are_decoded_like_a_searched_file() {
+        let dir = env::temp_dir().join(format!("minigrep-follow-encoding-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // A UTF-16 file is split on UTF-16 newlines, even when one arrives in halves.
+        let path = dir.join("utf16.log");
+        let utf16: Vec<u8> = "\u{FEFF}café\nto"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        fs::write(&path, &utf16).unwrap();
+        let mut follower = Follower::open(&path, None).unwrap();
+        assert_eq!(vec![line(1, "café")], lines(&mut follower));
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\n").unwrap();
+        assert!(lines(&mut follower).is_empty());
+        file.write_all(&[0]).unwrap();
+        assert_eq!(vec![line(2, "to")], lines(&mut follower));
+
+        // Without a BOM, a line that isn't UTF-8 is latin-1.
+        let path = dir.join("latin1.log");
+        fs::write(&path, b"caf\xE9\ncaf\xC3\xA9\n").unwrap();
+        let mut follower = Follower::open(&path, None).unwrap();
+        assert_eq!(vec![line(1, "café"), line(2, "café")], lines(&mut follower));
+
+        // `--encoding` wins over detection, and can fail.
+        let mut follower = Follower::open(&path, Some(Encoding::Latin1)).unwrap();
+        assert_eq!(
+            vec![line(1, "café"), line(2, "cafÃ©")],
+            lines(&mut follower)
+        );
+        let mut follower = Follower::open(&path, Some(Encoding::Utf8)).unwrap();
+        let error = follower.poll().unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+//! Approximate matching for `--fuzzy N`: a line matches if some part of it is
+//! within `N` edits of the query, where an edit is inserting, deleting, or
+//! changing one character. With `--fuzzy 1`, `recieve` finds `receive`.
+//!
+//! This is Sellers' variant of the Levenshtein distance. The usual table has a
+//! row for each character of the query and a column for each character of the
+//! line; the difference is that the first row is all zeros, so a match may
+//! start anywhere in the line instead of only at its beginning. Only one column
+//! is kept at a time, and every cell also remembers where in the line its
+//! best alignment started, which gives us the matched span.
+
+use std::ops::Range;
+
+/// A match found by `Fuzzy::find_iter`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    /// The byte range of the matched text in the line.
+    pub range: Range<usize>,
+    /// How many edits turn the matched text into the query.
+    pub distance: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Fuzzy {
+    query: Vec<char>,
+    max_edits: usize,
+    ignore_case: bool,
+}
+
+/// One cell of the table: the fewest edits to match the query so far, and the
+/// byte offset in the line where that alignment starts.
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    cost: usize,
+    start: usize,
+}
+
+impl Fuzzy {
+    /// Matches `query` with up to `max_edits` edits. If `max_edits` isn't less
+    /// than the number of characters in `query`, every line matches.
+    pub fn new(query: &str, max_edits: usize, ignore_case: bool) -> Fuzzy {
+        Fuzzy {
+            query: query.chars().map(|c| fold(c, ignore_case)).collect(),
+            max_edits,
+            ignore_case,
+        }
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        !self.find_iter(line).is_empty()
+    }
+
+    /// The closest matches in `line`, left to right and without overlaps. Of
+    /// two overlapping candidates, the one with fewer edits wins. If they tie,
+    /// a longer one that starts at the same place wins, so `café` beats `caf`
+    /// as a match for `cafe`; otherwise the earlier one does.
+    pub fn find_iter(&self, line: &str) -> Vec<FuzzyMatch> {
+        let mut found: Vec<FuzzyMatch> = Vec::new();
+        let mut best: Option<FuzzyMatch> = None;
+
+        // Before any of the line is read, matching the first `i` characters of
+        // the query takes `i` deletions.
+        let mut column: Vec<Cell> = (0..=self.query.len())
+            .map(|i| Cell { cost: i, start: 0 })
+            .collect();
+
+        for (offset, c) in line.char_indices() {
+            let end = offset + c.len_utf8();
+            let c = fold(c, self.ignore_case);
+
+            // `diagonal` is the cell up and to the left of the one being filled.
+            let mut diagonal = column[0];
+            column[0] = Cell {
+                cost: 0,
+                start: end,
+            };
+            for i in 1..=self.query.len() {
+                let above = column[i - 1];
+                let left = column[i];
+                let substitute = Cell {
+                    cost: diagonal.cost + usize::from(self.query[i - 1] != c),
+                    ..diagonal
+                };
+                let insert = Cell {
+                    cost: left.cost + 1,
+                    ..left
+                };
+                let delete = Cell {
+                    cost: above.cost + 1,
+                    ..above
+                };
+                diagonal = left;
+                column[i] = [substitute, insert, delete]
+                    .into_iter()
+                    .min_by_key(|cell| cell.cost)
+                    .unwrap();
+            }
+
+            let last = column[self.query.len()];
+            if last.cost > self.max_edits || last.start == end {
+                continue;
+            }
+            let candidate = FuzzyMatch {
+                range: last.start..end,
+                distance: last.cost,
+            };
+            if found
+                .last()
+                .is_some_and(|m| candidate.range.start < m.range.end)
+            {
+                continue;
+            }
+            best = match best {
+                Some(current) if candidate.range.start < current.range.end => {
+                    let better = (candidate.distance, candidate.range.start)
+                        <= (current.distance, current.range.start);
+                    if better {
+                        Some(candidate)
+                    } else {
+                        Some(current)
+                    }
+                }
+                Some(current) => {
+                    found.push(current);
+                    Some(candidate)
+                }
+                None => Some(candidate),
+            };
+        }
+
+        found.extend(best);
+        found
+    }
+
+    /// The fewest edits of any match in `line`, or `None` if there's none.
+    pub fn distance(&self, line: &str) -> Option<usize> {
+        self.find_iter(line).iter().map(|m| m.distance).min()
+    }
+}
+
+/// The character to compare: `c` itself, or its lowercase form when ignoring
+/// case. Only the first character of the lowercase form is used, so that one
+/// character of the line is still one character of the table.
+fn fold(c: char, ignore_case: bool) -> char {
+    if ignore_case {
+        c.to_lowercase().next().unwrap_or(c)
+    } else {
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans<'a>(query: &str, max_edits: usize, line: &'a str) -> Vec<(&'a str, usize)> {
+        Fuzzy::new(query, max_edits, false)
+            .find_iter(line)
+            .into_iter()
+            .map(|m| (&line[m.range], m.distance))
+            .collect()
+    }
+
+    #[test]
+    fn finds_misspellings() {
+        assert_eq!(
+            vec![("recieve", 2)],
+            spans("receive", 2, "failed to recieve packet")
+        );
+        assert!(spans("receive", 1, "failed to recieve packet").is_empty());
+        assert_eq!(vec![("colour", 1)], spans("color", 1, "the colour red"));
+        assert_eq!(vec![("rust", 0)], spans("rust", 1, "rusty"));
+    }
+
+    #[test]
+    fn matches_do_not_overlap() {
+        assert_eq!(
+            vec![("rust", 0), ("rast", 1)],
+            spans("rust", 1, "rust rast")
+        );
+        assert_eq!(vec![("ab", 0)], spans("ab", 1, "ab"));
+    }
+
+    #[test]
+    fn spans_are_byte_ranges() {
+        let line = "naïve café";
+        let found = Fuzzy::new("cafe", 1, false).find_iter(line);
+        assert_eq!("café", &line[found[0].range.clone()]);
+        assert_eq!(1, found[0].distance);
+    }
+
+    #[test]
+    fn ignoring_case() {
+        assert!(!Fuzzy::new("ERROR", 1, false).is_match("an eror here"));
+        let fuzzy = Fuzzy::new("ERROR", 1, true);
+        assert_eq!(Some(1), fuzzy.distance("an eror here"));
+        assert_eq!(None, fuzzy.distance("all good"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Shell-style file name patterns such as `src/**/*.rs`.
+//!
+//! Patterns are matched one path component at a time:
+//! * `*` matches any run of characters within a component
+//! * `?` matches a single character
+//! * `[abc]`, `[a-z]`, and `[!a-z]` match one character from (or not from) a set
+//! * a component that is exactly `**` matches any number of directories,
+//!   including none
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::walk;
+
+/// Returns `true` if `pattern` contains any of the special characters above.
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Returns `true` if the whole of `path` matches `pattern`.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    match_components(&pattern, &path)
+}
+
+/// Returns `true` if `path` matches any of the `--include` or `--exclude`
+/// `patterns`.
+///
+/// `path` is relative to the directory being searched. A pattern containing `/`
+/// must match the whole of it. A pattern without one, like `*.lock` or `target`,
+/// matches if any single component of it does, so it covers matching files and
+/// everything inside matching directories.
+pub fn matches_any(patterns: &[String], path: &str) -> bool {
+    let path = path.strip_prefix("./").unwrap_or(path);
+
+    patterns.iter().any(|pattern| {
+        if pattern.contains('/') {
+            matches(pattern, path)
+        } else {
+            let pattern: Vec<char> = pattern.chars().collect();
+            path.split('/').any(|component| {
+                let component: Vec<char> = component.chars().collect();
+                match_name(&pattern, &component)
+            })
+        }
+    })
+}
+
+/// Expands `pattern` into the sorted list of files it matches.
+///
+/// The leading components without special characters name the directory to
+/// start from, so `src/**/*.rs` only walks `src`. `options` controls the walk
+/// itself, such as whether ignore files are honored.
+pub fn expand(pattern: &str, options: &walk::Options) -> io::Result<Vec<PathBuf>> {
+    let components: Vec<&str> = pattern.split('/').collect();
+    let fixed = components.iter().take_while(|c| !is_glob(c)).count();
+
+    let base = match components[..fixed].join("/") {
+        base if base.is_empty() && pattern.starts_with('/') => String::from("/"),
+        base if base.is_empty() => String::from("."),
+        base => base,
+    };
+    let rest: Vec<&str> = components[fixed..]
+        .iter()
+        .copied()
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    // Without `**`, there's no point walking deeper than the pattern reaches.
+    let max_depth = if rest.contains(&"**") {
+        options.max_depth
+    } else {
+        Some(rest.len())
+    };
+    let options = walk::Options {
+        max_depth,
+        ..options.clone()
+    };
+
+    let base = Path::new(&base);
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut found = Vec::new();
+    for path in walk::files_with(base, &options)? {
+        let relative = path.strip_prefix(base).unwrap_or(&path);
+        let relative: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let relative: Vec<&str> = relative.iter().map(String::as_str).collect();
+
+        if match_components(&rest, &relative) {
+            // Print `src/lib.rs` rather than `./src/lib.rs` for patterns like `*.rs`.
+            found.push(if fixed == 0 && !pattern.starts_with('/') {
+                relative.iter().collect()
+            } else {
+                path
+            });
+        }
+    }
+
+    Ok(found)
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        // `**` either matches nothing, or swallows one more directory and tries again.
+        Some((&"**", rest)) => {
+            match_components(rest, path)
+                || (!path.is_empty() && match_components(pattern, &path[1..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((name, names)) => {
+                let first: Vec<char> = first.chars().collect();
+                let name: Vec<char> = name.chars().collect();
+                match_name(&first, &name) && match_components(rest, names)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_name(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| match_name(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && match_name(rest, &name[1..]),
+        Some(('[', rest)) => match (name.split_first(), parse_set(rest)) {
+            (Some((&c, names)), Some((set, after))) => set(c) && match_name(after, names),
+            // An unclosed `[` is an ordinary character.
+            (Some((&c, names)), None) => c == '[' && match_name(rest, names),
+            (None, _) => false,
+        },
+        Some((&c, rest)) => name.first() == Some(&c) && match_name(rest, &name[1..]),
+    }
+}
+
+/// Parses the inside of `[...]`, returning a membership test and the rest of
+/// the pattern after the closing `]`.
+fn parse_set(pattern: &[char]) -> Option<(impl Fn(char) -> bool, &[char])> {
+    let negated = matches!(pattern.first(), Some('!' | '^'));
+    let body = if negated { &pattern[1..] } else { pattern };
+
+    // A `]` right after the opening bracket is a literal.
+    let close = body.iter().skip(1).position(|&c| c == ']')? + 1;
+    let (items, rest) = (&body[..close], &body[close + 1..]);
+
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < items.len() {
+        if i + 2 < items.len() && items[i + 1] == '-' {
+            ranges.push((items[i], items[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((items[i], items[i]));
+            i += 1;
+        }
+    }
+
+    let set = move |c: char| ranges.iter().any(|&(low, high)| low <= c && c <= high) != negated;
+    Some((set, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn component_wildcards() {
+        assert!(matches("*.rs", "lib.rs"));
+        assert!(!matches("*.rs", "src/lib.rs"));
+        assert!(matches("src/?ib.rs", "src/lib.rs"));
+        assert!(matches("[m-q]*.txt", "poem.txt"));
+        assert!(!matches("[!a-z]*.txt", "poem.txt"));
+        assert!(matches("[]]", "]"));
+    }
+
+    #[test]
+    fn double_star_matches_any_number_of_directories() {
+        assert!(matches("src/**/*.rs", "src/lib.rs"));
+        assert!(matches("src/**/*.rs", "src/a/b/c.rs"));
+        assert!(!matches("src/**/*.rs", "tests/a.rs"));
+        assert!(matches("**", "a/b/c"));
+    }
+
+    #[test]
+    fn filter_patterns() {
+        let patterns = vec![String::from("target"), String::from("src/**/*.txt")];
+
+        assert!(matches_any(&patterns, "target/debug/build.rs"));
+        assert!(matches_any(&patterns, "./crates/a/target/out.rs"));
+        assert!(matches_any(&patterns, "src/a/notes.txt"));
+        assert!(!matches_any(&patterns, "notes.txt"));
+        assert!(!matches_any(&patterns, "src/lib.rs"));
+    }
+
+    #[test]
+    fn expand_walks_from_the_fixed_prefix() {
+        let dir = env::temp_dir().join(format!("minigrep-glob-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/nested")).unwrap();
+        for file in [
+            "src/lib.rs",
+            "src/nested/deep.rs",
+            "src/notes.txt",
+            "top.rs",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let root = dir.to_str().unwrap();
+        let found = expand(&format!("{}/src/**/*.rs", root), &walk::Options::default()).unwrap();
+        assert_eq!(
+            vec![dir.join("src/lib.rs"), dir.join("src/nested/deep.rs")],
+            found
+        );
+
+        let found = expand(&format!("{}/*.rs", root), &walk::Options::default()).unwrap();
+        assert_eq!(vec![dir.join("top.rs")], found);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
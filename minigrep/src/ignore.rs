@@ -0,0 +1,185 @@
+//! `.gitignore` and `.ignore` files, so a recursive search skips build output
+//! like `target/` and `node_modules/`.
+//!
+//! This understands the common subset of the gitignore format:
+//! * blank lines and lines starting with `#` are skipped
+//! * a pattern without a `/` (other than a trailing one) matches a file or
+//!   directory name at any depth below the ignore file
+//! * a pattern with a `/` is relative to the directory holding the ignore file
+//! * a trailing `/` only matches directories
+//! * a leading `!` re-includes a path that an earlier pattern ignored
+//!
+//! Patterns use the same wildcards as the `glob` module.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::glob;
+
+/// The ignore files read from each directory, in order; later ones win.
+pub const FILE_NAMES: [&str; 2] = [".gitignore", ".ignore"];
+
+#[derive(Debug, PartialEq)]
+struct Rule {
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Rule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let pattern = line.trim_start_matches('/');
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(Rule {
+            pattern: pattern.to_string(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// `relative` is the path from the ignore file's directory, with `/`
+    /// separators.
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            glob::matches(&self.pattern, relative)
+        } else {
+            let name = relative.rsplit('/').next().unwrap_or(relative);
+            glob::matches(&self.pattern, name)
+        }
+    }
+}
+
+/// The rules from one directory's ignore files.
+#[derive(Debug)]
+pub struct IgnoreFile {
+    dir: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl IgnoreFile {
+    pub fn parse(dir: &Path, contents: &str) -> IgnoreFile {
+        IgnoreFile {
+            dir: dir.to_path_buf(),
+            rules: contents.lines().filter_map(Rule::parse).collect(),
+        }
+    }
+
+    /// Reads the ignore files in `dir`. Returns `None` if there aren't any, so
+    /// callers only keep directories that add rules.
+    pub fn read(dir: &Path) -> Option<IgnoreFile> {
+        let contents: Vec<String> = FILE_NAMES
+            .iter()
+            .filter_map(|name| fs::read_to_string(dir.join(name)).ok())
+            .collect();
+
+        if contents.is_empty() {
+            None
+        } else {
+            Some(IgnoreFile::parse(dir, &contents.join("\n")))
+        }
+    }
+
+    /// `Some(true)` if the last matching rule ignores `path`, `Some(false)` if it
+    /// re-includes it, and `None` if no rule here says anything about it.
+    fn check(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.dir).ok()?;
+        let relative: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let relative = relative.join("/");
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(&relative, is_dir))
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// Returns `true` if `path` is ignored by `files`, which are ordered from the
+/// top of the walk down, so a deeper directory's rules override its parents'.
+pub fn is_ignored(files: &[IgnoreFile], path: &Path, is_dir: bool) -> bool {
+    // Git's own metadata is never worth searching.
+    if is_dir && path.file_name().is_some_and(|name| name == ".git") {
+        return true;
+    }
+
+    files
+        .iter()
+        .rev()
+        .find_map(|file| file.check(path, is_dir))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignored(contents: &str, path: &str, is_dir: bool) -> bool {
+        let files = [IgnoreFile::parse(Path::new("root"), contents)];
+        is_ignored(&files, &Path::new("root").join(path), is_dir)
+    }
+
+    #[test]
+    fn names_match_at_any_depth() {
+        assert!(ignored("target", "target", true));
+        assert!(ignored("*.log", "a/b/debug.log", false));
+        assert!(!ignored("*.log", "a/b/debug.txt", false));
+    }
+
+    #[test]
+    fn slashes_anchor_to_the_ignore_file() {
+        assert!(ignored("/build", "build", true));
+        assert!(!ignored("/build", "src/build", true));
+        assert!(ignored("docs/*.html", "docs/index.html", false));
+        assert!(!ignored("docs/*.html", "src/docs/index.html", false));
+    }
+
+    #[test]
+    fn trailing_slash_only_matches_directories() {
+        assert!(ignored("node_modules/", "node_modules", true));
+        assert!(!ignored("node_modules/", "node_modules", false));
+    }
+
+    #[test]
+    fn negation_and_comments() {
+        let contents = "# logs\n*.log\n!keep.log";
+        assert!(ignored(contents, "debug.log", false));
+        assert!(!ignored(contents, "keep.log", false));
+    }
+
+    #[test]
+    fn deeper_files_override_their_parents() {
+        let files = [
+            IgnoreFile::parse(Path::new("root"), "*.txt"),
+            IgnoreFile::parse(Path::new("root/docs"), "!*.txt"),
+        ];
+        assert!(is_ignored(&files, Path::new("root/notes.txt"), false));
+        assert!(!is_ignored(&files, Path::new("root/docs/guide.txt"), false));
+        assert!(is_ignored(&files, Path::new("root/.git"), true));
+    }
+}
@@ -1,10 +1,95 @@
 use std::error::Error;
-use std::{env, fs};
+use std::ops::Range;
+use std::path::Path;
+use std::thread;
 
+use crate::args::Arg;
+use crate::fuzzy::Fuzzy;
+use crate::progress::Progress;
+use std::io::{BufWriter, IsTerminal, Read, Write};
+use std::{env, fs, io};
+
+pub use crate::args::usage;
+pub use crate::builder::{ConfigBuilder, ConfigError};
+pub use crate::context::{search_with_context, ContextLine};
+pub use crate::encoding::Encoding;
+pub use crate::output::{Format, OutputFormatter};
+pub use crate::postprocess::SortBy;
+pub use crate::rcfile::{Case, Settings};
+pub use crate::regex::Regex;
+
+pub mod args;
+pub mod bench;
+pub mod builder;
+pub mod context;
+pub mod encoding;
+pub mod follow;
+pub mod fuzzy;
+pub mod glob;
+pub mod ignore;
+pub mod output;
+pub mod parallel;
+pub mod postprocess;
+pub mod progress;
+pub mod rcfile;
+pub mod regex;
+pub mod walk;
+
+/// The file name that stands for standard input.
+pub const STDIN_FILENAME: &str = "-";
+
+#[derive(Debug, Clone)]
 pub struct Config {
     pub query: String,
-    pub filename: String,
+    /// More patterns that a line has to match as well as `query` (`--all-of`).
+    pub all_of: Vec<String>,
+    /// Keep watching the file for new lines after reaching its end (`--follow`).
+    pub follow: bool,
+    /// Print the line number and the column of the first match (`--column`).
+    pub column: bool,
+    /// Print nothing; `run` only reports whether anything matched (`-q`).
+    pub quiet: bool,
+    /// Stop searching each file after this many matching lines (`-m`).
+    pub max_count: Option<usize>,
+    /// The files to search, with glob patterns already expanded. `-` means
+    /// standard input.
+    pub filenames: Vec<String>,
     pub ignore_case: bool,
+    /// Treat `query` as a regular expression instead of a plain substring.
+    pub regex: bool,
+    /// Also match text this many edits away from the query (`--fuzzy`).
+    pub fuzzy: Option<usize>,
+    /// Lines of context to print before each match (`-B`, or `-C` for both).
+    pub before_context: usize,
+    /// Lines of context to print after each match (`-A`, or `-C` for both).
+    pub after_context: usize,
+    /// Write results to this file instead of standard output (`-o`).
+    pub output: Option<String>,
+    /// Show which file is being searched on standard error (`--progress`).
+    pub progress: bool,
+    /// Print the files in this order instead of as they're searched (`--sort`).
+    pub sort: Option<SortBy>,
+    /// Print each distinct matching line only once (`--unique`).
+    pub unique: bool,
+    /// How results are printed (`--format`).
+    pub format: Format,
+    /// Highlight the matched text with ANSI colors.
+    pub color: bool,
+    /// Glob patterns for the files to keep when expanding directories and file
+    /// name patterns (`--include`). Empty means keep them all.
+    pub include: Vec<String>,
+    /// Glob patterns for files to skip when expanding directories and file
+    /// name patterns (`--exclude`).
+    pub exclude: Vec<String>,
+    /// How many directory levels to search below each directory (`--max-depth`).
+    pub max_depth: Option<usize>,
+    /// Search paths that `.gitignore` and `.ignore` files list, too
+    /// (`--no-ignore`).
+    pub no_ignore: bool,
+    /// Enter symbolically linked directories when walking (`--follow-symlinks`).
+    pub follow_symlinks: bool,
+    /// Decode every file with this codec (`--encoding`) instead of detecting it.
+    pub encoding: Option<Encoding>,
 }
 
 /// We use the `var` function from the `env` module to check to see if any value
@@ -19,7 +104,7 @@ pub struct Config {
 /// variable isn't set to anything, `is_ok` will return false and the program will perform a
 /// case-insensitive search.
 ///
-/// ```rust
+/// ```rust,ignore
 /// impl Config {
 ///     pub fn new(args: &[String]) -> Result<Config, &'static str> {
 ///         if args.len() < 3 {
@@ -52,54 +137,712 @@ pub struct Config {
 /// of the `impl Trait` syntax means that `args` can be any type that implements the `Iterator`
 /// type and returns `String` items.
 impl Config {
+    /// Options come from several layers. From lowest to highest priority:
+    /// 1. built-in defaults (case-sensitive, no color, no context)
+    /// 2. `~/.minigreprc`
+    /// 3. `.minigreprc` in the current directory
+    /// 4. the `IGNORE_CASE` environment variable
+    /// 5. command line arguments
+    ///
+    /// Case sensitivity is a single option that every layer can set, so the
+    /// precedence above decides between `ignore-case`/`smart-case` in a file,
+    /// `IGNORE_CASE`, and `-i`/`--ignore-case`, `-s`/`--smart-case`, and
+    /// `--case-sensitive` on the command line. Among the flags, the last one wins.
+    /// Smart case is resolved against the query once all layers are merged.
+    ///
+    /// See the `rcfile` module for the file format.
+    pub fn new(
+        args: impl Iterator<Item=String>,
+    ) -> Result<Config, String> {
+        Config::with_settings(args, Settings::load()?)
+    }
+
+    /// Like `new`, but with the settings normally read from `.minigreprc` files
+    /// passed in.
+    pub fn with_settings(
+        args: impl Iterator<Item=String>,
+        file_settings: Settings,
+    ) -> Result<Config, String> {
+        match Command::with_settings(args, file_settings)? {
+            Command::Search(config) => Ok(config),
+            // `main` goes through `Command` to handle these; a caller that only
+            // wants a `Config` gets the text it would have printed as the error.
+            Command::Help => Err(args::usage()),
+            Command::Version => Err(version()),
+            Command::Bench(_) => Err(String::from("bench is a command, not a search")),
+        }
+    }
+}
+
+/// What the command line asks minigrep to do.
+pub enum Command {
+    Search(Config),
+    /// `minigrep bench ...` times the search strategies.
+    Bench(bench::BenchOptions),
+    /// `--help` was given.
+    Help,
+    /// `--version` was given.
+    Version,
+}
+
+impl Command {
+    /// Parses the command line the same way as `Config::new`.
     pub fn new(
+        args: impl Iterator<Item=String>,
+    ) -> Result<Command, String> {
+        Command::with_settings(args, Settings::load()?)
+    }
+
+    /// Like `new`, but with the settings normally read from `.minigreprc` files
+    /// passed in.
+    pub fn with_settings(
         mut args: impl Iterator<Item=String>,
-    ) -> Result<Config, &'static str> {
+        file_settings: Settings,
+    ) -> Result<Command, String> {
         // Using `Iterator` Trait Methods Instead of Indexing
         args.next();  // Ignore the first command line argument.
 
-        let query = match args.next() {
-            Some(arg) => arg,
-            None => return Err("Didn't get a query string"),
-        };
+        // `bench` is only a subcommand as the very first argument; use
+        // `minigrep -- bench` to search for the word.
+        let mut args = args.peekable();
+        if args.next_if(|arg| arg == "bench").is_some() {
+            return Ok(Command::Bench(bench::BenchOptions::parse(args)?));
+        }
 
-        let filename = match args.next() {
-            Some(arg) => arg,
-            None => return Err("Didn't get a file name"),
+        // The environment variable is its own layer, between the files and the
+        // command line.
+        let env_settings = Settings {
+            case: env::var("IGNORE_CASE").ok().map(|_| Case::Insensitive),
+            ..Settings::default()
         };
 
-        let ignore_case = env::var("IGNORE_CASE").is_ok();
+        // Options may appear anywhere; everything else is positional. See the
+        // `args` module for the spellings the parser understands. The options
+        // that can also come from the other layers are collected in
+        // `cli_settings`; the rest go straight to the builder.
+        let mut builder = ConfigBuilder::new();
+        let mut cli_settings = Settings::default();
+        let mut before_context = None;
+        let mut after_context = None;
+        let mut progress = None;
+        let mut to_file = false;
+        let mut all_of = Vec::new();
+        let mut positional = Vec::new();
+        let mut parser = args::Parser::new(args);
+        while let Some(arg) = parser.next() {
+            let (name, value) = match arg? {
+                Arg::Positional(arg) => {
+                    positional.push(arg);
+                    continue;
+                }
+                Arg::Option(name, value) => (name, value.unwrap_or_default()),
+            };
+            let number = |what: &str| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| format!("--{} expects a number of {}, not {}", name, what, value))
+            };
+
+            match name {
+                "help" => return Ok(Command::Help),
+                "version" => return Ok(Command::Version),
+                "regex" => builder = builder.regex(true),
+                "fuzzy" => builder = builder.fuzzy(number("edits")?),
+                "no-ignore" => builder = builder.no_ignore(true),
+                "follow-symlinks" => builder = builder.follow_symlinks(true),
+                "column" => builder = builder.column(true),
+                "quiet" => builder = builder.quiet(true),
+                "format" => builder = builder.format(value.parse()?),
+                "sort" => builder = builder.sort(value.parse()?),
+                "unique" => builder = builder.unique(true),
+                "output" => {
+                    builder = builder.output(&value);
+                    to_file = true;
+                }
+                "progress" => progress = Some(true),
+                "no-progress" => progress = Some(false),
+                "follow" => builder = builder.follow(true),
+                "encoding" => builder = builder.encoding(value.parse()?),
+                "max-count" => builder = builder.max_count(number("matches")?),
+                "max-depth" => builder = builder.max_depth(number("levels")?),
+                "include" => builder = builder.include(&value),
+                "all-of" => {
+                    all_of.extend(parser.values());
+                    if all_of.is_empty() {
+                        return Err(String::from("--all-of expects at least one pattern"));
+                    }
+                }
+                "ignore-case" => cli_settings.case = Some(Case::Insensitive),
+                "smart-case" => cli_settings.case = Some(Case::Smart),
+                "case-sensitive" => cli_settings.case = Some(Case::Sensitive),
+                "color" => cli_settings.color = Some(true),
+                "no-color" => cli_settings.color = Some(false),
+                "exclude" => cli_settings.exclude.push(value),
+                "after-context" => after_context = Some(number("lines")?),
+                "before-context" => before_context = Some(number("lines")?),
+                "context" => {
+                    let lines = number("lines")?;
+                    after_context = Some(lines);
+                    before_context = Some(lines);
+                }
+                // Every name in `args::OPTIONS` is handled above.
+                _ => unreachable!("unhandled option --{}", name),
+            }
+        }
+
+        let settings = file_settings.merge(env_settings).merge(cli_settings);
+        builder = builder
+            .case(settings.case.unwrap_or(Case::Sensitive))
+            .color(settings.color.unwrap_or(false))
+            .before_context(before_context.or(settings.context).unwrap_or(0))
+            .after_context(after_context.or(settings.context).unwrap_or(0));
+        // Unless asked, only show progress where someone can see it and it
+        // won't end up in the middle of the results: on a terminal that the
+        // matches aren't being printed to.
+        let progress = progress.unwrap_or_else(|| {
+            io::stderr().is_terminal() && (to_file || !io::stdout().is_terminal())
+        });
+        builder = builder.progress(progress);
+        for pattern in &settings.exclude {
+            builder = builder.exclude(pattern);
+        }
+
+        // With `--all-of`, the patterns came with the option and every
+        // positional argument is a file name.
+        let mut positional = positional.iter();
+        let mut patterns = all_of.iter();
+        match patterns.next().or_else(|| positional.next()) {
+            Some(query) => builder = builder.query(query),
+            None => return Err(ConfigError::MissingQuery.to_string()),
+        }
+        for pattern in patterns {
+            builder = builder.all_of(pattern);
+        }
+        for path in positional {
+            builder = builder.path(path);
+        }
+
+        let config = builder.build().map_err(|e| e.to_string())?;
+        Ok(Command::Search(config))
+    }
+}
+
+/// The text printed by `--version`.
+pub fn version() -> String {
+    format!("minigrep {}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Runs the search and returns whether any line matched.
+pub fn run(config: Config) -> Result<bool, Box<dyn Error>> {
+    // Printing to a terminal can take longer than the search itself, so when
+    // there are a lot of matches it pays to send them to a file. Either way the
+    // writes are buffered; `finish` flushes them.
+    let writer: Box<dyn Write> = match &config.output {
+        Some(path) => {
+            let file = fs::File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+            Box::new(BufWriter::new(file))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let mut out = output::formatter(&config, writer);
+
+    if config.follow {
+        follow_file(&config, &config.filenames[0], out.as_mut())?;
+        return Ok(true);
+    }
+
+    // A single file goes by too quickly to need a progress line.
+    let mut progress = if config.progress && config.filenames.len() > 1 {
+        Some(Progress::stderr(config.filenames.len()))
+    } else {
+        None
+    };
+
+    // `--sort` and `--unique` need every match before printing any, and the
+    // matches borrow their lines, so every file's contents are kept until then.
+    let post_process = !config.quiet && (config.sort.is_some() || config.unique);
+    let mut kept = Vec::new();
+
+    let mut matched = false;
+    for filename in &config.filenames {
+        if let Some(progress) = &mut progress {
+            progress.start(display_name(filename))?;
+        }
+        let contents = read_contents(filename, config.encoding)?;
+
+        if post_process {
+            kept.push((display_name(filename), contents));
+            continue;
+        }
+
+        // With `-q` the first match anywhere answers the question, so we don't
+        // even look at the rest of the line, let alone the other files.
+        if config.quiet {
+            if match_iter(&config, &contents)?.next().is_some() {
+                matched = true;
+                break;
+            }
+            continue;
+        }
+
+        matched |= search_contents(&config, &contents, display_name(filename), out.as_mut())?;
+    }
+
+    if let Some(progress) = &mut progress {
+        progress.finish()?;
+    }
+
+    if post_process {
+        let mut files = Vec::with_capacity(kept.len());
+        for (path, contents) in &kept {
+            files.push((*path, find_matches(&config, contents)?));
+        }
+        postprocess::apply(&mut files, config.sort, config.unique);
+
+        for (path, matches) in files {
+            out.start_file(path)?;
+            for m in matches {
+                out.write_match(&m.with_path(path))?;
+                matched = true;
+            }
+        }
+    }
+
+    out.finish()?;
+    Ok(matched)
+}
+
+/// Writes the lines of `filename` that match, then keeps polling it and writes
+/// new matching lines as they're appended, until `-m` matches have been found
+/// or the program is interrupted. Context lines aren't written in this mode.
+fn follow_file(config: &Config, filename: &str, out: &mut dyn OutputFormatter) -> Result<(), Box<dyn Error>> {
+    let is_match = line_matcher(config)?;
+    let finders = finders(config)?;
+
+    let mut follower = follow::Follower::open(Path::new(filename), config.encoding)?;
+    let mut found = 0;
+    out.start_file(filename)?;
+    loop {
+        for (number, line) in follower.poll()? {
+            if !is_match(&line) {
+                continue;
+            }
+
+            out.write_match(&Match {
+                path: Some(filename.to_string()),
+                line_number: number,
+                line: &line,
+                ranges: match_ranges(&finders, &line),
+                distance: match_distance(&finders, &line),
+            })?;
+
+            found += 1;
+            if config.max_count == Some(found) {
+                return Ok(out.finish()?);
+            }
+        }
+
+        // Output to a pipe is buffered, so push out what we have before waiting.
+        out.finish()?;
+        thread::sleep(follow::POLL_INTERVAL);
+    }
+}
+
+/// Searches one file's contents and hands the results to `out`. Returns whether
+/// any line matched.
+fn search_contents(
+    config: &Config,
+    contents: &str,
+    path: &str,
+    out: &mut dyn OutputFormatter,
+) -> Result<bool, Box<dyn Error>> {
+    out.start_file(path)?;
+
+    if config.before_context > 0 || config.after_context > 0 {
+        let finders = finders(config)?;
+        let is_match = line_matcher(config)?;
+        let lines = context::search_with_context_max(
+            contents,
+            is_match,
+            config.before_context,
+            config.after_context,
+            config.max_count,
+        );
+
+        let matched = lines.iter().any(|line| matches!(line, ContextLine::Match { .. }));
+        for line in lines {
+            match line {
+                ContextLine::Match { number, text } => out.write_match(&Match {
+                    path: Some(path.to_string()),
+                    line_number: number,
+                    line: text,
+                    ranges: match_ranges(&finders, text),
+                    distance: match_distance(&finders, text),
+                })?,
+                ContextLine::Context { number, text } => out.write_context(path, number, text)?,
+                ContextLine::Separator => out.write_separator()?,
+            }
+        }
+
+        return Ok(matched);
+    }
+
+    // A big file is split up and searched on every CPU. The matches have to be
+    // collected before they're printed, so smaller files are streamed instead.
+    if parallel::threads_for(contents) > 1 {
+        let matches = find_matches(config, contents)?;
+        for m in &matches {
+            out.write_match(&m.clone().with_path(path))?;
+        }
+        return Ok(!matches.is_empty());
+    }
+
+    let mut matched = false;
+    for m in match_iter(config, contents)? {
+        out.write_match(&m.with_path(path))?;
+        matched = true;
+    }
+
+    Ok(matched)
+}
+
+impl Config {
+    /// `query` followed by the `--all-of` patterns.
+    fn patterns(&self) -> impl Iterator<Item=&String> {
+        std::iter::once(&self.query).chain(&self.all_of)
+    }
+
+    /// Whether the output shows where in each line the matches are: highlighted,
+    /// as a column, or as JSON `ranges`. Finding them all costs more than finding
+    /// out whether a line matches, so it's skipped when nothing would show them.
+    fn needs_ranges(&self) -> bool {
+        self.color || self.column || self.format == Format::Json
+    }
+}
+
+/// Finds the lines the configuration asks for, matching every `--all-of`
+/// pattern in the same pass so line numbers and file names stay intact.
+///
+/// The lines are produced lazily, so with `-m` or `-q` we stop reading
+/// `contents` as soon as we have enough of them instead of scanning to the end.
+fn match_iter<'a>(
+    config: &Config,
+    contents: &'a str,
+) -> Result<impl Iterator<Item=Match<'a>>, regex::Error> {
+    let is_match = line_matcher(config)?;
+    let finders = finders(config)?;
+
+    let matches = contents
+        .lines()
+        .enumerate()
+        .filter(move |(_, line)| is_match(line))
+        .map(move |(i, line)| Match {
+            path: None,
+            line_number: i + 1,
+            line,
+            ranges: match_ranges(&finders, line),
+            distance: match_distance(&finders, line),
+        })
+        .take(config.max_count.unwrap_or(usize::MAX));
+
+    Ok(matches)
+}
+
+/// Every match in `contents`, using several threads if it's big enough.
+fn find_matches<'a>(config: &Config, contents: &'a str) -> Result<Vec<Match<'a>>, regex::Error> {
+    match parallel::threads_for(contents) {
+        1 => Ok(match_iter(config, contents)?.collect()),
+        threads => parallel::search(config, contents, threads),
+    }
+}
+
+/// Finds where one pattern matches within a line, to highlight it.
+enum Finder {
+    /// A plain, case-sensitive query, which doesn't need a regex to find.
+    Literal(String),
+    Regex(Regex),
+    Fuzzy(Fuzzy),
+}
+
+impl Finder {
+    fn find_iter(&self, line: &str) -> Vec<Range<usize>> {
+        match self {
+            Finder::Literal(query) => line
+                .match_indices(query.as_str())
+                .map(|(start, found)| start..start + found.len())
+                .collect(),
+            Finder::Regex(re) => re.find_iter(line),
+            Finder::Fuzzy(fuzzy) => fuzzy.find_iter(line).into_iter().map(|m| m.range).collect(),
+        }
+    }
+}
+
+/// A `Finder` for each of the config's patterns, or none at all if the output
+/// doesn't show where the matches are.
+fn finders(config: &Config) -> Result<Vec<Finder>, regex::Error> {
+    if !config.needs_ranges() {
+        return Ok(Vec::new());
+    }
+
+    config
+        .patterns()
+        .map(|pattern| match config.fuzzy {
+            Some(max_edits) => Ok(Finder::Fuzzy(Fuzzy::new(pattern, max_edits, config.ignore_case))),
+            None if !config.regex && !config.ignore_case => Ok(Finder::Literal(pattern.clone())),
+            None => match_regex(config, pattern).map(Finder::Regex),
+        })
+        .collect()
+}
+
+/// The ranges of `line` that any of `finders` matches, in order and without
+/// overlaps, ready for `highlight`.
+fn match_ranges(finders: &[Finder], line: &str) -> Vec<Range<usize>> {
+    merge_ranges(finders.iter().flat_map(|finder| finder.find_iter(line)).collect())
+}
 
-        Ok(Config {
-            query,
-            filename,
-            ignore_case,
+/// For a fuzzy search, the edits needed for the line to match: the most that
+/// any one pattern needs, since the line has to match them all.
+fn match_distance(finders: &[Finder], line: &str) -> Option<usize> {
+    finders
+        .iter()
+        .filter_map(|finder| match finder {
+            Finder::Fuzzy(fuzzy) => fuzzy.distance(line),
+            Finder::Literal(_) | Finder::Regex(_) => None,
         })
+        .max()
+}
+
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| (range.start, range.end));
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
     }
+    merged
 }
 
+/// A regular expression that finds exactly what the configured search matches
+/// for `query`, used to locate the matched text within a line.
+fn match_regex(config: &Config, query: &str) -> Result<Regex, regex::Error> {
+    let pattern = if config.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+
+    if config.ignore_case {
+        Regex::new_case_insensitive(&pattern)
+    } else {
+        Regex::new(&pattern)
+    }
+}
 
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let contents = fs::read_to_string(config.filename)?;
+fn display_name(filename: &str) -> &str {
+    if filename == STDIN_FILENAME {
+        "(standard input)"
+    } else {
+        filename
+    }
+}
 
-    let results = if config.ignore_case {
-        search_case_insensitive(&config.query, &contents)
+/// Reads the whole file, or all of standard input when `filename` is `-`, and
+/// transcodes it to UTF-8 (see the `encoding` module).
+fn read_contents(filename: &str, encoding: Option<Encoding>) -> Result<String, Box<dyn Error>> {
+    let bytes = if filename == STDIN_FILENAME {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        bytes
     } else {
-        search(&config.query, &contents)
+        fs::read(filename)?
     };
 
-    for line in results {
-        println!("{}", line);
+    encoding::decode(&bytes, encoding).map_err(|e| format!("{}: {}", display_name(filename), e).into())
+}
+
+type LineMatcher = Box<dyn Fn(&str) -> bool>;
+
+/// Builds a predicate that answers "does this line match?" the same way
+/// `match_iter` would.
+fn line_matcher(config: &Config) -> Result<LineMatcher, regex::Error> {
+    let matchers = config
+        .patterns()
+        .map(|query| query_matcher(config, query))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Box::new(move |line| matchers.iter().all(|is_match| is_match(line))))
+}
+
+/// Like `line_matcher`, for a single pattern.
+fn query_matcher(config: &Config, query: &str) -> Result<LineMatcher, regex::Error> {
+    if let Some(max_edits) = config.fuzzy {
+        let fuzzy = Fuzzy::new(query, max_edits, config.ignore_case);
+        return Ok(Box::new(move |line| fuzzy.is_match(line)));
+    }
+
+    let query = query.to_string();
+    let matcher: LineMatcher = match (config.regex, config.ignore_case) {
+        (true, true) => {
+            let re = Regex::new_case_insensitive(&query)?;
+            Box::new(move |line| re.is_match(line))
+        }
+        (true, false) => {
+            let re = Regex::new(&query)?;
+            Box::new(move |line| re.is_match(line))
+        }
+        (false, true) => {
+            let query = query.to_lowercase();
+            Box::new(move |line| line.to_lowercase().contains(&query))
+        }
+        (false, false) => Box::new(move |line| line.contains(&query)),
+    };
+
+    Ok(matcher)
+}
+
+/// A matching line, with enough detail that programs using minigrep as a
+/// library don't have to parse its printed output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match<'a> {
+    /// The file the line came from. The `search*` functions only see the
+    /// contents, so they leave this as `None`; use `with_path` to fill it in.
+    pub path: Option<String>,
+    /// The line number, counting from 1.
+    pub line_number: usize,
+    /// The whole line, without its line ending.
+    pub line: &'a str,
+    /// The byte ranges within `line` of every non-overlapping match.
+    pub ranges: Vec<Range<usize>>,
+    /// With `--fuzzy`, how many edits the closest match in the line is from
+    /// the query. `None` for exact searches.
+    pub distance: Option<usize>,
+}
+
+impl<'a> Match<'a> {
+    pub fn with_path(self, path: &str) -> Match<'a> {
+        Match {
+            path: Some(path.to_string()),
+            ..self
+        }
+    }
+
+    /// The column where the first match starts, counting characters from 1.
+    pub fn column(&self) -> Option<usize> {
+        self.ranges.first().map(|range| column_of(self.line, range.start))
     }
+}
+
+/// Converts a byte offset into `line` into a 1-based character column, so a
+/// line starting with `é` still puts the next character in column 2.
+fn column_of(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// Runs `find` on every line, lazily, and keeps the lines where it found
+/// something.
+fn match_lines<'a, F>(contents: &'a str, find: F) -> impl Iterator<Item=Match<'a>>
+where
+    F: Fn(&str) -> Vec<Range<usize>>,
+{
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(move |(i, line)| {
+            let ranges = find(line);
+            if ranges.is_empty() {
+                None
+            } else {
+                Some(Match {
+                    path: None,
+                    line_number: i + 1,
+                    line,
+                    ranges,
+                    distance: None,
+                })
+            }
+        })
+}
 
-    Ok(())
+/// Finds the lines containing `query` one at a time, so a caller can stop after
+/// the first match or stream results without collecting them all.
+pub fn search_iter<'a, 'q>(query: &'q str, contents: &'a str) -> impl Iterator<Item=Match<'a>> + 'q
+where
+    'a: 'q,
+{
+    match_lines(contents, move |line| {
+        // An empty query matches every line, but `match_indices` would report an
+        // empty match between every character.
+        if query.is_empty() {
+            return vec![Range { start: 0, end: 0 }];
+        }
+
+        line.match_indices(query)
+            .map(|(start, found)| start..start + found.len())
+            .collect()
+    })
+}
+
+pub fn search_matches<'a>(query: &str, contents: &'a str) -> Vec<Match<'a>> {
+    search_iter(query, contents).collect()
+}
+
+pub fn search_matches_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<Match<'a>> {
+    let lowercase_query = query.to_lowercase();
+    // Lowercasing can change a line's length, so offsets into the lowercased
+    // line aren't offsets into the original. A case-insensitive regex finds the
+    // ranges in the original line instead.
+    let re = Regex::new_case_insensitive(&regex::escape(query))
+        .expect("an escaped query is always a valid pattern");
+
+    match_lines(contents, move |line| {
+        if line.to_lowercase().contains(&lowercase_query) {
+            let ranges = re.find_iter(line);
+            if ranges.is_empty() {
+                vec![Range { start: 0, end: 0 }]
+            } else {
+                ranges
+            }
+        } else {
+            Vec::new()
+        }
+    })
+    .collect()
+}
+
+/// Like `search_matches`, but `pattern` is a regular expression such as
+/// `^fn\s+\w+`. See the `regex` module for the supported syntax.
+pub fn search_regex_matches<'a>(
+    pattern: &str,
+    contents: &'a str,
+) -> Result<Vec<Match<'a>>, regex::Error> {
+    let re = Regex::new(pattern)?;
+
+    Ok(match_lines(contents, move |line| re.find_iter(line)).collect())
+}
+
+pub fn search_regex_matches_case_insensitive<'a>(
+    pattern: &str,
+    contents: &'a str,
+) -> Result<Vec<Match<'a>>, regex::Error> {
+    let re = Regex::new_case_insensitive(pattern)?;
+
+    Ok(match_lines(contents, move |line| re.find_iter(line)).collect())
+}
+
+/// The string-returning functions below keep the original API: each is a thin
+/// wrapper that drops everything but the line from the matches.
+fn lines<'a>(matches: Vec<Match<'a>>) -> Vec<&'a str> {
+    matches.into_iter().map(|m| m.line).collect()
 }
 
 /// ### Iterating Through Lines with the `lines` Method
 ///
 /// The `lines` method returns an iterator.
 ///
-/// ```rust
+/// ```rust,ignore
 /// pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
 ///     let mut results = Vec::new();
 ///
@@ -119,27 +862,43 @@ pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
 /// state to make code clearer. Removing the mutable state might enable a future
 /// enhancement to make searching happen in parallel, because we wouldn't have
 /// to manage concurrent access to the `results` vector.
+///
+/// ```rust,ignore
+/// pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+///     contents
+///         .lines()
+///         .filter(|line| line.contains(query))
+///         .collect()
+/// }
+/// ```
+///
+/// The same iterator adaptor chain now lives in `match_lines`, which also
+/// records where each match is.
 pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    contents
-        .lines()
-        .filter(|line| line.contains(query))
-        .collect()
+    lines(search_matches(query, contents))
 }
 
 pub fn search_case_insensitive<'a>(
     query: &str,
     contents: &'a str,
 ) -> Vec<&'a str> {
-    let query = query.to_lowercase();
-    let mut results = Vec::new();
+    lines(search_matches_case_insensitive(query, contents))
+}
 
-    for line in contents.lines() {
-        if line.to_lowercase().contains(&query) {
-            results.push(line)
-        }
-    }
+/// Like `search`, but `pattern` is a regular expression such as `^fn\s+\w+`.
+/// See the `regex` module for the supported syntax.
+pub fn search_regex<'a>(
+    pattern: &str,
+    contents: &'a str,
+) -> Result<Vec<&'a str>, regex::Error> {
+    search_regex_matches(pattern, contents).map(lines)
+}
 
-    results
+pub fn search_regex_case_insensitive<'a>(
+    pattern: &str,
+    contents: &'a str,
+) -> Result<Vec<&'a str>, regex::Error> {
+    search_regex_matches_case_insensitive(pattern, contents).map(lines)
 }
 
 /// Now we add the search logic to the program using the test-driven development (TDD)
@@ -149,6 +908,7 @@ pub fn search_case_insensitive<'a>(
 /// 3. Refactor the code we just added or changed and make sure the tests continue to pass.
 /// 4. Repeat from step 1!
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod tests {
     use super::*;
 
@@ -174,4 +934,315 @@ Trust me.";
 
         assert_eq!(vec!["Rust:", "Trust me."], search_case_insensitive(query, contents));
     }
+
+    #[test]
+    fn regex() {
+        let contents = "\
+fn main() {
+    let x = 1;
+}
+pub fn helper() {}";
+
+        assert_eq!(vec!["fn main() {"], search_regex(r"^fn\s+\w+", contents).unwrap());
+        assert_eq!(
+            vec!["fn main() {", "pub fn helper() {}"],
+            search_regex_case_insensitive(r"\bFN [a-z]+\(", contents).unwrap()
+        );
+    }
+
+    #[test]
+    fn matches_record_line_numbers_and_ranges() {
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Trust me, rust.";
+
+        let matches = search_matches_case_insensitive("rust", contents);
+        assert_eq!(
+            vec![
+                Match {
+                    path: None,
+                    line_number: 1,
+                    line: "Rust:",
+                    ranges: vec![0..4],
+                    distance: None,
+                },
+                Match {
+                    path: None,
+                    line_number: 4,
+                    line: "Trust me, rust.",
+                    ranges: vec![1..5, 10..14],
+                    distance: None,
+                },
+            ],
+            matches
+        );
+
+        let matches = search_regex_matches(r"\w+\.$", contents).unwrap();
+        assert_eq!(vec![2, 3, 4], matches.iter().map(|m| m.line_number).collect::<Vec<_>>());
+        assert_eq!(vec![12..23], matches[0].ranges);
+
+        let m = search_matches("three", contents).remove(0).with_path("poem.txt");
+        assert_eq!(Some(String::from("poem.txt")), m.path);
+        assert_eq!(vec![5..10], m.ranges);
+    }
+
+    #[test]
+    fn context_flags() {
+        let args = ["minigrep", "-C", "2", "to", "poem.txt", "-A", "1"].map(String::from);
+
+        let config = Config::with_settings(args.into_iter(), Settings::default()).unwrap();
+        assert_eq!((2, 1), (config.before_context, config.after_context));
+        assert_eq!("to", config.query);
+        assert_eq!(vec!["poem.txt"], config.filenames);
+
+        let args = ["minigrep", "-B", "many", "to", "poem.txt"].map(String::from);
+        assert!(Config::with_settings(args.into_iter(), Settings::default()).is_err());
+    }
+
+    #[test]
+    fn missing_file_name_means_stdin() {
+        let args = ["minigrep", "to"].map(String::from);
+        let config = Config::with_settings(args.into_iter(), Settings::default()).unwrap();
+        assert_eq!(vec![STDIN_FILENAME], config.filenames);
+
+        let args = ["minigrep", "to", "-"].map(String::from);
+        let config = Config::with_settings(args.into_iter(), Settings::default()).unwrap();
+        assert_eq!(vec![STDIN_FILENAME], config.filenames);
+    }
+
+    #[test]
+    fn file_names_and_globs() {
+        let args = ["minigrep", "to", "poem.txt", "src/*.rs", "-"].map(String::from);
+
+        let config = Config::with_settings(args.into_iter(), Settings::default()).unwrap();
+        assert_eq!("poem.txt", config.filenames[0]);
+        assert!(config.filenames.contains(&String::from("src/lib.rs")));
+        assert_eq!(Some(&String::from("-")), config.filenames.last());
+
+        let args = ["minigrep", "to", "src/*.nothing"].map(String::from);
+        assert!(Config::with_settings(args.into_iter(), Settings::default()).is_err());
+    }
+
+    #[test]
+    fn command_line_overrides_rc_file_settings() {
+        let file_settings = Settings {
+            case: Some(Case::Sensitive),
+            color: Some(true),
+            context: Some(2),
+            exclude: vec![String::from("*.txt")],
+        };
+        let args = ["minigrep", "-i", "-A", "0", "--no-color", "to", "*.txt"].map(String::from);
+
+        let error = Config::with_settings(args.clone().into_iter(), file_settings.clone()).unwrap_err();
+        // The walk leaves out every file, so there's nothing to search.
+        assert_eq!("No files match *.txt", error);
+
+        let args = args.map(|arg| if arg == "*.txt" { String::from("-") } else { arg });
+        let config = Config::with_settings(args.into_iter(), file_settings).unwrap();
+        assert!(config.ignore_case);
+        assert!(!config.color);
+        assert_eq!((2, 0), (config.before_context, config.after_context));
+    }
+
+    #[test]
+    fn smart_case_depends_on_the_query() {
+        let config = |args: &[&str], file_case| {
+            let args = ["minigrep"].iter().chain(args).map(|arg| arg.to_string());
+            let settings = Settings {
+                case: file_case,
+                ..Settings::default()
+            };
+            Config::with_settings(args, settings).unwrap()
+        };
+
+        assert!(config(&["--smart-case", "rust", "-"], None).ignore_case);
+        assert!(!config(&["--smart-case", "Rust", "-"], None).ignore_case);
+        // `\S` is regex syntax, not an uppercase letter.
+        assert!(config(&["-s", "--regex", r"\Srust", "-"], None).ignore_case);
+
+        // The command line overrides the file, and the last flag wins.
+        assert!(!config(&["-s", "Rust", "-"], Some(Case::Insensitive)).ignore_case);
+        assert!(config(&["-s", "-i", "Rust", "-"], None).ignore_case);
+        assert!(!config(&["-i", "--case-sensitive", "rust", "-"], None).ignore_case);
+        assert!(config(&["rust", "-"], Some(Case::Smart)).ignore_case);
+    }
+
+    #[test]
+    fn encoding_flag() {
+        let args = ["minigrep", "--encoding", "latin1", "to", "poem.txt"];
+        let config = Config::with_settings(args.iter().map(|arg| arg.to_string()), Settings::default()).unwrap();
+        assert_eq!(Some(Encoding::Latin1), config.encoding);
+
+        let args = ["minigrep", "--encoding", "ebcdic", "to", "poem.txt"];
+        assert!(Config::with_settings(args.iter().map(|arg| arg.to_string()), Settings::default()).is_err());
+    }
+
+    #[test]
+    fn all_of_keeps_lines_matching_every_pattern() {
+        let args = ["minigrep", "--all-of", "safe", "fast", "--", "poem.txt"];
+        let mut config = Config::with_settings(args.iter().map(|arg| arg.to_string()), Settings::default()).unwrap();
+        assert_eq!("safe", config.query);
+        assert_eq!(vec![String::from("fast")], config.all_of);
+        assert_eq!(vec![String::from("poem.txt")], config.filenames);
+
+        let contents = "\
+Rust:
+safe, fast, productive.
+safe and sound.
+Fast food.";
+        config.ignore_case = true;
+        config.color = true;
+        let matches = match_iter(&config, contents).unwrap().collect::<Vec<_>>();
+        assert_eq!(1, matches.len());
+        assert_eq!(2, matches[0].line_number);
+        assert_eq!(vec![0..4, 6..10], matches[0].ranges);
+
+        let is_match = line_matcher(&config).unwrap();
+        assert!(is_match("Fast and SAFE"));
+        assert!(!is_match("safe and sound."));
+    }
+
+    #[test]
+    fn max_count_stops_early() {
+        let args = ["minigrep", "-m", "2", "e", "poem.txt"];
+        let config = Config::with_settings(args.iter().map(|arg| arg.to_string()), Settings::default()).unwrap();
+        assert_eq!(Some(2), config.max_count);
+
+        let matches = match_iter(&config, "one\ntwo\nthree\nfour\nfive").unwrap().collect::<Vec<_>>();
+        let numbers: Vec<usize> = matches.iter().map(|m| m.line_number).collect();
+        assert_eq!(vec![1, 3], numbers);
+
+        let args = ["minigrep", "-m", "lots", "e", "poem.txt"];
+        assert!(Config::with_settings(args.iter().map(|arg| arg.to_string()), Settings::default()).is_err());
+    }
+
+    #[test]
+    fn columns_count_characters_from_one() {
+        let contents = "Rust:\ncafé, safe, fast";
+        let matches = search_matches("safe", contents);
+        assert_eq!(Some(7), matches[0].column());
+        assert_eq!(Some(1), search_matches("Rust", contents)[0].column());
+    }
+
+    #[test]
+    fn ranges_are_only_found_when_the_output_shows_them() {
+        let contents = "café xx and xx";
+        let plain = ConfigBuilder::new().query("xx").build().unwrap();
+        let matches: Vec<Match> = match_iter(&plain, contents).unwrap().collect();
+        assert_eq!(1, matches.len());
+        assert!(matches[0].ranges.is_empty());
+
+        let colored = ConfigBuilder::new().query("xx").color(true).build().unwrap();
+        let matches: Vec<Match> = match_iter(&colored, contents).unwrap().collect();
+        assert_eq!(vec![6..8, 13..15], matches[0].ranges);
+    }
+
+    #[test]
+    fn follow_needs_a_single_file() {
+        let config = |args: &[&str]| {
+            let args = ["minigrep", "--follow", "error"].iter().chain(args).map(|arg| arg.to_string());
+            Config::with_settings(args, Settings::default())
+        };
+
+        assert!(config(&["app.log"]).unwrap().follow);
+        assert!(config(&["app.log", "other.log"]).is_err());
+        assert!(config(&[]).is_err());
+    }
+
+    #[test]
+    fn output_goes_to_a_file() {
+        let dir = env::temp_dir().join(format!("minigrep-output-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("matches.txt");
+        let path = path.to_str().unwrap();
+
+        let args = ["minigrep", "-o", path, "frog", "poem.txt"].map(String::from);
+        let config = Config::with_settings(args.into_iter(), Settings::default()).unwrap();
+        assert!(run(config).unwrap());
+        assert_eq!("How public, like a frog\n", fs::read_to_string(path).unwrap());
+
+        let missing = dir.join("no-such-dir").join("matches.txt");
+        let config = ConfigBuilder::new()
+            .query("frog")
+            .path("poem.txt")
+            .output(missing.to_str().unwrap())
+            .build()
+            .unwrap();
+        assert!(run(config).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn help_version_and_double_dash() {
+        let command = |args: &[&str]| {
+            let args = ["minigrep"].iter().chain(args).map(|arg| arg.to_string());
+            Command::with_settings(args, Settings::default())
+        };
+
+        assert!(matches!(command(&["--help"]), Ok(Command::Help)));
+        assert!(matches!(command(&["-V", "query"]), Ok(Command::Version)));
+        match command(&["-i", "--", "-v", "--help"]) {
+            Ok(Command::Search(config)) => {
+                assert_eq!("-v", config.query);
+                assert_eq!(vec![String::from("--help")], config.filenames);
+                assert!(config.ignore_case);
+            }
+            _ => panic!("expected a search"),
+        }
+        assert_eq!(Some(String::from("unknown flag --foo")), command(&["--foo", "q"]).err());
+        assert!(command(&["-m", "lots", "q"]).is_err());
+    }
+
+    #[test]
+    fn search_iter_is_lazy() {
+        let contents = "safe\nfast\nsafe again";
+        let mut matches = search_iter("safe", contents);
+        assert_eq!(Some(1), matches.next().map(|m| m.line_number));
+        assert_eq!(Some(3), matches.next().map(|m| m.line_number));
+        assert_eq!(None, matches.next());
+
+        // Stopping early never looks at the rest of the input.
+        let endless = "needle\n".repeat(1_000);
+        assert_eq!(1, search_iter("needle", &endless).take(1).count());
+    }
+
+    #[test]
+    fn fuzzy_matches_report_their_distance() {
+        let contents = "connection refused\nconection reset\nall good\n";
+        let config = ConfigBuilder::new()
+            .query("connection")
+            .fuzzy(1)
+            .format(Format::Json)
+            .build()
+            .unwrap();
+
+        let matches: Vec<Match> = match_iter(&config, contents).unwrap().collect();
+        assert_eq!(2, matches.len());
+        assert_eq!((Some(0), vec![0..10]), (matches[0].distance, matches[0].ranges.clone()));
+        assert_eq!((Some(1), vec![0..9]), (matches[1].distance, matches[1].ranges.clone()));
+
+        let args = ["minigrep", "--fuzzy", "2", "--regex", "x+y", "poem.txt"].map(String::from);
+        assert!(Config::with_settings(args.into_iter(), Settings::default()).is_err());
+        let args = ["minigrep", "--fuzzy", "4", "frog", "poem.txt"].map(String::from);
+        assert_eq!(
+            "--fuzzy 4 would match every line, since \"frog\" has only 4 characters",
+            Config::with_settings(args.into_iter(), Settings::default()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn merged_ranges_do_not_overlap() {
+        assert_eq!(vec![0..5, 7..8], merge_ranges(vec![7..8, 2..5, 0..3]));
+    }
+
+    #[test]
+    fn malformed_regex_is_a_config_error() {
+        let args = ["minigrep", "--regex", "(unclosed", "poem.txt"].map(String::from);
+
+        let error = Config::with_settings(args.into_iter(), Settings::default()).err().unwrap();
+        assert!(error.starts_with("Invalid regular expression"), "{}", error);
+    }
 }
\ No newline at end of file
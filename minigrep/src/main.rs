@@ -2,7 +2,7 @@
 
 use std::{env, process};
 
-use minigrep::Config;
+use minigrep::Command;
 
 fn main() {
     /// ## Accepting Command Line Arguments
@@ -55,7 +55,7 @@ fn main() {
     /// * Split our program into a *main.rs* and a *lib.rs* and move our program's logic to *lib.rs*.
     /// * As long as our command line parsing logic is small, it can remaining in *main.rs*.
     /// * When the command line parsing logic starts getting complicated, extract it from *main.rs*
-    /// and move it to *lib.rs*.
+    ///   and move it to *lib.rs*.
     ///
     /// The responsibilities that remain in the `main` function after this process should be limited
     /// to the following:
@@ -153,15 +153,46 @@ fn main() {
     /// ```
     ///
     /// ## Removing a `clone` Using an Iterator
-    let config = Config::new(env::args()).unwrap_or_else(|err| {
+    let command = Command::new(env::args()).unwrap_or_else(|err| {
         eprintln!("Problem parsing argument: {}", err);
+        eprintln!("Try 'minigrep --help' for more information.");
         process::exit(1);
     });
 
-    if let Err(e) = minigrep::run(config) {
-        eprintln!("Application error: {}", e);
+    // `--help` and `--version` aren't errors, so they print to stdout and exit
+    // successfully.
+    let config = match command {
+        Command::Search(config) => config,
+        Command::Help => {
+            print!("{}", minigrep::usage());
+            return;
+        }
+        Command::Version => {
+            println!("{}", minigrep::version());
+            return;
+        }
+        Command::Bench(options) => {
+            if let Err(e) = minigrep::bench::run(&options) {
+                eprintln!("Application error: {}", e);
+                process::exit(1);
+            }
+            return;
+        }
+    };
 
-        process::exit(1);
+    // With `-q` the exit status is the only output, like grep's.
+    let quiet = config.quiet;
+    match minigrep::run(config) {
+        Ok(matched) => {
+            if quiet && !matched {
+                process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Application error: {}", e);
+
+            process::exit(1);
+        }
     }
 }
 
@@ -0,0 +1,416 @@
+//! How results are written, chosen with `--format`.
+//!
+//! `run` finds the lines and hands each one to an `OutputFormatter`, which
+//! decides what it looks like:
+//! * `plain` prints `path:line` like grep (the default)
+//! * `json` prints one JSON object per line, for other programs to read
+//! * `grouped` prints each file's name once as a heading, then its matches
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::{Config, Match};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Plain,
+    Json,
+    Grouped,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Format, String> {
+        match name {
+            "plain" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            "grouped" => Ok(Format::Grouped),
+            _ => Err(format!(
+                "unknown format {} (expected plain, json, or grouped)",
+                name
+            )),
+        }
+    }
+}
+
+/// Receives the results of a search, one file at a time.
+///
+/// Every `path` is the name to show for the file, with standard input already
+/// spelled `(standard input)`. A `Match` passed in has its `path` filled in.
+pub trait OutputFormatter {
+    /// Called before any lines of a file are written.
+    fn start_file(&mut self, _path: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_match(&mut self, m: &Match) -> io::Result<()>;
+
+    /// A line printed only because it's near a match (`-A`, `-B`, `-C`).
+    fn write_context(&mut self, path: &str, number: usize, text: &str) -> io::Result<()>;
+
+    /// Written between groups of context lines that aren't adjacent.
+    fn write_separator(&mut self) -> io::Result<()>;
+
+    /// Called once everything has been written.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the formatter `config` asks for, writing to `out`.
+pub fn formatter<'w>(config: &Config, out: Box<dyn Write + 'w>) -> Box<dyn OutputFormatter + 'w> {
+    let style = Style {
+        color: config.color,
+        column: config.column,
+        // Like grep, name the file on each line only when there's more than one.
+        // Editors reading `--column` output as a quickfix list always need the
+        // name.
+        show_filenames: config.filenames.len() > 1 || config.column,
+    };
+
+    match config.format {
+        Format::Plain => Box::new(Plain { out, style }),
+        Format::Json => Box::new(Json { out }),
+        Format::Grouped => Box::new(Grouped {
+            out,
+            style,
+            current: None,
+            files: 0,
+        }),
+    }
+}
+
+/// The options that affect how a line of text output looks.
+#[derive(Debug, Clone, Copy)]
+struct Style {
+    color: bool,
+    column: bool,
+    show_filenames: bool,
+}
+
+impl Style {
+    /// The line number and column, when `--column` asks for them: `12:7:` before
+    /// a match and `13-` before a context line.
+    fn location(&self, separator: char, number: usize, column: Option<usize>) -> String {
+        let mut location = String::new();
+        if self.column {
+            write!(location, "{}{}", number, separator).unwrap();
+            if let Some(column) = column {
+                write!(location, "{}{}", column, separator).unwrap();
+            }
+        }
+        location
+    }
+
+    fn paint(&self, line: &str, ranges: &[Range<usize>]) -> String {
+        if self.color {
+            highlight(line, ranges)
+        } else {
+            line.to_string()
+        }
+    }
+}
+
+/// `path:line` like grep. With `--column`, the line number and column follow
+/// the path, so a match prints as `path:line:column:text`, the format editors
+/// read as a quickfix list.
+pub struct Plain<'w> {
+    out: Box<dyn Write + 'w>,
+    style: Style,
+}
+
+impl Plain<'_> {
+    fn name(&self, path: &str, separator: char) -> String {
+        if self.style.show_filenames {
+            format!("{}{}", path, separator)
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl OutputFormatter for Plain<'_> {
+    fn write_match(&mut self, m: &Match) -> io::Result<()> {
+        let path = m.path.as_deref().unwrap_or_default();
+        writeln!(
+            self.out,
+            "{}{}{}",
+            self.name(path, ':'),
+            self.style.location(':', m.line_number, m.column()),
+            self.style.paint(m.line, &m.ranges)
+        )
+    }
+
+    fn write_context(&mut self, path: &str, number: usize, text: &str) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "{}{}{}",
+            self.name(path, '-'),
+            self.style.location('-', number, None),
+            text
+        )
+    }
+
+    fn write_separator(&mut self) -> io::Result<()> {
+        writeln!(self.out, "--")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// One JSON object per line ("JSON Lines"), so a consumer can start reading
+/// before the search is done. Byte ranges are `[start, end]` pairs.
+///
+/// ```text
+/// {"type":"match","path":"poem.txt","line_number":2,"column":12,"line":"...","ranges":[[11,14]]}
+/// {"type":"context","path":"poem.txt","line_number":3,"line":"..."}
+/// ```
+///
+/// With `--fuzzy`, a match also has a `"distance"`: the number of edits
+/// between the query and the closest text in the line.
+pub struct Json<'w> {
+    out: Box<dyn Write + 'w>,
+}
+
+impl OutputFormatter for Json<'_> {
+    fn write_match(&mut self, m: &Match) -> io::Result<()> {
+        let ranges: Vec<String> = m
+            .ranges
+            .iter()
+            .map(|range| format!("[{},{}]", range.start, range.end))
+            .collect();
+        let column = m.column().map_or(String::from("null"), |c| c.to_string());
+        // Only fuzzy searches have a distance to report.
+        let distance = m
+            .distance
+            .map_or(String::new(), |d| format!(r#","distance":{}"#, d));
+
+        writeln!(
+            self.out,
+            r#"{{"type":"match","path":{},"line_number":{},"column":{},"line":{},"ranges":[{}]{}}}"#,
+            json_string(m.path.as_deref().unwrap_or_default()),
+            m.line_number,
+            column,
+            json_string(m.line),
+            ranges.join(","),
+            distance
+        )
+    }
+
+    fn write_context(&mut self, path: &str, number: usize, text: &str) -> io::Result<()> {
+        writeln!(
+            self.out,
+            r#"{{"type":"context","path":{},"line_number":{},"line":{}}}"#,
+            json_string(path),
+            number,
+            json_string(text)
+        )
+    }
+
+    /// Readers can tell groups apart from the line numbers.
+    fn write_separator(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Quotes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Each file's name once as a heading, then its lines as `line:text`, with a
+/// blank line between files. Files without matches aren't mentioned at all.
+pub struct Grouped<'w> {
+    out: Box<dyn Write + 'w>,
+    style: Style,
+    /// The file whose lines we're getting, and whether its heading is out yet.
+    current: Option<(String, bool)>,
+    /// How many headings have been printed.
+    files: usize,
+}
+
+impl Grouped<'_> {
+    fn heading(&mut self) -> io::Result<()> {
+        if let Some((path, printed @ false)) = &mut self.current {
+            if self.files > 0 {
+                writeln!(self.out)?;
+            }
+            writeln!(self.out, "{}", path)?;
+            *printed = true;
+            self.files += 1;
+        }
+        Ok(())
+    }
+}
+
+impl OutputFormatter for Grouped<'_> {
+    fn start_file(&mut self, path: &str) -> io::Result<()> {
+        self.current = Some((path.to_string(), false));
+        Ok(())
+    }
+
+    fn write_match(&mut self, m: &Match) -> io::Result<()> {
+        self.heading()?;
+        let column = if self.style.column {
+            m.column().map_or(String::new(), |c| format!("{}:", c))
+        } else {
+            String::new()
+        };
+        writeln!(
+            self.out,
+            "{}:{}{}",
+            m.line_number,
+            column,
+            self.style.paint(m.line, &m.ranges)
+        )
+    }
+
+    fn write_context(&mut self, _path: &str, number: usize, text: &str) -> io::Result<()> {
+        self.heading()?;
+        writeln!(self.out, "{}-{}", number, text)
+    }
+
+    fn write_separator(&mut self) -> io::Result<()> {
+        writeln!(self.out, "--")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Wraps every range of `line` in ANSI escape codes for bold red text.
+pub fn highlight(line: &str, ranges: &[Range<usize>]) -> String {
+    let mut painted = String::with_capacity(line.len());
+    let mut last = 0;
+
+    for range in ranges.iter().filter(|range| !range.is_empty()) {
+        painted.push_str(&line[last..range.start]);
+        painted.push_str("\x1b[1;31m");
+        painted.push_str(&line[range.clone()]);
+        painted.push_str("\x1b[0m");
+        last = range.end;
+    }
+    painted.push_str(&line[last..]);
+
+    painted
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use crate::ConfigBuilder;
+
+    /// Writes two files' worth of results with the formatter for `format`.
+    fn render(format: Format, column: bool) -> String {
+        let config = ConfigBuilder::new()
+            .query("to")
+            .path("a.txt")
+            .path("b.txt")
+            .format(format)
+            .column(column)
+            .build()
+            .unwrap();
+        let mut buffer = Vec::new();
+
+        {
+            let mut out = formatter(&config, Box::new(&mut buffer));
+            let first = Match {
+                path: Some(String::from("a.txt")),
+                line_number: 2,
+                line: "How \"dreary\" to be",
+                ranges: vec![13..15],
+                distance: None,
+            };
+            out.start_file("a.txt").unwrap();
+            out.write_match(&first).unwrap();
+            out.write_context("a.txt", 3, "Somebody!").unwrap();
+            out.start_file("b.txt").unwrap();
+            out.start_file("c.txt").unwrap();
+            out.write_match(&Match {
+                path: Some(String::from("c.txt")),
+                line_number: 1,
+                ..first
+            })
+            .unwrap();
+            out.finish().unwrap();
+        }
+
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            "a.txt:How \"dreary\" to be\na.txt-Somebody!\nc.txt:How \"dreary\" to be\n",
+            render(Format::Plain, false)
+        );
+        assert!(render(Format::Plain, true).starts_with("a.txt:2:14:How"));
+    }
+
+    #[test]
+    fn json() {
+        let output = render(Format::Json, false);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            r#"{"type":"match","path":"a.txt","line_number":2,"column":14,"line":"How \"dreary\" to be","ranges":[[13,15]]}"#,
+            lines[0]
+        );
+        assert_eq!(
+            r#"{"type":"context","path":"a.txt","line_number":3,"line":"Somebody!"}"#,
+            lines[1]
+        );
+        assert_eq!(3, lines.len());
+    }
+
+    #[test]
+    fn grouped() {
+        assert_eq!(
+            "a.txt\n2:How \"dreary\" to be\n3-Somebody!\n\nc.txt\n1:How \"dreary\" to be\n",
+            render(Format::Grouped, false)
+        );
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(r#""a\"b\\c\n\u0007""#, json_string("a\"b\\c\n\x07"));
+    }
+
+    #[test]
+    fn highlighting_marks_every_match() {
+        assert_eq!(
+            "\x1b[1;31mTo\x1b[0m \x1b[1;31mto\x1b[0mad",
+            highlight("To toad", &[0..2, 3..5])
+        );
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!(Ok(Format::Grouped), "grouped".parse());
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
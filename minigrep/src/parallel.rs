@@ -0,0 +1,131 @@
+//! Searching one big file on several threads.
+//!
+//! The contents are split at line boundaries into one chunk per thread. Each
+//! thread searches its chunk as if it were a whole file, and the line numbers
+//! are shifted afterwards by the number of lines in the chunks before it.
+//! Joining the threads in order puts the matches back in file order.
+//!
+//! Only plain searches are split this way. Context lines (`-A`, `-B`, `-C`)
+//! can reach across a chunk boundary, so those stay on one thread.
+
+use std::panic;
+use std::thread;
+
+use crate::{match_iter, regex, Config, Match};
+
+/// Files shorter than this are searched on one thread: starting the threads
+/// would take longer than the search.
+pub const MIN_LEN: usize = 4 * 1024 * 1024;
+
+/// No chunk is made shorter than this, however many threads there are.
+const MIN_CHUNK_LEN: usize = 1024 * 1024;
+
+/// How many threads are worth using for `contents`: one per CPU, but no more
+/// than there are chunks of at least `MIN_CHUNK_LEN` bytes.
+pub fn threads_for(contents: &str) -> usize {
+    let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+    if contents.len() < MIN_LEN {
+        1
+    } else {
+        cpus.min(contents.len() / MIN_CHUNK_LEN).max(1)
+    }
+}
+
+/// Splits `contents` into at most `count` pieces of about the same length,
+/// each ending just after a newline (or at the end of `contents`). Returns
+/// each piece with the number of lines that come before it.
+pub fn chunks(contents: &str, count: usize) -> Vec<(usize, &str)> {
+    let target = contents.len() / count.max(1) + 1;
+    let mut chunks = Vec::with_capacity(count);
+    let mut rest = contents;
+    let mut lines_before = 0;
+
+    while !rest.is_empty() {
+        let end = if rest.len() <= target {
+            rest.len()
+        } else {
+            // Extend the chunk to the end of the line the target lands in.
+            match rest.as_bytes()[target..].iter().position(|&b| b == b'\n') {
+                Some(newline) => target + newline + 1,
+                None => rest.len(),
+            }
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push((lines_before, chunk));
+        lines_before += chunk.bytes().filter(|&b| b == b'\n').count();
+        rest = tail;
+    }
+
+    chunks
+}
+
+/// Searches `contents` with `threads` threads and returns the matches in
+/// order, the same ones `match_iter` would find, `-m` limit included.
+pub fn search<'a>(
+    config: &Config,
+    contents: &'a str,
+    threads: usize,
+) -> Result<Vec<Match<'a>>, regex::Error> {
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = chunks(contents, threads)
+            .into_iter()
+            .map(|(lines_before, chunk)| {
+                scope.spawn(move || {
+                    let matches = match_iter(config, chunk)?
+                        .map(|m| Match {
+                            line_number: m.line_number + lines_before,
+                            ..m
+                        })
+                        .collect::<Vec<_>>();
+                    Ok(matches)
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect::<Vec<Result<Vec<Match>, regex::Error>>>()
+    });
+
+    let mut matches = Vec::new();
+    for result in results {
+        matches.extend(result?);
+    }
+    // Each chunk stopped at the limit on its own; the file as a whole has to
+    // stop there too.
+    matches.truncate(config.max_count.unwrap_or(usize::MAX));
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigBuilder;
+
+    #[test]
+    fn chunks_end_at_line_boundaries() {
+        let contents = "one\ntwo\nthree\nfour\nfive";
+        let chunks = chunks(contents, 3);
+
+        assert_eq!(vec![(0, "one\ntwo\nthree\n"), (3, "four\nfive")], chunks);
+        assert!(super::chunks("", 4).is_empty());
+        assert_eq!(vec![(0, "no newline")], super::chunks("no newline", 4));
+    }
+
+    #[test]
+    fn same_matches_as_one_thread() {
+        let contents = crate::bench::generate(500, 3);
+        let config = ConfigBuilder::new().query("needle").build().unwrap();
+
+        let expected: Vec<Match> = match_iter(&config, &contents).unwrap().collect();
+        assert_eq!(expected, search(&config, &contents, 4).unwrap());
+
+        let config = ConfigBuilder::new()
+            .query("needle")
+            .max_count(7)
+            .build()
+            .unwrap();
+        assert_eq!(&expected[..7], &search(&config, &contents, 4).unwrap()[..]);
+    }
+}
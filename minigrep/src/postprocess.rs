@@ -0,0 +1,121 @@
+//! Rearranging the matches before they're printed (`--sort`, `--unique`).
+//!
+//! Normally every match is printed as soon as it's found. These options need
+//! to see all of them first, so `run` collects the matches of every file, hands
+//! them to `apply`, and only then passes them to the `OutputFormatter`.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::Match;
+
+/// The order files are printed in with `--sort`. The lines of a file always
+/// stay in order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortBy {
+    /// By file name.
+    Path,
+    /// The file with the most matching lines first. Files with the same count
+    /// are sorted by name.
+    Count,
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<SortBy, String> {
+        match name {
+            "path" => Ok(SortBy::Path),
+            "count" => Ok(SortBy::Count),
+            _ => Err(format!(
+                "unknown sort order {} (expected path or count)",
+                name
+            )),
+        }
+    }
+}
+
+/// One file's name and matches.
+pub type FileMatches<'a> = (&'a str, Vec<Match<'a>>);
+
+/// Sorts `files` and, with `unique`, drops every line whose text was already
+/// printed, in this file or an earlier one.
+///
+/// Duplicates are dropped in the order the files end up printed by path, so
+/// `--sort path --unique` keeps the copy in the first file by name. Counts for
+/// `--sort count` are of the lines left after that.
+pub fn apply(files: &mut [FileMatches], sort: Option<SortBy>, unique: bool) {
+    if sort == Some(SortBy::Path) {
+        files.sort_by(|a, b| a.0.cmp(b.0));
+    }
+
+    if unique {
+        let mut seen = HashSet::new();
+        for (_, matches) in files.iter_mut() {
+            matches.retain(|m| seen.insert(m.line));
+        }
+    }
+
+    if sort == Some(SortBy::Count) {
+        files.sort_by_key(|(path, matches)| (Reverse(matches.len()), *path));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_matches;
+
+    const A: &str = "rust\nduct tape\nrust\n";
+    const B: &str = "trust\nrust\nrusty\nrust\n";
+
+    fn lines<'a>(files: &[FileMatches<'a>]) -> Vec<(&'a str, Vec<&'a str>)> {
+        files
+            .iter()
+            .map(|(path, matches)| (*path, matches.iter().map(|m| m.line).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn sort_by_path_or_count() {
+        let mut files = vec![
+            ("b.txt", search_matches("rust", B)),
+            ("a.txt", search_matches("rust", A)),
+        ];
+
+        apply(&mut files, Some(SortBy::Path), false);
+        assert_eq!(
+            vec!["a.txt", "b.txt"],
+            files.iter().map(|f| f.0).collect::<Vec<_>>()
+        );
+
+        apply(&mut files, Some(SortBy::Count), false);
+        assert_eq!(
+            vec!["b.txt", "a.txt"],
+            files.iter().map(|f| f.0).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn unique_keeps_the_first_copy() {
+        let mut files = vec![
+            ("b.txt", search_matches("rust", B)),
+            ("a.txt", search_matches("rust", A)),
+        ];
+
+        apply(&mut files, Some(SortBy::Path), true);
+        assert_eq!(
+            vec![("a.txt", vec!["rust"]), ("b.txt", vec!["trust", "rusty"])],
+            lines(&files)
+        );
+        // Line numbers still refer to the original file.
+        assert_eq!(3, files[1].1[1].line_number);
+    }
+
+    #[test]
+    fn sort_names() {
+        assert_eq!(Ok(SortBy::Count), "count".parse());
+        assert!("size".parse::<SortBy>().is_err());
+    }
+}
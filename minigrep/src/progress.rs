@@ -0,0 +1,133 @@
+//! A one-line progress indicator on standard error, for long searches.
+//!
+//! The line is redrawn in place with a carriage return, so it only makes sense
+//! on a terminal. It's drawn at most once every `INTERVAL`; drawing on every
+//! file would cost more than searching the small ones.
+//!
+//! Matches go to standard output, so the two only share a screen when both are
+//! the same terminal. `run` doesn't show progress then (see `Config::progress`),
+//! which keeps the indicator out of the middle of the results.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// The least time between two redraws.
+pub const INTERVAL: Duration = Duration::from_millis(100);
+
+/// The longest file name shown; longer ones keep their last characters.
+const NAME_WIDTH: usize = 50;
+
+pub struct Progress<W: Write> {
+    out: W,
+    total: usize,
+    done: usize,
+    last_drawn: Option<Instant>,
+    /// Whether there's a line on screen that `finish` has to clear.
+    drawn: bool,
+}
+
+impl Progress<io::Stderr> {
+    pub fn stderr(total: usize) -> Progress<io::Stderr> {
+        Progress::new(io::stderr(), total)
+    }
+}
+
+impl<W: Write> Progress<W> {
+    /// Reports on `total` files to `out`.
+    pub fn new(out: W, total: usize) -> Progress<W> {
+        Progress {
+            out,
+            total,
+            done: 0,
+            last_drawn: None,
+            drawn: false,
+        }
+    }
+
+    /// Call before searching each file. Redraws the line if it's been at
+    /// least `INTERVAL` since the last time.
+    pub fn start(&mut self, name: &str) -> io::Result<()> {
+        self.start_at(name, Instant::now())
+    }
+
+    /// Like `start`, with the time passed in, so tests don't depend on how
+    /// fast they run.
+    fn start_at(&mut self, name: &str, now: Instant) -> io::Result<()> {
+        let due = self
+            .last_drawn
+            .is_none_or(|last| now.duration_since(last) >= INTERVAL);
+        if due {
+            self.draw(name)?;
+            self.last_drawn = Some(now);
+        }
+        self.done += 1;
+        Ok(())
+    }
+
+    fn draw(&mut self, name: &str) -> io::Result<()> {
+        // `\x1b[K` clears whatever a longer name left behind.
+        write!(
+            self.out,
+            "\r\x1b[K[{}/{}] {}",
+            self.done,
+            self.total,
+            shorten(name, NAME_WIDTH)
+        )?;
+        self.drawn = true;
+        self.out.flush()
+    }
+
+    /// Clears the progress line, leaving the cursor where it started.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.drawn {
+            write!(self.out, "\r\x1b[K")?;
+            self.drawn = false;
+        }
+        self.out.flush()
+    }
+}
+
+/// Keeps the end of `name`, which is the part that tells files apart, when it
+/// has more than `width` characters.
+fn shorten(name: &str, width: usize) -> String {
+    let count = name.chars().count();
+    if count <= width {
+        return name.to_string();
+    }
+    let tail: String = name.chars().skip(count - (width - 3)).collect();
+    format!("...{}", tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redraws_are_rate_limited() {
+        let start = Instant::now();
+        let mut progress = Progress::new(Vec::new(), 4);
+        progress.start_at("a.txt", start).unwrap();
+        progress.start_at("b.txt", start + INTERVAL / 2).unwrap();
+        progress.start_at("c.txt", start + INTERVAL).unwrap();
+        progress.start_at("d.txt", start + INTERVAL * 3 / 2).unwrap();
+        progress.finish().unwrap();
+
+        // The first file is drawn right away, and the third once `INTERVAL`
+        // has passed; the others come too quickly.
+        let output = String::from_utf8(progress.out).unwrap();
+        assert_eq!("\r\x1b[K[0/4] a.txt\r\x1b[K[2/4] c.txt\r\x1b[K", output);
+    }
+
+    #[test]
+    fn nothing_to_clear_if_nothing_was_drawn() {
+        let mut progress = Progress::new(Vec::new(), 0);
+        progress.finish().unwrap();
+        assert!(progress.out.is_empty());
+    }
+
+    #[test]
+    fn long_names_keep_their_end() {
+        assert_eq!("short.txt", shorten("short.txt", 10));
+        assert_eq!("...ong.txt", shorten("a/very/long.txt", 10));
+    }
+}
@@ -0,0 +1,204 @@
+//! Default options loaded from `.minigreprc` files.
+//!
+//! The file is INI-style: one `key = value` per line, with `#` or `;` starting a
+//! comment. An optional `[minigrep]` section header is allowed. The recognized
+//! keys are:
+//!
+//! ```text
+//! ignore-case = true
+//! smart-case = true
+//! color = true
+//! context = 2
+//! exclude = target/**
+//! exclude = *.lock
+//! ```
+//!
+//! `exclude` may be given several times; every other key keeps its last value.
+//! `ignore-case` and `smart-case` both choose the case mode, so whichever comes
+//! last wins.
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = ".minigreprc";
+
+/// How letter case is treated when matching.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Case {
+    Sensitive,
+    Insensitive,
+    /// Insensitive if the query is all lowercase, sensitive as soon as it
+    /// contains an uppercase letter.
+    Smart,
+}
+
+/// One layer of options. `None` means "not set in this layer", so a lower layer's
+/// value shows through when layers are merged.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Settings {
+    pub case: Option<Case>,
+    pub color: Option<bool>,
+    pub context: Option<usize>,
+    pub exclude: Vec<String>,
+}
+
+impl Settings {
+    /// Returns `self` with every option that `higher` sets overridden.
+    /// Exclusions accumulate instead, so a project can add to the user's list.
+    pub fn merge(mut self, higher: Settings) -> Settings {
+        self.case = higher.case.or(self.case);
+        self.color = higher.color.or(self.color);
+        self.context = higher.context.or(self.context);
+        self.exclude.extend(higher.exclude);
+        self
+    }
+
+    pub fn parse(contents: &str) -> Result<Settings, String> {
+        let mut settings = Settings::default();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(['#', ';']) || line == "[minigrep]" {
+                continue;
+            }
+
+            let error = |message: &str| format!("line {}: {}", i + 1, message);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected `key = value`"))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "ignore-case" | "smart-case" => {
+                    let enabled =
+                        parse_bool(value).ok_or_else(|| error("expected true or false"))?;
+                    settings.case = Some(match (key.trim(), enabled) {
+                        (_, false) => Case::Sensitive,
+                        ("smart-case", true) => Case::Smart,
+                        _ => Case::Insensitive,
+                    });
+                }
+                "color" => {
+                    settings.color =
+                        Some(parse_bool(value).ok_or_else(|| error("expected true or false"))?)
+                }
+                "context" => {
+                    settings.context = Some(
+                        value
+                            .parse()
+                            .map_err(|_| error("expected a number of lines"))?,
+                    )
+                }
+                "exclude" => settings.exclude.push(value.to_string()),
+                key => return Err(error(&format!("unknown option `{}`", key))),
+            }
+        }
+
+        Ok(settings)
+    }
+
+    /// Reads one file. A missing file is an empty layer, not an error.
+    pub fn read(path: &Path) -> Result<Settings, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                Settings::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Settings::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Loads `~/.minigreprc` and then `./.minigreprc`, so project settings
+    /// override the user's.
+    pub fn load() -> Result<Settings, String> {
+        let home = env::var_os("HOME").map(|home| PathBuf::from(home).join(FILE_NAME));
+        let project = PathBuf::from(FILE_NAME);
+
+        let mut settings = Settings::default();
+        for path in home.iter().chain([&project]) {
+            settings = settings.merge(Settings::read(path)?);
+        }
+        Ok(settings)
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_option() {
+        let settings = Settings::parse(
+            "\
+# defaults for this project
+[minigrep]
+ignore-case = yes
+color = off
+context = 2
+exclude = target/**
+exclude = *.lock",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Settings {
+                case: Some(Case::Insensitive),
+                color: Some(false),
+                context: Some(2),
+                exclude: vec![String::from("target/**"), String::from("*.lock")],
+            },
+            settings
+        );
+    }
+
+    #[test]
+    fn last_case_option_wins() {
+        let settings = Settings::parse("ignore-case = true\nsmart-case = true").unwrap();
+        assert_eq!(Some(Case::Smart), settings.case);
+
+        let settings = Settings::parse("smart-case = true\nignore-case = false").unwrap();
+        assert_eq!(Some(Case::Sensitive), settings.case);
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        assert_eq!(
+            Err(String::from("line 2: unknown option `colour`")),
+            Settings::parse("color = true\ncolour = true")
+        );
+        assert!(Settings::parse("context = lots")
+            .unwrap_err()
+            .starts_with("line 1"));
+    }
+
+    #[test]
+    fn higher_layers_win_and_exclusions_accumulate() {
+        let home = Settings {
+            case: Some(Case::Smart),
+            context: Some(3),
+            exclude: vec![String::from("target/**")],
+            ..Settings::default()
+        };
+        let project = Settings {
+            case: Some(Case::Sensitive),
+            exclude: vec![String::from("*.lock")],
+            ..Settings::default()
+        };
+
+        let merged = home.merge(project);
+        assert_eq!(Some(Case::Sensitive), merged.case);
+        assert_eq!(Some(3), merged.context);
+        assert_eq!(None, merged.color);
+        assert_eq!(2, merged.exclude.len());
+    }
+}
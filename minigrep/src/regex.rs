@@ -0,0 +1,785 @@
+//! A small regular expression engine, which compiles a pattern to a program and
+//! runs it over every character of a line once.
+//!
+//! It supports the subset of syntax that's useful when searching source code and
+//! logs:
+//! * literals, `.` (any character), `^` and `$` (start and end of the line)
+//! * character classes such as `[a-z_]` and `[^0-9]`
+//! * the escapes `\d`, `\w`, `\s`, their negations `\D`, `\W`, `\S`, and `\b`
+//! * the quantifiers `*`, `+`, `?`, `{n}`, `{n,}`, and `{n,m}`, each optionally
+//!   followed by `?` to match as few repetitions as possible
+//! * groups `(...)`, non-capturing groups `(?:...)`, and alternation `a|b`
+//!
+//! Like Perl, the engine prefers the leftmost match and, among those, the first
+//! one found by trying alternatives in order.
+
+use std::error;
+use std::fmt;
+use std::ops::Range;
+
+/// A malformed pattern, with the character position where parsing failed.
+#[derive(Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+    pub position: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl error::Error for Error {}
+
+#[derive(Debug, Clone)]
+pub struct Regex {
+    program: Vec<Inst>,
+    ignore_case: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    WordBoundary(bool),
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Class {
+    items: Vec<ClassItem>,
+    negated: bool,
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Regex, Error> {
+        Regex::build(pattern, false)
+    }
+
+    /// Compiles a pattern that ignores case when comparing characters.
+    pub fn new_case_insensitive(pattern: &str) -> Result<Regex, Error> {
+        Regex::build(pattern, true)
+    }
+
+    fn build(pattern: &str, ignore_case: bool) -> Result<Regex, Error> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+        };
+        let alternatives = parser.parse_alternatives()?;
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unmatched `)`"));
+        }
+
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+        compiler.alternatives(&alternatives)?;
+        compiler.push(Inst::Match)?;
+
+        Ok(Regex {
+            program: compiler.program,
+            ignore_case,
+        })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Returns the byte range of the leftmost match in `text`.
+    pub fn find(&self, text: &str) -> Option<Range<usize>> {
+        self.find_at(text, 0)
+    }
+
+    /// Like `find`, but starts looking at byte offset `start`. Anchors and word
+    /// boundaries still see the whole of `text`.
+    pub fn find_at(&self, text: &str, start: usize) -> Option<Range<usize>> {
+        let input = Input::new(text);
+        let first = input.offsets.iter().position(|&offset| offset >= start)?;
+        let (begin, end) = self.matcher(&input).search(first)?;
+        Some(input.offsets[begin]..input.offsets[end])
+    }
+
+    /// Returns the byte ranges of all non-overlapping matches in `text`.
+    pub fn find_iter(&self, text: &str) -> Vec<Range<usize>> {
+        // Decoded once, rather than by `find_at` for every match.
+        let input = Input::new(text);
+        let matcher = self.matcher(&input);
+        let mut matches = Vec::new();
+        let mut first = 0;
+
+        while first <= input.chars.len() {
+            let (begin, end) = match matcher.search(first) {
+                Some(found) => found,
+                None => break,
+            };
+            // An empty match would be found again at the same place, so step
+            // past the next character.
+            first = if begin == end { end + 1 } else { end };
+            matches.push(input.offsets[begin]..input.offsets[end]);
+        }
+
+        matches
+    }
+
+    fn matcher<'a>(&'a self, input: &'a Input) -> Matcher<'a> {
+        Matcher {
+            program: &self.program,
+            chars: &input.chars,
+            ignore_case: self.ignore_case,
+        }
+    }
+}
+
+/// Escapes every special character in `text`, producing a pattern that matches
+/// `text` literally.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> Error {
+        Error {
+            message: String::from(message),
+            position: self.pos,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn parse_alternatives(&mut self) -> Result<Vec<Vec<Node>>, Error> {
+        let mut alternatives = vec![self.parse_sequence()?];
+
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.parse_sequence()?);
+        }
+
+        Ok(alternatives)
+    }
+
+    fn parse_sequence(&mut self) -> Result<Vec<Node>, Error> {
+        let mut nodes = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            nodes.push(self.parse_quantifier(atom)?);
+        }
+
+        Ok(nodes)
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, Error> {
+        match self.next() {
+            Some('(') => {
+                if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                }
+                let alternatives = self.parse_alternatives()?;
+                if self.next() != Some(')') {
+                    return Err(self.error("unclosed group"));
+                }
+                Ok(Node::Group(alternatives))
+            }
+            Some('[') => self.parse_class(),
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('\\') => self.parse_escape(),
+            Some('*' | '+' | '?') => {
+                self.pos -= 1;
+                Err(self.error("nothing to repeat"))
+            }
+            Some(c) => Ok(Node::Char(c)),
+            None => Err(self.error("unexpected end of pattern")),
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<Node, Error> {
+        let node = match self.next() {
+            Some('b') => Node::WordBoundary(true),
+            Some('B') => Node::WordBoundary(false),
+            Some('n') => Node::Char('\n'),
+            Some('t') => Node::Char('\t'),
+            Some('r') => Node::Char('\r'),
+            Some(c) => match class_escape(c) {
+                Some(item) => Node::Class(Class {
+                    items: vec![item],
+                    negated: false,
+                }),
+                None => Node::Char(c),
+            },
+            None => return Err(self.error("trailing backslash")),
+        };
+
+        Ok(node)
+    }
+
+    fn parse_class(&mut self) -> Result<Node, Error> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut items = Vec::new();
+        // A `]` right after the opening bracket is a literal.
+        let mut first = true;
+
+        loop {
+            let c = match self.next() {
+                Some(']') if !first => break,
+                Some(c) => c,
+                None => return Err(self.error("unclosed character class")),
+            };
+            first = false;
+
+            let low = if c == '\\' {
+                match self.next() {
+                    Some(e) => match class_escape(e) {
+                        Some(item) => {
+                            items.push(item);
+                            continue;
+                        }
+                        None => unescape(e),
+                    },
+                    None => return Err(self.error("trailing backslash")),
+                }
+            } else {
+                c
+            };
+
+            let is_range = self.peek() == Some('-')
+                && self
+                    .chars
+                    .get(self.pos + 1)
+                    .is_some_and(|&next| next != ']');
+            if is_range {
+                self.pos += 1;
+                let high = match self.next() {
+                    Some('\\') => self.next().map(unescape),
+                    other => other,
+                };
+                let high = high.ok_or_else(|| self.error("unclosed character class"))?;
+                if high < low {
+                    return Err(self.error("invalid range in character class"));
+                }
+                items.push(ClassItem::Range(low, high));
+            } else {
+                items.push(ClassItem::Range(low, low));
+            }
+        }
+
+        Ok(Node::Class(Class { items, negated }))
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, Error> {
+        let (min, max) = match self.peek() {
+            Some('{') => match self.parse_braces() {
+                Some(bounds) => bounds,
+                // Not a valid repetition, so the `{` is a literal.
+                None => return Ok(atom),
+            },
+            Some(c @ ('*' | '+' | '?')) => {
+                self.pos += 1;
+                match c {
+                    '*' => (0, None),
+                    '+' => (1, None),
+                    _ => (0, Some(1)),
+                }
+            }
+            _ => return Ok(atom),
+        };
+
+        if matches!(atom, Node::Start | Node::End | Node::WordBoundary(_)) {
+            return Err(self.error("nothing to repeat"));
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(self.error("invalid repetition range"));
+        }
+
+        let greedy = self.peek() != Some('?');
+        if !greedy {
+            self.pos += 1;
+        }
+        if matches!(self.peek(), Some('*' | '+')) {
+            return Err(self.error("nothing to repeat"));
+        }
+
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    /// Parses `{n}`, `{n,}`, or `{n,m}`, consuming the input only on success.
+    fn parse_braces(&mut self) -> Option<(usize, Option<usize>)> {
+        let rest: String = self.chars[self.pos + 1..].iter().collect();
+        let close = rest.find('}')?;
+        let body = &rest[..close];
+
+        let bounds = match body.split_once(',') {
+            None => {
+                let n = body.parse().ok()?;
+                (n, Some(n))
+            }
+            Some((min, "")) => (min.parse().ok()?, None),
+            Some((min, max)) => (min.parse().ok()?, Some(max.parse().ok()?)),
+        };
+
+        self.pos += body.chars().count() + 2;
+        Some(bounds)
+    }
+}
+
+fn class_escape(c: char) -> Option<ClassItem> {
+    match c {
+        'd' => Some(ClassItem::Digit(true)),
+        'D' => Some(ClassItem::Digit(false)),
+        'w' => Some(ClassItem::Word(true)),
+        'W' => Some(ClassItem::Word(false)),
+        's' => Some(ClassItem::Space(true)),
+        'S' => Some(ClassItem::Space(false)),
+        _ => None,
+    }
+}
+
+fn unescape(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        c => c,
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// One instruction of a compiled pattern. `Split` and `Jump` move between
+/// instructions without consuming a character; the rest consume one character or
+/// check a position.
+#[derive(Debug, Clone)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    WordBoundary(bool),
+    /// Carries on at both instructions, preferring the first.
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+/// Repetition counts are written out in full, so `(a{1000}){1000}` would be a
+/// million instructions. Patterns bigger than this are refused instead.
+const MAX_PROGRAM: usize = 100_000;
+
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn push(&mut self, inst: Inst) -> Result<usize, Error> {
+        if self.program.len() == MAX_PROGRAM {
+            return Err(Error {
+                message: String::from("pattern too large"),
+                position: 0,
+            });
+        }
+        self.program.push(inst);
+        Ok(self.program.len() - 1)
+    }
+
+    fn alternatives(&mut self, alternatives: &[Vec<Node>]) -> Result<(), Error> {
+        let mut jumps = Vec::new();
+
+        for (i, sequence) in alternatives.iter().enumerate() {
+            if i + 1 == alternatives.len() {
+                self.sequence(sequence)?;
+                break;
+            }
+            let split = self.push(Inst::Split(0, 0))?;
+            self.sequence(sequence)?;
+            jumps.push(self.push(Inst::Jump(0))?);
+            self.program[split] = Inst::Split(split + 1, self.program.len());
+        }
+
+        let end = self.program.len();
+        for jump in jumps {
+            self.program[jump] = Inst::Jump(end);
+        }
+        Ok(())
+    }
+
+    fn sequence(&mut self, nodes: &[Node]) -> Result<(), Error> {
+        nodes.iter().try_for_each(|node| self.node(node))
+    }
+
+    fn node(&mut self, node: &Node) -> Result<(), Error> {
+        let inst = match node {
+            Node::Char(c) => Inst::Char(*c),
+            Node::Any => Inst::Any,
+            Node::Class(class) => Inst::Class(class.clone()),
+            Node::Start => Inst::Start,
+            Node::End => Inst::End,
+            Node::WordBoundary(yes) => Inst::WordBoundary(*yes),
+            Node::Group(alternatives) => return self.alternatives(alternatives),
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } => return self.repeat(node, *min, *max, *greedy),
+        };
+        self.push(inst).map(|_| ())
+    }
+
+    /// Writes out `min` copies of `node`, followed by a loop if there's no maximum,
+    /// or by the optional copies up to the maximum otherwise. Skipping an optional
+    /// copy skips all the ones after it too, so `a{0,3}` is `(a(a(a)?)?)?`.
+    fn repeat(
+        &mut self,
+        node: &Node,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    ) -> Result<(), Error> {
+        for _ in 0..min {
+            self.node(node)?;
+        }
+        // A greedy repetition prefers another copy; a lazy one prefers moving on.
+        let split = |more: usize, done: usize| {
+            if greedy {
+                Inst::Split(more, done)
+            } else {
+                Inst::Split(done, more)
+            }
+        };
+
+        match max {
+            None => {
+                let start = self.push(Inst::Split(0, 0))?;
+                self.node(node)?;
+                self.push(Inst::Jump(start))?;
+                self.program[start] = split(start + 1, self.program.len());
+            }
+            Some(max) => {
+                let mut splits = Vec::new();
+                for _ in min..max {
+                    splits.push(self.push(Inst::Split(0, 0))?);
+                    self.node(node)?;
+                }
+                let done = self.program.len();
+                for start in splits {
+                    self.program[start] = split(start + 1, done);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A line decoded into characters once, with the byte offset of each, so matching
+/// can step through it by character and still report byte ranges.
+struct Input {
+    chars: Vec<char>,
+    /// One more than `chars`: the last one is the length of the line.
+    offsets: Vec<usize>,
+}
+
+impl Input {
+    fn new(text: &str) -> Input {
+        let (mut offsets, chars): (Vec<usize>, Vec<char>) = text.char_indices().unzip();
+        offsets.push(text.len());
+        Input { chars, offsets }
+    }
+}
+
+/// The threads at one position, in priority order, each with the position its match
+/// started at. A thread that reaches an instruction another thread already reached
+/// at this position is dropped: the first one has priority, and they'd match the
+/// same from here on.
+struct Threads {
+    list: Vec<(usize, usize)>,
+    seen: Vec<bool>,
+    /// Every instruction marked in `seen`, `Split`s and `Jump`s included, so
+    /// clearing only has to unmark those.
+    marked: Vec<usize>,
+}
+
+impl Threads {
+    fn new(size: usize) -> Threads {
+        Threads {
+            list: Vec::new(),
+            seen: vec![false; size],
+            marked: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        for &pc in &self.marked {
+            self.seen[pc] = false;
+        }
+        self.marked.clear();
+        self.list.clear();
+    }
+}
+
+/// Runs a compiled pattern over all of its possible paths at once, one character at
+/// a time, in the style of Pike's VM. That takes time proportional to the length of
+/// the line times the size of the pattern, where trying each path in turn can take
+/// exponential time on patterns like `(a+)+b`, and needs no recursion on the
+/// characters, so long lines can't overflow the stack.
+struct Matcher<'a> {
+    program: &'a [Inst],
+    chars: &'a [char],
+    ignore_case: bool,
+}
+
+impl Matcher<'_> {
+    fn same(&self, a: char, b: char) -> bool {
+        a == b || (self.ignore_case && a.to_lowercase().eq(b.to_lowercase()))
+    }
+
+    fn in_class(&self, class: &Class, c: char) -> bool {
+        let contains = |c: char| {
+            class.items.iter().any(|item| match *item {
+                ClassItem::Range(low, high) => low <= c && c <= high,
+                ClassItem::Digit(yes) => c.is_ascii_digit() == yes,
+                ClassItem::Word(yes) => is_word(c) == yes,
+                ClassItem::Space(yes) => c.is_whitespace() == yes,
+            })
+        };
+
+        let found = contains(c)
+            || (self.ignore_case
+                && (c.to_lowercase().any(contains) || c.to_uppercase().any(contains)));
+        found != class.negated
+    }
+
+    /// Returns the character range of the leftmost match starting at `first` or
+    /// later.
+    fn search(&self, first: usize) -> Option<(usize, usize)> {
+        let mut current = Threads::new(self.program.len());
+        let mut next = Threads::new(self.program.len());
+        let mut found = None;
+
+        for pos in first..=self.chars.len() {
+            // A match starting here is only wanted if none started earlier, and it
+            // comes last, after the threads that did start earlier.
+            if found.is_none() {
+                self.add(&mut current, 0, pos, pos);
+            } else if current.list.is_empty() {
+                break;
+            }
+
+            let c = self.chars.get(pos).copied();
+            for &(pc, start) in &current.list {
+                let consumed = match &self.program[pc] {
+                    Inst::Char(expected) => c.is_some_and(|c| self.same(c, *expected)),
+                    Inst::Any => c.is_some(),
+                    Inst::Class(class) => c.is_some_and(|c| self.in_class(class, c)),
+                    Inst::Match => {
+                        // The threads after this one have lower priority, so they're
+                        // dropped; the ones before it may still find a match they'd
+                        // prefer.
+                        found = Some((start, pos));
+                        break;
+                    }
+                    _ => false,
+                };
+                if consumed {
+                    self.add(&mut next, pc + 1, pos + 1, start);
+                }
+            }
+
+            current.clear();
+            std::mem::swap(&mut current, &mut next);
+        }
+
+        found
+    }
+
+    /// Adds the thread at `pc` to `threads`, following `Split`s and `Jump`s and
+    /// checking anchors at `pos`, so only instructions that consume a character or
+    /// match end up in the list.
+    fn add(&self, threads: &mut Threads, pc: usize, pos: usize, start: usize) {
+        let mut stack = vec![pc];
+
+        while let Some(pc) = stack.pop() {
+            if threads.seen[pc] {
+                continue;
+            }
+            threads.seen[pc] = true;
+            threads.marked.push(pc);
+
+            match self.program[pc] {
+                Inst::Split(first, second) => {
+                    // The stack is last in, first out, so the preferred one goes on
+                    // last.
+                    stack.push(second);
+                    stack.push(first);
+                }
+                Inst::Jump(to) => stack.push(to),
+                Inst::Start => {
+                    if pos == 0 {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::End => {
+                    if pos == self.chars.len() {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::WordBoundary(yes) => {
+                    let before = pos > 0 && is_word(self.chars[pos - 1]);
+                    let after = self.chars.get(pos).copied().is_some_and(is_word);
+                    if (before != after) == yes {
+                        stack.push(pc + 1);
+                    }
+                }
+                _ => threads.list.push((pc, start)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, text: &str) -> Option<String> {
+        let re = Regex::new(pattern).unwrap();
+        re.find(text).map(|range| text[range].to_string())
+    }
+
+    #[test]
+    fn literals_anchors_and_classes() {
+        assert_eq!(Some("fn main".into()), matches(r"^fn\s+\w+", "fn main() {"));
+        assert_eq!(None, matches(r"^fn\s+\w+", "  fn main() {"));
+        assert_eq!(Some("42".into()), matches(r"[0-9]+$", "answer: 42"));
+        assert_eq!(Some("b-c".into()), matches(r"[^a ]-.", "a b-c"));
+        assert_eq!(Some("]".into()), matches(r"[]]", "x]"));
+        assert_eq!(Some("cat".into()), matches(r"\bcat\b", "concat cat"));
+    }
+
+    #[test]
+    fn quantifiers_and_alternation() {
+        assert_eq!(Some("aaa".into()), matches("a{2,3}", "aaaa"));
+        assert_eq!(Some("aa".into()), matches("a{2}", "aaaa"));
+        assert_eq!(Some("<a>".into()), matches("<.+?>", "<a><b>"));
+        assert_eq!(Some("<a><b>".into()), matches("<.+>", "<a><b>"));
+        assert_eq!(Some("colour".into()), matches("colou?r", "colour"));
+        assert_eq!(Some("dog".into()), matches("(?:cat|dog)s?", "hotdog"));
+        assert_eq!(Some("".into()), matches("(a*)*", "b"));
+        assert_eq!(Some("x{".into()), matches("x{", "x{"));
+    }
+
+    #[test]
+    fn case_insensitive_matching() {
+        let re = Regex::new_case_insensitive("rust[A-Z]").unwrap();
+        assert!(re.is_match("TRUSTy"));
+        assert!(!Regex::new("rust").unwrap().is_match("Rust"));
+    }
+
+    #[test]
+    fn find_iter_returns_byte_ranges() {
+        let re = Regex::new(r"\d+").unwrap();
+        assert_eq!(vec![3..5, 9..12], re.find_iter("ab 12 ü 345"));
+        assert_eq!(vec![0..0, 1..1], Regex::new("x*").unwrap().find_iter("a"));
+    }
+
+    #[test]
+    fn long_lines_do_not_overflow_the_stack() {
+        let line = format!("x{}y", "-".repeat(20_000));
+        assert_eq!(Some(0..line.len()), Regex::new("x.*y").unwrap().find(&line));
+        let words = "word ".repeat(20_000);
+        assert_eq!(20_000, Regex::new(r"\w+").unwrap().find_iter(&words).len());
+    }
+
+    #[test]
+    fn nested_quantifiers_do_not_take_exponential_time() {
+        let text = format!("{}c", "a".repeat(28));
+        assert_eq!(None, matches("(a+)+b", &text));
+        assert_eq!(Some("aaab".into()), matches("(a+)+b", "aaab"));
+        assert_eq!(Some("".into()), matches("(a*)*?", "aa"));
+    }
+
+    #[test]
+    fn escaped_text_matches_literally() {
+        let text = r"a.b*(c)[d]{2}\e|f^$";
+        let re = Regex::new(&escape(text)).unwrap();
+        assert_eq!(Some(0..text.len()), re.find(text));
+        assert!(!re.is_match("aXb"));
+    }
+
+    #[test]
+    fn malformed_patterns_are_errors() {
+        for pattern in [
+            "(",
+            "a)",
+            "[a-",
+            "*a",
+            "a**",
+            r"\",
+            "[z-a]",
+            "^*",
+            "(a{1000}){1000}",
+        ] {
+            assert!(
+                Regex::new(pattern).is_err(),
+                "{:?} should not compile",
+                pattern
+            );
+        }
+    }
+}
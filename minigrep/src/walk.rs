@@ -0,0 +1,297 @@
+//! Recursive directory traversal.
+//!
+//! Symbolic links to directories are skipped unless `follow_symlinks` is set,
+//! because a link can point back up the tree and send the walk around in
+//! circles. When they are followed, every directory is remembered by its
+//! identity on disk, so each one is entered only once. Links to files are
+//! always searched.
+//!
+//! The `include` and `exclude` patterns are checked here, before anything is
+//! opened, so an excluded directory like `target` costs nothing to skip.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::glob;
+use crate::ignore::{self, IgnoreFile};
+
+/// How far and where a walk goes.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Limits how many directory levels are entered: files directly in the
+    /// starting directory are at depth 1. `None` means no limit.
+    pub max_depth: Option<usize>,
+    /// Skip paths listed in `.gitignore` and `.ignore` files along the way.
+    pub respect_ignore: bool,
+    /// Enter directories through symbolic links.
+    pub follow_symlinks: bool,
+    /// If not empty, only files matching one of these patterns are returned
+    /// (`--include`). Every directory is still entered.
+    pub include: Vec<String>,
+    /// Files matching any of these patterns are left out, and directories
+    /// matching one aren't entered at all (`--exclude`).
+    pub exclude: Vec<String>,
+}
+
+/// What makes a directory the same directory no matter which path reached it:
+/// the device and inode numbers on Unix, and the canonical path elsewhere.
+#[cfg(unix)]
+type DirId = (u64, u64);
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+#[cfg(unix)]
+fn dir_id(dir: &Path) -> io::Result<DirId> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = fs::metadata(dir)?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_id(dir: &Path) -> io::Result<DirId> {
+    dir.canonicalize()
+}
+
+/// The state of one walk, shared by every level of the recursion.
+struct Walk<'a> {
+    /// Where the walk started. Patterns are matched against paths relative to it,
+    /// so the directories above it can't exclude anything.
+    root: &'a Path,
+    options: &'a Options,
+    ignores: Vec<IgnoreFile>,
+    visited: HashSet<DirId>,
+    found: Vec<PathBuf>,
+}
+
+/// Returns every file below `dir`, sorted so results come out in a stable order.
+pub fn files(dir: &Path, max_depth: Option<usize>) -> io::Result<Vec<PathBuf>> {
+    files_with(
+        dir,
+        &Options {
+            max_depth,
+            ..Options::default()
+        },
+    )
+}
+
+/// Like `files`, with every option spelled out.
+pub fn files_with(dir: &Path, options: &Options) -> io::Result<Vec<PathBuf>> {
+    let mut walk = Walk {
+        root: dir,
+        options,
+        ignores: Vec::new(),
+        visited: HashSet::new(),
+        found: Vec::new(),
+    };
+    walk.visit(dir, 1)?;
+    walk.found.sort();
+    Ok(walk.found)
+}
+
+impl Walk<'_> {
+    fn visit(&mut self, dir: &Path, depth: usize) -> io::Result<()> {
+        if self.options.max_depth.is_some_and(|max| depth > max) {
+            return Ok(());
+        }
+        // Reaching a directory a second time means a link led us in a loop (or
+        // to a directory we've already searched).
+        if self.options.follow_symlinks && !self.visited.insert(dir_id(dir)?) {
+            return Ok(());
+        }
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            // Like `grep -r`, a directory we can't read is reported and skipped,
+            // and the rest of the walk goes on. Only the starting directory is an
+            // error, since then there's nothing to search at all.
+            Err(err) if depth > 1 => {
+                report(dir, &err);
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+
+        // The rules from this directory apply to everything below it, and only
+        // until we leave it again.
+        let pushed = match self.options.respect_ignore.then(|| IgnoreFile::read(dir)).flatten() {
+            Some(file) => {
+                self.ignores.push(file);
+                true
+            }
+            None => false,
+        };
+
+        for entry in entries {
+            // So is an entry we can't look at.
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    report(dir, &err);
+                    continue;
+                }
+            };
+            let path = entry.path();
+            // `file_type` describes the link itself, `is_dir` what it points to.
+            let is_link = match entry.file_type() {
+                Ok(file_type) => file_type.is_symlink(),
+                Err(err) => {
+                    report(&path, &err);
+                    continue;
+                }
+            };
+            // A link to nothing has nothing to search.
+            if is_link && fs::metadata(&path).is_err() {
+                continue;
+            }
+            let is_dir = path.is_dir();
+            if is_link && is_dir && !self.options.follow_symlinks {
+                continue;
+            }
+            if self.options.respect_ignore && ignore::is_ignored(&self.ignores, &path, is_dir) {
+                continue;
+            }
+            let name = path.strip_prefix(self.root).unwrap_or(&path).to_string_lossy();
+            if glob::matches_any(&self.options.exclude, &name) {
+                continue;
+            }
+
+            if is_dir {
+                self.visit(&path, depth + 1)?;
+            } else if self.options.include.is_empty() || glob::matches_any(&self.options.include, &name) {
+                self.found.push(path);
+            }
+        }
+
+        if pushed {
+            self.ignores.pop();
+        }
+        Ok(())
+    }
+}
+
+/// Tells the user about something the walk had to skip, the way `grep -r` does.
+fn report(path: &Path, err: &io::Error) {
+    eprintln!("minigrep: {}: {}", path.display(), err);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn ignore_files_prune_the_walk() {
+        let dir = env::temp_dir().join(format!("minigrep-walk-{}", std::process::id()));
+        fs::create_dir_all(dir.join("target/debug")).unwrap();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        for file in ["target/debug/out.rs", "src/lib.rs", "src/notes.log", "keep.log"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        fs::write(dir.join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::write(dir.join(".ignore"), "!keep.log\n").unwrap();
+
+        let options = Options {
+            respect_ignore: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            vec![
+                dir.join(".gitignore"),
+                dir.join(".ignore"),
+                dir.join("keep.log"),
+                dir.join("src/lib.rs"),
+            ],
+            files_with(&dir, &options).unwrap()
+        );
+        assert_eq!(6, files(&dir, None).unwrap().len());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn depth_and_filters_scope_the_walk() {
+        let dir = env::temp_dir().join(format!("minigrep-filters-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/deep")).unwrap();
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        for file in ["top.rs", "src/lib.rs", "src/notes.txt", "src/deep/mod.rs", "tests/it.rs"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let options = Options {
+            max_depth: Some(2),
+            include: vec![String::from("*.rs")],
+            exclude: vec![String::from("tests")],
+            ..Options::default()
+        };
+        assert_eq!(
+            vec![dir.join("src/lib.rs"), dir.join("top.rs")],
+            files_with(&dir, &options).unwrap()
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn patterns_only_see_the_path_below_the_root() {
+        let top = env::temp_dir().join(format!("minigrep-root-{}", std::process::id()));
+        let dir = top.join("build/proj");
+        fs::create_dir_all(dir.join("build")).unwrap();
+        fs::write(dir.join("main.rs"), "").unwrap();
+        fs::write(dir.join("build/out.rs"), "").unwrap();
+
+        let options = Options {
+            exclude: vec![String::from("build")],
+            ..Options::default()
+        };
+        assert_eq!(vec![dir.join("main.rs")], files_with(&dir, &options).unwrap());
+
+        fs::remove_dir_all(top).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_directories_and_cycles() {
+        use std::os::unix::fs::symlink;
+
+        let dir = env::temp_dir().join(format!("minigrep-symlinks-{}", std::process::id()));
+        fs::create_dir_all(dir.join("real")).unwrap();
+        fs::write(dir.join("real/file.txt"), "").unwrap();
+        symlink(dir.join("real"), dir.join("alias")).unwrap();
+        // A link back to the top would recurse forever without loop detection.
+        symlink(&dir, dir.join("real/loop")).unwrap();
+
+        assert_eq!(vec![dir.join("real/file.txt")], files(&dir, None).unwrap());
+
+        let options = Options {
+            follow_symlinks: true,
+            ..Options::default()
+        };
+        let found = files_with(&dir, &options).unwrap();
+        assert_eq!(1, found.len());
+        assert!(found[0].ends_with("file.txt"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn broken_links_are_skipped() {
+        use std::os::unix::fs::symlink;
+
+        let dir = env::temp_dir().join(format!("minigrep-broken-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("file.txt"), "").unwrap();
+        symlink(dir.join("missing"), dir.join("dangling")).unwrap();
+
+        assert_eq!(vec![dir.join("file.txt")], files(&dir, None).unwrap());
+        let options = Options {
+            follow_symlinks: true,
+            ..Options::default()
+        };
+        assert_eq!(vec![dir.join("file.txt")], files_with(&dir, &options).unwrap());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,5 +1,8 @@
 #![allow(unused_doc_comments)]
 
+use std::collections::VecDeque;
+use std::fmt;
+
 fn main() {
     /// # Characteristics of Object-Oriented Languages
     ///
@@ -51,33 +54,431 @@ fn main() {
 
 pub struct AveragedCollection {
     list: Vec<i32>,
+    /// The same values as `list`, kept from smallest to largest, so the median and
+    /// percentiles can be looked up without sorting.
+    sorted: Vec<i32>,
+    /// 0 while the list is empty, which isn't the average of anything, so `average` checks
+    /// for that instead of returning it.
     average: f64,
+    /// The sum of the squared distances of the values from the average, which the variance
+    /// is worked out from.
+    squared_distances: f64,
 }
 
 impl AveragedCollection {
+    pub fn new() -> AveragedCollection {
+        AveragedCollection {
+            list: Vec::new(),
+            sorted: Vec::new(),
+            average: 0.0,
+            squared_distances: 0.0,
+        }
+    }
+
     pub fn add(&mut self, value: i32) {
         self.list.push(value);
-        self.update_average();
+        let index = self.sorted.partition_point(|&sorted| sorted <= value);
+        self.sorted.insert(index, value);
+        self.update_statistics_after_add(value);
     }
 
     pub fn remove(&mut self) -> Option<i32> {
         let result = self.list.pop();
         match result {
             Some(value) => {
-                self.update_average();
+                self.remove_sorted(value);
+                self.update_statistics_after_remove(value);
                 Some(value)
             }
             None => None,
         }
     }
 
-    pub fn average(&self) -> f64 {
-        self.average
+    /// Removes one copy of `value`, wherever it is in the list, and returns whether there
+    /// was one to remove.
+    pub fn remove_value(&mut self, value: i32) -> bool {
+        match self.list.iter().position(|&v| v == value) {
+            Some(index) => {
+                self.list.remove(index);
+                self.remove_sorted(value);
+                self.update_statistics_after_remove(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The values, in the order they were added. They can be read, but not changed: the
+    /// only way to change them is through methods like `add` and `remove`, which keep the
+    /// statistics right.
+    pub fn iter(&self) -> impl Iterator<Item = &i32> {
+        self.list.iter()
+    }
+
+    /// Looks `value` up in the sorted copy, which is quicker than going through the list.
+    pub fn contains(&self, value: i32) -> bool {
+        self.sorted.binary_search(&value).is_ok()
+    }
+
+    /// `None` if there are no values. Dividing by a count of zero would give NaN, "not a
+    /// number", which spreads into every calculation it's used in, so the caller has to
+    /// decide what an empty collection means instead.
+    pub fn average(&self) -> Option<f64> {
+        if self.list.is_empty() {
+            None
+        } else {
+            Some(self.average)
+        }
+    }
+
+    /// The population variance: the average of the squared distances of the values from
+    /// their average.
+    pub fn variance(&self) -> Option<f64> {
+        if self.list.is_empty() {
+            None
+        } else {
+            Some(self.squared_distances / self.list.len() as f64)
+        }
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// The smallest value, or `None` if there aren't any. The sorted copy keeps it at the
+    /// front, so there's nothing to search for.
+    pub fn min(&self) -> Option<i32> {
+        self.sorted.first().copied()
+    }
+
+    /// The largest value, at the back of the sorted copy.
+    pub fn max(&self) -> Option<i32> {
+        self.sorted.last().copied()
+    }
+
+    /// All the statistics at once, or `None` if there are no values to have statistics.
+    pub fn summary(&self) -> Option<Summary> {
+        Some(Summary {
+            count: self.list.len(),
+            min: self.min()?,
+            max: self.max()?,
+            mean: self.average()?,
+            std_dev: self.std_dev()?,
+        })
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        self.percentile(50.0)
+    }
+
+    /// The value `p` percent of the way from the smallest value to the largest, so
+    /// `percentile(0.0)` is the smallest and `percentile(100.0)` the largest. Between two
+    /// values, it's the same fraction of the way from one to the other.
+    ///
+    /// # Panics
+    ///
+    /// If `p` isn't between 0 and 100.
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "percentile {} isn't between 0 and 100",
+            p
+        );
+        if self.sorted.is_empty() {
+            return None;
+        }
+        let rank = p / 100.0 * (self.sorted.len() - 1) as f64;
+        let below = self.sorted[rank.floor() as usize] as f64;
+        let above = self.sorted[rank.ceil() as usize] as f64;
+        Some(below + (above - below) * rank.fract())
+    }
+
+    /// Private method!
+    ///
+    /// `value` has to be in `sorted`. If there are a few copies of it, any one will do.
+    fn remove_sorted(&mut self, value: i32) {
+        if let Ok(index) = self.sorted.binary_search(&value) {
+            self.sorted.remove(index);
+        }
     }
 
     /// Private method!
-    fn update_average(&mut self) {
-        let total: i32 = self.list.iter().sum();
-        self.average = total as f64 / self.list.len() as f64;
+    ///
+    /// Adding all the values up again on every change takes longer the more values there
+    /// are. Welford's algorithm updates the average and the squared distances from the one
+    /// value that was added, which takes the same time however long the list is.
+    fn update_statistics_after_add(&mut self, value: i32) {
+        let value = value as f64;
+        let count = self.list.len() as f64;
+        let delta = value - self.average;
+        self.average += delta / count;
+        self.squared_distances += delta * (value - self.average);
+    }
+
+    /// Private method!
+    ///
+    /// Welford's algorithm run backwards, to take `value` back out.
+    fn update_statistics_after_remove(&mut self, value: i32) {
+        let value = value as f64;
+        let count = self.list.len() as f64;
+        if self.list.is_empty() {
+            self.average = 0.0;
+            self.squared_distances = 0.0;
+            return;
+        }
+        let previous_average = self.average;
+        self.average = (previous_average * (count + 1.0) - value) / count;
+        self.squared_distances -= (value - self.average) * (value - previous_average);
+        // Rounding can leave it a little under zero, which no sum of squares can be.
+        self.squared_distances = self.squared_distances.max(0.0);
+    }
+}
+
+impl Default for AveragedCollection {
+    fn default() -> AveragedCollection {
+        AveragedCollection::new()
+    }
+}
+
+/// So a collection can be used in a `for` loop, like `for value in &collection`.
+impl<'a> IntoIterator for &'a AveragedCollection {
+    type Item = &'a i32;
+    type IntoIter = std::slice::Iter<'a, i32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
+
+/// The statistics of an `AveragedCollection` with at least one value, at one moment. It's a
+/// plain struct with public fields: it's a copy, so changing it can't get the collection's
+/// statistics wrong.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub min: i32,
+    pub max: i32,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+/// One line, to put in a log:
+///
+/// ```text
+/// count=8 min=2 max=9 mean=5.00 std_dev=2.00
+/// ```
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "count={} min={} max={} mean={:.2} std_dev={:.2}",
+            self.count, self.min, self.max, self.mean, self.std_dev
+        )
+    }
+}
+
+/// Like `AveragedCollection`, but it only keeps the last `capacity` values added, and
+/// averages those. That's what monitoring usually wants, like the request rate or the
+/// latency over the last minute rather than since the program started.
+pub struct WindowedAverage {
+    /// A ring buffer: once it's full, adding a value to the back drops the oldest one from
+    /// the front.
+    window: VecDeque<i32>,
+    capacity: usize,
+    /// Kept as values come and go, so the average doesn't have to add them all up again.
+    /// It's an `i64`, so a full window of large `i32`s can't overflow it.
+    total: i64,
+}
+
+impl WindowedAverage {
+    /// # Panics
+    ///
+    /// If `capacity` is 0, since a window that can't hold any values has no average.
+    pub fn new(capacity: usize) -> WindowedAverage {
+        assert!(capacity > 0, "a window has to hold at least one value");
+        WindowedAverage {
+            window: VecDeque::with_capacity(capacity),
+            capacity,
+            total: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds `value`, dropping the oldest value if the window is full.
+    pub fn add(&mut self, value: i32) {
+        if self.window.len() == self.capacity {
+            if let Some(oldest) = self.window.pop_front() {
+                self.total -= oldest as i64;
+            }
+        }
+        self.window.push_back(value);
+        self.total += value as i64;
+    }
+
+    /// Removes the value added last, like `AveragedCollection::remove`.
+    pub fn remove(&mut self) -> Option<i32> {
+        let value = self.window.pop_back()?;
+        self.total -= value as i64;
+        Some(value)
+    }
+
+    /// `None` while the window is empty, like `AveragedCollection::average`.
+    pub fn average(&self) -> Option<f64> {
+        if self.window.is_empty() {
+            None
+        } else {
+            Some(self.total as f64 / self.window.len() as f64)
+        }
+    }
+
+    /// The values in the window, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &i32> {
+        self.window.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The variance worked out the slow way: the average first, then the squared distances
+    /// from it.
+    fn two_pass_variance(values: &[i32]) -> f64 {
+        let count = values.len() as f64;
+        let average = values.iter().map(|&v| v as f64).sum::<f64>() / count;
+        values
+            .iter()
+            .map(|&v| (v as f64 - average).powi(2))
+            .sum::<f64>()
+            / count
+    }
+
+    fn collection(values: &[i32]) -> AveragedCollection {
+        let mut collection = AveragedCollection::new();
+        for &value in values {
+            collection.add(value);
+        }
+        collection
     }
-}
\ No newline at end of file
+
+    /// Floating point sums come out a little differently in a different order, so only the
+    /// first nine or so significant digits have to agree.
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() <= 1e-9 * expected.abs().max(1.0),
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn variance_matches_working_it_out_again() {
+        let values = [2, 4, 4, 4, 5, 5, 7, 9, -1_000_000, 1_000_000, 3];
+        let mut collection = collection(&values);
+        assert_close(two_pass_variance(&values), collection.variance().unwrap());
+
+        // Taking values back out has to undo what adding them did.
+        for end in (1..values.len()).rev() {
+            collection.remove();
+            assert_close(
+                two_pass_variance(&values[..end]),
+                collection.variance().unwrap(),
+            );
+            assert_close(
+                values[..end].iter().map(|&v| v as f64).sum::<f64>() / end as f64,
+                collection.average().unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn removing_the_last_value_leaves_no_statistics() {
+        let mut collection = collection(&[7]);
+        assert_eq!(Some(0.0), collection.variance());
+
+        assert_eq!(Some(7), collection.remove());
+        assert_eq!(None, collection.average());
+        assert_eq!(None, collection.variance());
+        assert_eq!(None, collection.median());
+        assert_eq!(None, collection.summary());
+        assert_eq!(None, collection.remove());
+
+        // And it starts again from nothing.
+        collection.add(3);
+        assert_eq!(Some(3.0), collection.average());
+        assert_eq!(Some(0.0), collection.variance());
+    }
+
+    #[test]
+    fn values_can_be_removed_from_the_middle() {
+        let mut collection = collection(&[5, 1, 9, 1]);
+
+        assert!(collection.remove_value(1));
+        assert!(!collection.remove_value(4));
+        assert_eq!(
+            vec![5, 9, 1],
+            collection.iter().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(1), collection.min());
+        assert_close(
+            two_pass_variance(&[5, 9, 1]),
+            collection.variance().unwrap(),
+        );
+        assert!(collection.remove_value(1));
+        assert!(!collection.contains(1));
+    }
+
+    #[test]
+    fn median_and_percentiles() {
+        let even = collection(&[4, 1, 3, 2]);
+        assert_eq!(Some(2.5), even.median());
+        assert_eq!(Some(1.0), even.percentile(0.0));
+        assert_eq!(Some(4.0), even.percentile(100.0));
+        assert_eq!(Some(1.75), even.percentile(25.0));
+
+        let single = collection(&[6]);
+        assert_eq!(Some(6.0), single.median());
+        assert_eq!(Some(6.0), single.percentile(0.0));
+        assert_eq!(Some(6.0), single.percentile(90.0));
+
+        let empty = AveragedCollection::new();
+        assert_eq!(None, empty.median());
+        assert_eq!(None, empty.percentile(50.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't between 0 and 100")]
+    fn percentiles_over_100_panic() {
+        collection(&[1]).percentile(101.0);
+    }
+
+    #[test]
+    fn a_full_window_drops_its_oldest_value() {
+        let mut window = WindowedAverage::new(3);
+        assert_eq!(None, window.average());
+
+        window.add(1);
+        window.add(2);
+        assert_eq!(Some(1.5), window.average());
+        window.add(3);
+        window.add(10);
+        assert_eq!(vec![2, 3, 10], window.iter().copied().collect::<Vec<_>>());
+        assert_eq!(Some(5.0), window.average());
+
+        assert_eq!(Some(10), window.remove());
+        assert_eq!(Some(2.5), window.average());
+    }
+
+    #[test]
+    fn a_window_of_large_values_doesnt_overflow() {
+        let mut window = WindowedAverage::new(4);
+        for _ in 0..6 {
+            window.add(i32::MAX);
+        }
+        assert_eq!(Some(i32::MAX as f64), window.average());
+    }
+}
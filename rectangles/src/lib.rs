@@ -0,0 +1,192 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+/// A rectangle positioned by its top-left corner `(x, y)`.
+///
+/// The dimensions are generic so the same struct works for whole pixels (`u32`)
+/// as well as fractional measurements (`f64`). The struct lives in the library half
+/// of the crate so other crates (such as `gui` for hit-testing) can reuse the geometry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle<T> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+/// The arithmetic a coordinate type needs for the geometry methods.
+///
+/// Integer coordinates can overflow, so addition and multiplication are checked:
+/// `u32` uses `checked_add`/`checked_mul`, while `f64` only fails when the result
+/// is no longer finite.
+pub trait Coordinate:
+    Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self>
+{
+    fn zero() -> Self;
+    fn two() -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+impl Coordinate for u32 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn two() -> Self {
+        2
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        u32::checked_add(self, rhs)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        u32::checked_mul(self, rhs)
+    }
+}
+
+impl Coordinate for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn two() -> Self {
+        2.0
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(self + rhs).filter(|sum| sum.is_finite())
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Some(self * rhs).filter(|product| product.is_finite())
+    }
+}
+
+// Computing the area needs multiplication, and comparing two rectangles needs
+// `PartialOrd`. `Copy` lets us read the fields out of `&self` without moving them.
+impl<T: Copy + Mul<Output = T> + PartialOrd> Rectangle<T> {
+    pub fn area(&self) -> T {
+        self.width * self.height
+    }
+
+    pub fn can_hold(&self, other: &Rectangle<T>) -> bool {
+        self.width > other.width && self.height > other.height
+    }
+}
+
+impl<T: Coordinate> Rectangle<T> {
+    /// Creates a rectangle with its top-left corner at the origin.
+    pub fn new(width: T, height: T) -> Rectangle<T> {
+        Rectangle {
+            x: T::zero(),
+            y: T::zero(),
+            width,
+            height,
+        }
+    }
+
+    /// Returns `true` if the point lies inside the rectangle.
+    ///
+    /// The left and top edges are inside, the right and bottom edges are not, so
+    /// two rectangles sharing an edge never both contain the same point.
+    pub fn contains_point(&self, x: T, y: T) -> bool {
+        // Subtracting instead of computing `self.x + self.width` avoids overflow.
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+
+    /// Moves the rectangle by `(dx, dy)`, or returns `None` if the new corner
+    /// doesn't fit in `T`.
+    pub fn translate(&self, dx: T, dy: T) -> Option<Rectangle<T>> {
+        Some(Rectangle {
+            x: self.x.checked_add(dx)?,
+            y: self.y.checked_add(dy)?,
+            ..*self
+        })
+    }
+
+    /// Multiplies the width and height by `factor`, keeping the top-left corner in
+    /// place, or returns `None` if the new dimensions don't fit in `T`.
+    pub fn scale(&self, factor: T) -> Option<Rectangle<T>> {
+        Some(Rectangle {
+            width: self.width.checked_mul(factor)?,
+            height: self.height.checked_mul(factor)?,
+            ..*self
+        })
+    }
+
+    /// Returns the center point, or `None` if it doesn't fit in `T`. Integer
+    /// coordinates round toward the top-left.
+    pub fn center(&self) -> Option<(T, T)> {
+        Some((
+            self.x.checked_add(self.width / T::two())?,
+            self.y.checked_add(self.height / T::two())?,
+        ))
+    }
+}
+
+// A convenience constructor that only exists for whole-pixel rectangles.
+impl Rectangle<u32> {
+    pub fn square(size: u32) -> Rectangle<u32> {
+        Rectangle::new(size, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_point_includes_top_left_edges_only() {
+        let rect = Rectangle {
+            x: 10,
+            y: 20,
+            width: 5,
+            height: 5,
+        };
+
+        assert!(rect.contains_point(10, 20));
+        assert!(rect.contains_point(14, 24));
+        assert!(!rect.contains_point(15, 24));
+        assert!(!rect.contains_point(9, 20));
+    }
+
+    #[test]
+    fn translate_and_scale_detect_u32_overflow() {
+        let rect = Rectangle::square(10);
+
+        assert_eq!(Some(Rectangle { x: 3, y: 4, ..rect }), rect.translate(3, 4));
+        assert_eq!(None, rect.translate(1, 0).and_then(|r| r.translate(u32::MAX, 0)));
+        assert_eq!(Some(Rectangle::square(30)), rect.scale(3));
+        assert_eq!(None, rect.scale(u32::MAX));
+    }
+
+    #[test]
+    fn center_detects_u32_overflow() {
+        let rect = Rectangle {
+            x: u32::MAX - 10,
+            y: 0,
+            width: 22,
+            height: 7,
+        };
+
+        assert_eq!(None, rect.center());
+        assert_eq!(
+            Some((u32::MAX - 1, 3)),
+            Rectangle { width: 19, ..rect }.center()
+        );
+    }
+
+    #[test]
+    fn center_works_for_fractional_dimensions() {
+        let rect = Rectangle {
+            x: 1.0,
+            y: 1.0,
+            width: 3.0,
+            height: 5.0,
+        };
+
+        assert_eq!(Some((2.5, 3.5)), rect.center());
+        assert_eq!(None, rect.scale(f64::INFINITY));
+    }
+}
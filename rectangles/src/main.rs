@@ -1,9 +1,9 @@
+use std::ops::Mul;
+
 // Rust *does* include functionality to print out debugging information.
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
+// `Rectangle` derives `Debug` and lives in the library half of this crate
+// (*src/lib.rs*), where it is generic over its numeric type.
+use rectangles::Rectangle;
 
 fn main() {
     // A program that calculate the area of a rectangle.
@@ -26,6 +26,8 @@ fn main() {
     // ### Refactoring with Structs: Adding More Meaning
     // We use structs to add meaning by labelling the data.
     let rect1 = Rectangle {
+        x: 0,
+        y: 0,
         width: 30,
         height: 50,
     };
@@ -36,6 +38,8 @@ fn main() {
 
     // ### Adding Useful Functionality with Derived Traits
     let rect1 = Rectangle {
+        x: 0,
+        y: 0,
         width: 30,
         height: 50,
     };
@@ -52,6 +56,8 @@ fn main() {
     // opposed to `println!` which prints to the standard output console stream (`stdout`).
     let scale = 2;
     let mut rect1 = Rectangle {
+        x: 0,
+        y: 0,
         width: dbg!(30 * scale),
         height: 50,
     };
@@ -62,6 +68,22 @@ fn main() {
     // We can put `dbg!` around the expression `30 * scale` and, because `dbg!` returns ownership of
     // the expression's value, the `width` field will get the same value as if we didn't have the
     // `dbg!` call there.
+
+    // ### Generic Dimensions
+    // Because `Rectangle` is generic, fractional dimensions work with the same methods.
+    let wide = Rectangle::new(2.5, 4.0);
+    let narrow = Rectangle::new(1.5, 3.25);
+    println!("The area of {:?} is {}", wide, wide.area());
+    println!("Can wide hold narrow? {}", wide.can_hold(&narrow));
+
+    let sq = Rectangle::square(3);
+    println!("The area of {:?} is {}", sq, area(&sq));
+
+    // ### Points and Transforms
+    let moved = sq.translate(10, 20).expect("translation overflowed");
+    println!("Moved square: {:?}, centered at {:?}", moved, moved.center());
+    println!("Does it contain (11, 21)? {}", moved.contains_point(11, 21));
+    println!("Scaling by u32::MAX overflows: {:?}", moved.scale(u32::MAX));
 }
 
 // fn area(width: u32, height: u32) -> u32 {
@@ -74,6 +96,6 @@ fn main() {
 
 // Now defined with one parameter, whose type is an immutable borrow of a struct
 // `Rectangle` instance.
-fn area(rectangle: &Rectangle) -> u32 {
-    rectangle.width * rectangle.height
+fn area<T: Copy + Mul<Output = T> + PartialOrd>(rectangle: &Rectangle<T>) -> T {
+    rectangle.area()
 }
@@ -1,40 +1,333 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Add;
+use std::time::{Duration, Instant};
+
+pub mod messengers;
+
+/// How serious a notification is. The levels are ordered, so receivers can filter with
+/// comparisons like `level >= Level::Urgent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// Good news: usage is back under the quota, after having been over it.
+    Info,
+    /// 75% of the quota or more is used.
+    Warning,
+    /// 90% or more.
+    Urgent,
+    /// Over the quota.
+    Error,
+}
+
+impl Level {
+    /// The level for `usage`, the fraction of the quota used, if it calls for one at all.
+    /// That's never `Info`, which is only sent on the way down.
+    pub fn for_usage(usage: f64) -> Option<Level> {
+        if usage > 1.0 {
+            Some(Level::Error)
+        } else if usage >= 0.9 {
+            Some(Level::Urgent)
+        } else if usage >= 0.75 {
+            Some(Level::Warning)
+        } else {
+            None
+        }
+    }
+
+    /// What to tell the user at this level.
+    pub fn message(self) -> &'static str {
+        match self {
+            Level::Info => "Info: You're back under your quota.",
+            Level::Warning => "Warning: You've used up over 75% of your quota!",
+            Level::Urgent => "Urgent warning: You've used up over 90% of your quota!",
+            Level::Error => "Error: You are over your quota!",
+        }
+    }
+}
+
+/// What a `LimitTracker` sends: how serious it is, the text for the user, and the fraction
+/// of the quota in use when it was sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub level: Level,
+    pub text: String,
+    pub usage: f64,
+}
+
+impl Notification {
+    pub fn new(level: Level, usage: f64) -> Notification {
+        Notification {
+            level,
+            text: String::from(level.message()),
+            usage,
+        }
+    }
+}
+
+impl fmt::Display for Notification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
 /// This `Messenger` trait has one method called `send` that takes an immutable reference to `self`
-/// and the text of the message. This trait is the interface our mock object needs to implement so
+/// and the notification to send. This trait is the interface our mock object needs to implement so
 /// that the mock can be used in the same way a real object is.
 pub trait Messenger {
+    fn send(&self, notification: &Notification);
+}
+
+/// The interface `Messenger` used to have, which only got the text of each message. Types
+/// that implement it are `Messenger`s too, through the blanket implementation below, so
+/// they keep working unchanged.
+pub trait TextMessenger {
     fn send(&self, msg: &str);
 }
 
-pub struct LimitTracker<'a, T: Messenger> {
+impl<M: TextMessenger> Messenger for M {
+    fn send(&self, notification: &Notification) {
+        TextMessenger::send(self, &notification.text);
+    }
+}
+
+/// Something a quota can be set for: a number of bytes, an amount of money, some time.
+/// `Default` has to be zero, which it is for the standard library's number types and
+/// `Duration`. `Add` is for `LimitTracker::add_usage`.
+pub trait Quota: Copy + PartialOrd + Default + Add<Output = Self> {
+    /// What fraction of `max` this is: 0.5 is half, and more than 1.0 is over.
+    fn ratio(self, max: Self) -> f64;
+}
+
+impl Quota for usize {
+    fn ratio(self, max: usize) -> f64 {
+        self as f64 / max as f64
+    }
+}
+
+impl Quota for u32 {
+    fn ratio(self, max: u32) -> f64 {
+        self as f64 / max as f64
+    }
+}
+
+impl Quota for u64 {
+    fn ratio(self, max: u64) -> f64 {
+        self as f64 / max as f64
+    }
+}
+
+impl Quota for f64 {
+    fn ratio(self, max: f64) -> f64 {
+        self / max
+    }
+}
+
+impl Quota for Duration {
+    fn ratio(self, max: Duration) -> f64 {
+        self.as_secs_f64() / max.as_secs_f64()
+    }
+}
+
+/// One `set_value` call, as `LimitTracker::history` remembers it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample<Q = usize> {
+    pub value: Q,
+    /// The fraction of the quota `value` was.
+    pub usage: f64,
+    pub at: Instant,
+}
+
+/// How many samples a `LimitTracker` keeps, unless it's made with `with_history`.
+pub const HISTORY_LEN: usize = 64;
+
+/// How far under a level's threshold usage has to drop before that level can be sent
+/// again, unless it's changed with `set_hysteresis`.
+pub const HYSTERESIS: f64 = 0.05;
+
+/// A `LimitTracker` sends one notification when usage goes up into a level, not one on every
+/// `set_value` while it stays there. Going up to a higher level sends again.
+///
+/// A level is *re-armed*, so it's sent again the next time usage reaches it, only once usage
+/// has dropped below its threshold by more than the *hysteresis* margin. Without the margin,
+/// usage going back and forth right at a threshold, like 89% and 90%, would send a
+/// notification every other time.
+///
+/// Closures registered with `on_threshold` are called the same way: once when usage goes up
+/// past their threshold, and again only after it has dropped below it by the margin.
+///
+/// What's counted is a `Q`, a `usize` unless the tracker is made with `with_quota`.
+pub struct LimitTracker<'a, T: Messenger, Q: Quota = usize> {
     messenger: &'a T,
-    value: usize,
-    max: usize,
+    value: Q,
+    max: Q,
+    /// The most recent samples, oldest first. Once it's full, each new sample pushes the
+    /// oldest one out, so it's a ring buffer.
+    history: VecDeque<Sample<Q>>,
+    history_len: usize,
+    max_observed: Option<Q>,
+    /// The level of the last notification sent, if it hasn't been re-armed since.
+    alerted: Option<Level>,
+    hysteresis: f64,
+    thresholds: Vec<Threshold<'a, Q>>,
+}
+
+/// A callback registered with `LimitTracker::on_threshold`.
+struct Threshold<'a, Q> {
+    fraction: f64,
+    /// Whether the callback will be called the next time usage reaches `fraction`.
+    armed: bool,
+    callback: Box<dyn FnMut(Q) + 'a>,
 }
 
+/// These constructors count a `usize`. A generic `new` would leave `LimitTracker::new(&m, 100)`
+/// without a type for `Q`: Rust doesn't fall back to a type parameter's default when inferring
+/// types, and an integer literal could be any of the integer `Quota`s.
 impl<'a, T> LimitTracker<'a, T>
     where
         T: Messenger {
     pub fn new(messenger: &'a T, max: usize) -> LimitTracker<'a, T> {
+        LimitTracker::with_history(messenger, max, HISTORY_LEN)
+    }
+
+    /// A tracker that keeps the last `history_len` samples.
+    pub fn with_history(messenger: &'a T, max: usize, history_len: usize) -> LimitTracker<'a, T> {
+        LimitTracker::with_quota_and_history(messenger, max, history_len)
+    }
+}
+
+impl<'a, T, Q> LimitTracker<'a, T, Q>
+    where
+        T: Messenger,
+        Q: Quota {
+    /// A tracker of a quota of `max`, of whatever type that is.
+    pub fn with_quota(messenger: &'a T, max: Q) -> LimitTracker<'a, T, Q> {
+        LimitTracker::with_quota_and_history(messenger, max, HISTORY_LEN)
+    }
+
+    pub fn with_quota_and_history(messenger: &'a T, max: Q, history_len: usize) -> LimitTracker<'a, T, Q> {
         LimitTracker {
             messenger,
-            value: 0,
+            value: Q::default(),
             max,
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+            max_observed: None,
+            alerted: None,
+            hysteresis: HYSTERESIS,
+            thresholds: Vec::new(),
         }
     }
 
-    pub fn set_value(&mut self, value: usize) {
+    /// Calls `callback` with the value whenever usage goes up to `fraction` of the quota or
+    /// past it, like `0.5` for half. Unlike a `Messenger`, it can be any closure, and it can
+    /// borrow whatever lives as long as the tracker's messenger.
+    pub fn on_threshold(&mut self, fraction: f64, callback: impl FnMut(Q) + 'a) {
+        self.thresholds.push(Threshold {
+            fraction,
+            armed: true,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// How far under a level's threshold usage has to drop to re-arm it, as a fraction of
+    /// the quota.
+    pub fn set_hysteresis(&mut self, margin: f64) {
+        self.hysteresis = margin;
+    }
+
+    /// The most recent `set_value` calls, oldest first.
+    pub fn history(&self) -> &VecDeque<Sample<Q>> {
+        &self.history
+    }
+
+    /// The highest value ever set, including ones that have dropped out of the history.
+    pub fn max_observed(&self) -> Option<Q> {
+        self.max_observed
+    }
+
+    pub fn set_value(&mut self, value: Q) {
         self.value = value;
 
-        let percentage_of_max = self.value as f64 / self.max as f64;
+        let percentage_of_max = self.value.ratio(self.max);
+        self.record(value, percentage_of_max);
+        self.notify(value, percentage_of_max);
+    }
+
+    pub fn value(&self) -> Q {
+        self.value
+    }
+
+    pub fn max(&self) -> Q {
+        self.max
+    }
+
+    /// Changes the quota. The value stays, but it's a different fraction of the new quota,
+    /// so the messenger and callbacks are told about that right away, like after
+    /// `set_value`. It isn't added to the history, since the value didn't change.
+    pub fn set_max(&mut self, max: Q) {
+        self.max = max;
+        self.notify(self.value, self.value.ratio(self.max));
+    }
+
+    /// Adds `delta` to the value, and goes on like `set_value`.
+    pub fn add_usage(&mut self, delta: Q) {
+        self.set_value(self.value + delta);
+    }
+
+    /// Sets the value back to zero, like at the start of a new billing period, which
+    /// re-arms every level and threshold. The history is kept.
+    pub fn reset(&mut self) {
+        self.set_value(Q::default());
+    }
+
+    /// Everything that reacts to usage changing is told from here: the messenger about
+    /// levels, and the callbacks about their thresholds.
+    fn notify(&mut self, value: Q, percentage_of_max: f64) {
+        // Usage `hysteresis` higher than it is says which levels are still too close to
+        // re-arm. `Option` orders `None` below every `Some`, so `None` means all of them are.
+        let still_close = Level::for_usage(percentage_of_max + self.hysteresis);
+        if self.alerted > still_close {
+            if self.alerted == Some(Level::Error) {
+                self.messenger.send(&Notification::new(Level::Info, percentage_of_max));
+            }
+            self.alerted = still_close;
+        }
 
-        if percentage_of_max > 1.0 {
-            self.messenger.send("Error: You are over your quota!");
-        } else if percentage_of_max >= 0.9 {
-            self.messenger.send("Urgent warning: You've used up over 90% of your quota!");
-        } else if percentage_of_max >= 0.75 {
-            self.messenger.send("Warning: You've used up over 75% of your quota!");
+        let level = Level::for_usage(percentage_of_max);
+        if level > self.alerted {
+            if let Some(level) = level {
+                self.messenger.send(&Notification::new(level, percentage_of_max));
+            }
+            self.alerted = level;
+        }
+
+        for threshold in self.thresholds.iter_mut() {
+            if percentage_of_max + self.hysteresis < threshold.fraction {
+                threshold.armed = true;
+            } else if threshold.armed && percentage_of_max >= threshold.fraction {
+                threshold.armed = false;
+                (threshold.callback)(value);
+            }
         }
     }
+
+    fn record(&mut self, value: Q, usage: f64) {
+        // `Q` is only `PartialOrd`, so there's no `max` method to do this.
+        if self.max_observed.is_none_or(|max| value > max) {
+            self.max_observed = Some(value);
+        }
+        if self.history_len == 0 {
+            return;
+        }
+        if self.history.len() == self.history_len {
+            self.history.pop_front();
+        }
+        self.history.push_back(Sample {
+            value,
+            usage,
+            at: Instant::now(),
+        });
+    }
 }
 
 #[cfg(test)]
@@ -44,7 +337,7 @@ mod tests {
 
     /// This is a situation in which interior mutability can help!
     struct MockMessenger {
-        sent_messages: RefCell<Vec<String>>,
+        sent_messages: RefCell<Vec<Notification>>,
     }
 
     impl MockMessenger {
@@ -55,18 +348,18 @@ mod tests {
         }
     }
 
-    /// We call `borrow_mut` on the `RefCell<Vec<String>>` in `self.sent_messages` to get
-    /// a mutable reference to the value inside the `RefCell<Vec<String>>`, which is the vector.
+    /// We call `borrow_mut` on the `RefCell<Vec<Notification>>` in `self.sent_messages` to get
+    /// a mutable reference to the value inside the `RefCell<Vec<Notification>>`, which is the vector.
     impl Messenger for MockMessenger {
-        fn send(&self, msg: &str) {
-            self.sent_messages.borrow_mut().push(String::from(msg))
+        fn send(&self, notification: &Notification) {
+            self.sent_messages.borrow_mut().push(notification.clone())
 
             // Will panic:
             // let mut one_borrow = self.sent_messages.borrow_mut();
             // let mut two_borrow = self.sent_messages.borrow_mut();
             //
-            // one_borrow.push(String::from(msg));
-            // two_borrow.push(String::from(msg));
+            // one_borrow.push(notification.clone());
+            // two_borrow.push(notification.clone());
         }
     }
 
@@ -80,4 +373,240 @@ mod tests {
 
         assert_eq!(mock_messenger.sent_messages.borrow().len(), 1);
     }
+
+    #[test]
+    fn notifications_say_how_serious_they_are() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        for value in [10, 80, 95, 101] {
+            limit_tracker.set_value(value);
+        }
+
+        let sent = mock_messenger.sent_messages.borrow();
+        let levels: Vec<Level> = sent.iter().map(|n| n.level).collect();
+        assert_eq!(levels, vec![Level::Warning, Level::Urgent, Level::Error]);
+        assert_eq!(sent[0].usage, 0.8);
+        assert_eq!(sent[2].text, "Error: You are over your quota!");
+    }
+
+    #[test]
+    fn receivers_can_filter_by_level() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        for value in [80, 95, 101] {
+            limit_tracker.set_value(value);
+        }
+
+        let serious = mock_messenger
+            .sent_messages
+            .borrow()
+            .iter()
+            .filter(|n| n.level >= Level::Urgent)
+            .count();
+        assert_eq!(serious, 2);
+    }
+
+    #[test]
+    fn it_remembers_every_value_it_was_set_to() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 200);
+
+        for value in [10, 150, 40] {
+            limit_tracker.set_value(value);
+        }
+
+        let history = limit_tracker.history();
+        let values: Vec<usize> = history.iter().map(|sample| sample.value).collect();
+        assert_eq!(values, vec![10, 150, 40]);
+        assert_eq!(history[1].usage, 0.75);
+        assert!(history[0].at <= history[1].at && history[1].at <= history[2].at);
+        assert_eq!(limit_tracker.max_observed(), Some(150));
+    }
+
+    #[test]
+    fn old_samples_drop_out_of_the_history() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::with_history(&mock_messenger, 100, 3);
+        assert_eq!(limit_tracker.max_observed(), None);
+
+        for value in [90, 1, 2, 3, 4] {
+            limit_tracker.set_value(value);
+        }
+
+        let values: Vec<usize> = limit_tracker.history().iter().map(|sample| sample.value).collect();
+        assert_eq!(values, vec![2, 3, 4]);
+        assert_eq!(limit_tracker.max_observed(), Some(90));
+    }
+
+    #[test]
+    fn staying_at_a_level_sends_one_notification() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        for value in [91, 92, 91, 95, 90] {
+            limit_tracker.set_value(value);
+        }
+
+        assert_eq!(mock_messenger.sent_messages.borrow().len(), 1);
+    }
+
+    #[test]
+    fn a_level_is_sent_again_after_dropping_below_the_margin() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+        limit_tracker.set_hysteresis(0.05);
+
+        // 88% is within the margin of 90%, so going back up doesn't send again.
+        for value in [91, 88, 91] {
+            limit_tracker.set_value(value);
+        }
+        assert_eq!(mock_messenger.sent_messages.borrow().len(), 1);
+
+        // 84% is further, and re-arms it. Still over 75%, so that's not sent again.
+        for value in [84, 91] {
+            limit_tracker.set_value(value);
+        }
+        let levels: Vec<Level> = mock_messenger.sent_messages.borrow().iter().map(|n| n.level).collect();
+        assert_eq!(levels, vec![Level::Urgent, Level::Urgent]);
+
+        // Dropping away from every level re-arms them all.
+        for value in [10, 80] {
+            limit_tracker.set_value(value);
+        }
+        assert_eq!(mock_messenger.sent_messages.borrow().last().unwrap().level, Level::Warning);
+    }
+
+    #[test]
+    fn quotas_can_be_bytes_dollars_or_time() {
+        let mock_messenger = MockMessenger::new();
+
+        let mut bytes = LimitTracker::with_quota(&mock_messenger, 4_000_000_000_u64);
+        bytes.set_value(3_200_000_000);
+        let mut dollars = LimitTracker::with_quota(&mock_messenger, 20.0);
+        dollars.set_value(19.5);
+        let mut time = LimitTracker::with_quota(&mock_messenger, Duration::from_secs(60));
+        time.set_value(Duration::from_secs(61));
+
+        let levels: Vec<Level> = mock_messenger.sent_messages.borrow().iter().map(|n| n.level).collect();
+        assert_eq!(levels, vec![Level::Warning, Level::Urgent, Level::Error]);
+        assert_eq!(dollars.max_observed(), Some(19.5));
+        assert_eq!(time.history()[0].value, Duration::from_secs(61));
+    }
+
+    #[test]
+    fn callbacks_are_called_when_usage_goes_past_their_threshold() {
+        let mock_messenger = MockMessenger::new();
+        let halfway = RefCell::new(vec![]);
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+        limit_tracker.on_threshold(0.5, |value| halfway.borrow_mut().push(value));
+
+        // 47% is within the margin, so going back up to 55% doesn't call it again, but 40%
+        // re-arms it.
+        for value in [20, 50, 60, 47, 55, 40, 70] {
+            limit_tracker.set_value(value);
+        }
+
+        assert_eq!(*halfway.borrow(), vec![50, 70]);
+        assert!(mock_messenger.sent_messages.borrow().is_empty());
+    }
+
+    #[test]
+    fn callbacks_and_messengers_are_told_about_the_same_values() {
+        let mock_messenger = MockMessenger::new();
+        let calls = RefCell::new(vec![]);
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+        limit_tracker.on_threshold(0.75, |value| calls.borrow_mut().push(("75%", value)));
+        limit_tracker.on_threshold(1.0, |value| calls.borrow_mut().push(("full", value)));
+
+        limit_tracker.set_value(120);
+
+        assert_eq!(*calls.borrow(), vec![("75%", 120), ("full", 120)]);
+        assert_eq!(mock_messenger.sent_messages.borrow()[0].level, Level::Error);
+    }
+
+    #[test]
+    fn raising_the_quota_sends_back_under_quota() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        limit_tracker.set_value(110);
+        limit_tracker.set_max(200);
+
+        let sent = mock_messenger.sent_messages.borrow();
+        let levels: Vec<Level> = sent.iter().map(|n| n.level).collect();
+        assert_eq!(levels, vec![Level::Error, Level::Info]);
+        assert_eq!(sent[1].usage, 0.55);
+        assert_eq!(limit_tracker.history().len(), 1);
+    }
+
+    #[test]
+    fn lowering_the_quota_sends_right_away() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        limit_tracker.set_value(50);
+        limit_tracker.set_max(60);
+
+        let sent = mock_messenger.sent_messages.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].level, Level::Warning);
+    }
+
+    #[test]
+    fn add_usage_adds_to_the_value() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        for delta in [30, 30, 30] {
+            limit_tracker.add_usage(delta);
+        }
+
+        assert_eq!(limit_tracker.value(), 90);
+        assert_eq!(mock_messenger.sent_messages.borrow()[0].level, Level::Urgent);
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let mock_messenger = MockMessenger::new();
+        let calls = RefCell::new(0);
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+        limit_tracker.on_threshold(0.5, |_| *calls.borrow_mut() += 1);
+
+        limit_tracker.set_value(101);
+        limit_tracker.reset();
+        limit_tracker.add_usage(101);
+
+        let levels: Vec<Level> = mock_messenger.sent_messages.borrow().iter().map(|n| n.level).collect();
+        assert_eq!(levels, vec![Level::Error, Level::Info, Level::Error]);
+        assert_eq!(*calls.borrow(), 2);
+        assert_eq!(limit_tracker.max_observed(), Some(101));
+    }
+
+    /// A messenger written against the old interface, which only gets the text.
+    struct TextMock {
+        sent_messages: RefCell<Vec<String>>,
+    }
+
+    impl TextMessenger for TextMock {
+        fn send(&self, msg: &str) {
+            self.sent_messages.borrow_mut().push(String::from(msg));
+        }
+    }
+
+    #[test]
+    fn text_messengers_still_get_the_messages() {
+        let text_mock = TextMock {
+            sent_messages: RefCell::new(vec![]),
+        };
+        let mut limit_tracker = LimitTracker::new(&text_mock, 100);
+
+        limit_tracker.set_value(92);
+
+        assert_eq!(
+            *text_mock.sent_messages.borrow(),
+            vec!["Urgent warning: You've used up over 90% of your quota!"]
+        );
+    }
 }
\ No newline at end of file
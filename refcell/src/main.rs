@@ -45,12 +45,12 @@ fn main() {
     ///
     /// Here is a recap of the reasons to choose `Box<T>`, `Rc<T>`, or `RefCell<T>`:
     /// * `Rc<T>` enables multiple owners of the same data; `Box<T>` and `RefCell<T>`
-    /// have single owners.
+    ///   have single owners.
     /// * `Box<T>` allows immutable or mutable borrows checked at compile time; `Rc<T>`
-    /// allows only immutable borrows checked at compile time; `RefCell<T>` allows immutable
-    /// or mutable borrows checked at runtime.
+    ///   allows only immutable borrows checked at compile time; `RefCell<T>` allows immutable
+    ///   or mutable borrows checked at runtime.
     /// * Because `RefCell<T>` allows mutable borrows checked at runtime, we **can mutate**
-    /// the value inside the `RefCell<T>` even when the `RefCell<T>` is immutable.
+    ///   the value inside the `RefCell<T>` even when the `RefCell<T>` is immutable.
     ///
     /// Mutating the value inside an immutable value is the *interior mutability* pattern.
     ///
@@ -115,6 +115,8 @@ fn main() {
     println!("c after = {:?}", c);
 }
 
+// The fields are only read by the `Debug` output, which dead code analysis doesn't count.
+#[allow(dead_code)]
 #[derive(Debug)]
 enum List {
     Cons(Rc<RefCell<i32>>, Rc<List>),
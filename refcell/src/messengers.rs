@@ -0,0 +1,140 @@
+//! Messengers that are ready to use, so seeing a `LimitTracker`'s notifications doesn't take
+//! writing a `Messenger` first.
+
+use std::cell::RefCell;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Messenger, Notification};
+
+/// Prints each notification's text on the standard error.
+#[derive(Debug, Default)]
+pub struct StderrMessenger;
+
+impl Messenger for StderrMessenger {
+    fn send(&self, notification: &Notification) {
+        eprintln!("{}", notification);
+    }
+}
+
+/// Appends each notification to a log file, one line each, after the time it was sent in
+/// seconds since the Unix epoch:
+///
+/// ```text
+/// 1700000000.250 Urgent Urgent warning: You've used up over 90% of your quota!
+/// ```
+///
+/// `send` takes `&self`, but writing to a file needs a `&mut File`. Like the mock messenger
+/// in the tests, this keeps what it changes in a `RefCell`.
+#[derive(Debug)]
+pub struct FileMessenger {
+    file: RefCell<File>,
+}
+
+impl FileMessenger {
+    /// Opens the log at `path`, making it if it isn't there. What's in it already is kept.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<FileMessenger> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FileMessenger {
+            file: RefCell::new(file),
+        })
+    }
+}
+
+impl Messenger for FileMessenger {
+    /// `send` can't return an error, so one writing the log is printed on the standard
+    /// error instead, and the notification is lost.
+    fn send(&self, notification: &Notification) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let line = format!(
+            "{}.{:03} {:?} {}",
+            now.as_secs(),
+            now.subsec_millis(),
+            notification.level,
+            notification.text
+        );
+        if let Err(e) = writeln!(self.file.borrow_mut(), "{}", line) {
+            eprintln!("couldn't write to the quota log: {}", e);
+        }
+    }
+}
+
+/// Sends each notification down a channel, to be handled on the other end, which may be
+/// another thread.
+#[derive(Debug)]
+pub struct ChannelMessenger {
+    sender: Sender<Notification>,
+}
+
+impl ChannelMessenger {
+    pub fn new(sender: Sender<Notification>) -> ChannelMessenger {
+        ChannelMessenger { sender }
+    }
+}
+
+impl Messenger for ChannelMessenger {
+    /// Once the receiving end is dropped, nobody is listening, and notifications are
+    /// dropped too.
+    fn send(&self, notification: &Notification) {
+        let _ = self.sender.send(notification.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Level, LimitTracker};
+    use std::env;
+    use std::fs;
+    use std::sync::mpsc;
+
+    #[test]
+    fn file_messenger_appends_timestamped_lines() {
+        let path = env::temp_dir().join(format!("refcell-quota-{}.log", std::process::id()));
+        fs::write(&path, "earlier line\n").unwrap();
+
+        let messenger = FileMessenger::open(&path).unwrap();
+        let mut limit_tracker = LimitTracker::new(&messenger, 100);
+        limit_tracker.set_value(80);
+        limit_tracker.set_value(101);
+
+        let log = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "earlier line");
+        assert!(lines[1].ends_with(" Warning Warning: You've used up over 75% of your quota!"));
+        assert!(lines[2].ends_with(" Error Error: You are over your quota!"));
+
+        let (time, _) = lines[1].split_once(' ').unwrap();
+        let (secs, millis) = time.split_once('.').unwrap();
+        assert!(secs.parse::<u64>().unwrap() > 0);
+        assert_eq!(millis.len(), 3);
+    }
+
+    #[test]
+    fn channel_messenger_sends_notifications_down_the_channel() {
+        let (sender, receiver) = mpsc::channel();
+        let messenger = ChannelMessenger::new(sender);
+        let mut limit_tracker = LimitTracker::new(&messenger, 100);
+        limit_tracker.set_value(95);
+
+        let notification = receiver.try_recv().unwrap();
+        assert_eq!(notification.level, Level::Urgent);
+        assert_eq!(notification.usage, 0.95);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn channel_messenger_without_a_receiver_drops_notifications() {
+        let (sender, receiver) = mpsc::channel();
+        drop(receiver);
+        let messenger = ChannelMessenger::new(sender);
+        LimitTracker::new(&messenger, 100).set_value(101);
+    }
+}
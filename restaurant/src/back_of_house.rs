@@ -0,0 +1,292 @@
+//! The kitchen: what's in the pantry, and what each dish takes to make.
+//!
+//! Cooking an order uses up the ingredients its dishes' recipes call for. Either the whole
+//! order can be made, and everything it takes is used up at once, or nothing is touched, so
+//! a kitchen never ends up with half an order's ingredients gone.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::front_of_house::serving::Order;
+
+/// What it takes to make a dish: how much of each ingredient, in whatever unit that
+/// ingredient is counted in, like grams of flour or eggs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    pub dish: String,
+    pub ingredients: Vec<(String, u32)>,
+}
+
+impl Recipe {
+    pub fn new(dish: impl Into<String>, ingredients: &[(&str, u32)]) -> Recipe {
+        Recipe {
+            dish: dish.into(),
+            ingredients: ingredients
+                .iter()
+                .map(|(name, amount)| (name.to_string(), *amount))
+                .collect(),
+        }
+    }
+}
+
+/// Why something can't be cooked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KitchenError {
+    /// Nobody in the kitchen knows how to make this dish.
+    NoRecipe(String),
+    /// There isn't enough of an ingredient.
+    Shortage {
+        ingredient: String,
+        needed: u32,
+        in_stock: u32,
+    },
+}
+
+impl fmt::Display for KitchenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KitchenError::NoRecipe(dish) => write!(f, "there's no recipe for {}", dish),
+            KitchenError::Shortage {
+                ingredient,
+                needed,
+                in_stock,
+            } => write!(
+                f,
+                "not enough {}: {} needed, {} in stock",
+                ingredient, needed, in_stock
+            ),
+        }
+    }
+}
+
+impl Error for KitchenError {}
+
+/// How much of each ingredient there is.
+#[derive(Debug, Default)]
+pub struct Inventory {
+    stock: BTreeMap<String, u32>,
+    /// At or below its level, an ingredient is low on stock.
+    low_levels: BTreeMap<String, u32>,
+}
+
+impl Inventory {
+    pub fn new() -> Inventory {
+        Inventory::default()
+    }
+
+    /// How much of `ingredient` there is. An ingredient the kitchen never had has none.
+    pub fn stock(&self, ingredient: &str) -> u32 {
+        self.stock.get(ingredient).copied().unwrap_or(0)
+    }
+
+    /// Adds `amount` of `ingredient` to what's there.
+    pub fn restock(&mut self, ingredient: &str, amount: u32) {
+        *self.stock.entry(ingredient.to_string()).or_insert(0) += amount;
+    }
+
+    /// Sets how low `ingredient` can get before it shows up in `low_stock`.
+    pub fn set_low_level(&mut self, ingredient: &str, level: u32) {
+        self.low_levels.insert(ingredient.to_string(), level);
+    }
+
+    /// The ingredients that are at or below their low level, with how much of each is left.
+    pub fn low_stock(&self) -> Vec<(&str, u32)> {
+        self.low_levels
+            .iter()
+            .map(|(ingredient, level)| (ingredient, self.stock(ingredient), *level))
+            .filter(|(_, stock, level)| stock <= level)
+            .map(|(ingredient, stock, _)| (ingredient.as_str(), stock))
+            .collect()
+    }
+
+    /// Checks there's enough of everything `recipes` need, all together.
+    pub fn can_make<'a>(
+        &self,
+        recipes: impl IntoIterator<Item = &'a Recipe>,
+    ) -> Result<(), KitchenError> {
+        for (ingredient, needed) in Inventory::total(recipes) {
+            let in_stock = self.stock(ingredient);
+            if in_stock < needed {
+                return Err(KitchenError::Shortage {
+                    ingredient: ingredient.to_string(),
+                    needed,
+                    in_stock,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Uses up the ingredients of `recipe`, if there's enough of all of them.
+    pub fn consume(&mut self, recipe: &Recipe) -> Result<(), KitchenError> {
+        self.consume_all([recipe])
+    }
+
+    fn consume_all<'a>(
+        &mut self,
+        recipes: impl IntoIterator<Item = &'a Recipe> + Clone,
+    ) -> Result<(), KitchenError> {
+        self.can_make(recipes.clone())?;
+        for (ingredient, needed) in Inventory::total(recipes) {
+            if let Some(stock) = self.stock.get_mut(ingredient) {
+                *stock -= needed;
+            }
+        }
+        Ok(())
+    }
+
+    /// How much of each ingredient `recipes` need, added up, since two dishes may need
+    /// the same one.
+    fn total<'a>(recipes: impl IntoIterator<Item = &'a Recipe>) -> BTreeMap<&'a str, u32> {
+        let mut total = BTreeMap::new();
+        for recipe in recipes {
+            for (ingredient, amount) in &recipe.ingredients {
+                *total.entry(ingredient.as_str()).or_insert(0) += amount;
+            }
+        }
+        total
+    }
+}
+
+/// The recipes the cooks know, and the inventory they cook from.
+#[derive(Debug, Default)]
+pub struct Kitchen {
+    pub inventory: Inventory,
+    recipes: BTreeMap<String, Recipe>,
+}
+
+impl Kitchen {
+    pub fn new() -> Kitchen {
+        Kitchen::default()
+    }
+
+    pub fn add_recipe(&mut self, recipe: Recipe) {
+        self.recipes.insert(recipe.dish.clone(), recipe);
+    }
+
+    /// Cooks every dish in `order`, using up their ingredients.
+    pub fn cook_order(&mut self, order: &Order) -> Result<(), KitchenError> {
+        let recipes = recipes_for(&self.recipes, order)?;
+        self.inventory.consume_all(recipes.iter().copied())
+    }
+
+    /// Makes `order` again, after it came out wrong. It's cooked from scratch, so it takes
+    /// as much as it did the first time. If that's more than there is, nothing is used up,
+    /// and the error says what's missing, so the server can tell the table.
+    pub fn fix_incorrect_order(&mut self, order: &Order) -> Result<(), KitchenError> {
+        self.cook_order(order)
+    }
+
+    /// Whether `order` could be cooked now, without cooking it.
+    pub fn can_cook(&self, order: &Order) -> Result<(), KitchenError> {
+        self.inventory.can_make(recipes_for(&self.recipes, order)?)
+    }
+}
+
+/// The recipe of each dish in `order`. It borrows only the recipes, not the whole kitchen,
+/// so the inventory can still be changed while they're borrowed.
+fn recipes_for<'a>(
+    recipes: &'a BTreeMap<String, Recipe>,
+    order: &Order,
+) -> Result<Vec<&'a Recipe>, KitchenError> {
+    order
+        .items()
+        .iter()
+        .map(|item| {
+            recipes
+                .get(&item.name)
+                .ok_or_else(|| KitchenError::NoRecipe(item.name.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::hosting::TableId;
+    use crate::front_of_house::serving::{MenuItem, Orders};
+
+    fn kitchen() -> Kitchen {
+        let mut kitchen = Kitchen::new();
+        kitchen.add_recipe(Recipe::new("Pancakes", &[("flour", 100), ("egg", 1)]));
+        kitchen.add_recipe(Recipe::new("Omelette", &[("egg", 3)]));
+        kitchen.inventory.restock("flour", 1000);
+        kitchen.inventory.restock("egg", 4);
+        kitchen
+    }
+
+    fn order(dishes: &[&str]) -> Order {
+        let mut orders = Orders::new();
+        let items = dishes
+            .iter()
+            .map(|dish| MenuItem::new(*dish, 500))
+            .collect();
+        let id = orders.take_order(TableId(1), items);
+        orders.get(id).unwrap().clone()
+    }
+
+    #[test]
+    fn cooking_uses_up_ingredients() {
+        let mut kitchen = kitchen();
+        kitchen
+            .cook_order(&order(&["Pancakes", "Omelette"]))
+            .unwrap();
+        assert_eq!(900, kitchen.inventory.stock("flour"));
+        assert_eq!(0, kitchen.inventory.stock("egg"));
+    }
+
+    #[test]
+    fn a_short_order_uses_up_nothing() {
+        let mut kitchen = kitchen();
+        // Each dish alone could be made, but not both.
+        let error = kitchen
+            .cook_order(&order(&["Omelette", "Omelette"]))
+            .unwrap_err();
+        assert_eq!(
+            KitchenError::Shortage {
+                ingredient: "egg".to_string(),
+                needed: 6,
+                in_stock: 4
+            },
+            error
+        );
+        assert_eq!(4, kitchen.inventory.stock("egg"));
+        assert_eq!(
+            Err(KitchenError::NoRecipe("Soup".to_string())),
+            kitchen.cook_order(&order(&["Soup"]))
+        );
+    }
+
+    #[test]
+    fn a_remake_needs_the_ingredients_again() {
+        let mut kitchen = kitchen();
+        let omelette = order(&["Omelette"]);
+        kitchen.cook_order(&omelette).unwrap();
+        assert!(kitchen.fix_incorrect_order(&omelette).is_err());
+
+        kitchen.inventory.restock("egg", 2);
+        assert!(kitchen.can_cook(&omelette).is_ok());
+        kitchen.fix_incorrect_order(&omelette).unwrap();
+        assert_eq!(0, kitchen.inventory.stock("egg"));
+    }
+
+    #[test]
+    fn low_stock_is_at_or_below_its_level() {
+        let mut inventory = Inventory::new();
+        inventory.restock("flour", 500);
+        inventory.restock("egg", 6);
+        inventory.set_low_level("flour", 200);
+        inventory.set_low_level("egg", 6);
+        inventory.set_low_level("milk", 1);
+        assert_eq!(vec![("egg", 6), ("milk", 0)], inventory.low_stock());
+
+        inventory
+            .consume(&Recipe::new("Bread", &[("flour", 300)]))
+            .unwrap();
+        assert_eq!(
+            vec![("egg", 6), ("flour", 200), ("milk", 0)],
+            inventory.low_stock()
+        );
+    }
+}
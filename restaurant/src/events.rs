@@ -0,0 +1,270 @@
+//! A log of everything that happens in the restaurant during the day, and the report made
+//! from it at closing time.
+//!
+//! Entries are only ever added to the log, never changed or taken out, so the log is a
+//! history that can always be trusted, and any report can be worked out from it again.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use crate::feedback::{self, DishRating};
+use crate::front_of_house::hosting::{Party, TableId};
+use crate::front_of_house::serving::OrderId;
+
+/// Something that happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Seated {
+        table: TableId,
+        party: Party,
+    },
+    Ordered {
+        order: OrderId,
+        table: TableId,
+    },
+    Cooked {
+        order: OrderId,
+    },
+    Served {
+        order: OrderId,
+    },
+    /// `amount` is in cents.
+    Paid {
+        order: OrderId,
+        amount: u32,
+    },
+    /// A guest gave `dish`, from `order`, 1 to 5 stars.
+    Rated {
+        order: OrderId,
+        dish: String,
+        stars: u8,
+    },
+}
+
+/// An event, and when it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub at: SystemTime,
+    pub event: Event,
+}
+
+#[derive(Debug, Default)]
+pub struct EventLog {
+    entries: Vec<Entry>,
+}
+
+impl EventLog {
+    pub fn new() -> EventLog {
+        EventLog::default()
+    }
+
+    /// Adds `event`, as happening now.
+    pub fn record(&mut self, event: Event) {
+        self.record_at(SystemTime::now(), event);
+    }
+
+    /// Adds `event`, as happening at `at`, for events that are written down after the fact.
+    pub fn record_at(&mut self, at: SystemTime, event: Event) {
+        self.entries.push(Entry { at, event });
+    }
+
+    /// Everything that happened, in the order it was recorded.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Adds up the day.
+    pub fn report(&self) -> DailyReport {
+        let mut report = DailyReport::default();
+        let mut ordered_at = BTreeMap::new();
+        let mut waits = Vec::new();
+        let mut ratings = Vec::new();
+
+        for entry in &self.entries {
+            match &entry.event {
+                Event::Seated { party, .. } => report.covers += party.size,
+                Event::Ordered { order, .. } => {
+                    ordered_at.insert(*order, entry.at);
+                }
+                Event::Cooked { .. } => {}
+                Event::Served { order } => {
+                    report.orders_served += 1;
+                    // A clock that went back gives no wait, rather than a negative one.
+                    if let Some(ordered) = ordered_at.get(order) {
+                        waits.push(entry.at.duration_since(*ordered).unwrap_or_default());
+                    }
+                }
+                Event::Paid { amount, .. } => report.revenue += amount,
+                Event::Rated { dish, stars, .. } => ratings.push((dish.as_str(), *stars)),
+            }
+        }
+        report.ratings = feedback::rank(ratings);
+
+        if !waits.is_empty() {
+            let total: Duration = waits.iter().sum();
+            report.average_wait = Some(total / waits.len() as u32);
+        }
+        report
+    }
+}
+
+/// What the day came to.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DailyReport {
+    /// How many guests were seated.
+    pub covers: u32,
+    pub orders_served: u32,
+    /// In cents.
+    pub revenue: u32,
+    /// How long, on average, an order took from being placed to being served, or `None` if
+    /// nothing was served.
+    pub average_wait: Option<Duration>,
+    /// Every dish that was rated, best first.
+    pub ratings: Vec<DishRating>,
+}
+
+impl DailyReport {
+    /// How many of the best rated dishes the printed report names.
+    pub const TOP_RATED: usize = 3;
+}
+
+impl fmt::Display for DailyReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Covers: {}", self.covers)?;
+        writeln!(f, "Orders served: {}", self.orders_served)?;
+        writeln!(
+            f,
+            "Revenue: ${}.{:02}",
+            self.revenue / 100,
+            self.revenue % 100
+        )?;
+        match self.average_wait {
+            Some(wait) => {
+                let seconds = wait.as_secs();
+                writeln!(f, "Average wait: {}m {:02}s", seconds / 60, seconds % 60)?;
+            }
+            None => writeln!(f, "Average wait: nothing served")?,
+        }
+        if self.ratings.is_empty() {
+            return write!(f, "Top rated: nothing rated");
+        }
+        write!(f, "Top rated:")?;
+        for rating in self.ratings.iter().take(DailyReport::TOP_RATED) {
+            write!(
+                f,
+                "\n  {} ({:.1} stars from {})",
+                rating.dish,
+                rating.average(),
+                rating.ratings
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::serving::{MenuItem, Orders};
+
+    #[test]
+    fn the_report_adds_up_the_day() {
+        let opening = SystemTime::UNIX_EPOCH + Duration::from_secs(8 * 3600);
+        let minutes = |m: u64| opening + Duration::from_secs(m * 60);
+        let mut orders = Orders::new();
+        let first = orders.take_order(TableId(1), vec![MenuItem::new("Pancakes", 850)]);
+        let second = orders.take_order(TableId(2), vec![MenuItem::new("Omelette", 1000)]);
+
+        let mut log = EventLog::new();
+        let events = [
+            (
+                0,
+                Event::Seated {
+                    table: TableId(1),
+                    party: Party::new("Ferris", 2),
+                },
+            ),
+            (
+                1,
+                Event::Seated {
+                    table: TableId(2),
+                    party: Party::new("Corro", 4),
+                },
+            ),
+            (
+                5,
+                Event::Ordered {
+                    order: first,
+                    table: TableId(1),
+                },
+            ),
+            (
+                6,
+                Event::Ordered {
+                    order: second,
+                    table: TableId(2),
+                },
+            ),
+            (15, Event::Cooked { order: first }),
+            (16, Event::Served { order: first }),
+            (
+                40,
+                Event::Paid {
+                    order: first,
+                    amount: 850,
+                },
+            ),
+            (
+                41,
+                Event::Rated {
+                    order: first,
+                    dish: "Pancakes".to_string(),
+                    stars: 5,
+                },
+            ),
+            (
+                42,
+                Event::Rated {
+                    order: first,
+                    dish: "Pancakes".to_string(),
+                    stars: 4,
+                },
+            ),
+        ];
+        for (minute, event) in events {
+            log.record_at(minutes(minute), event);
+        }
+
+        let report = log.report();
+        assert_eq!(
+            DailyReport {
+                covers: 6,
+                orders_served: 1,
+                revenue: 850,
+                average_wait: Some(Duration::from_secs(11 * 60)),
+                ratings: vec![DishRating {
+                    dish: "Pancakes".to_string(),
+                    total_stars: 9,
+                    ratings: 2,
+                }],
+            },
+            report
+        );
+        assert_eq!(
+            "Covers: 6\nOrders served: 1\nRevenue: $8.50\nAverage wait: 11m 00s\n\
+             Top rated:\n  Pancakes (4.5 stars from 2)",
+            report.to_string()
+        );
+        assert_eq!(9, log.entries().len());
+    }
+
+    #[test]
+    fn an_empty_day() {
+        let report = EventLog::new().report();
+        assert_eq!(None, report.average_wait);
+        assert!(report
+            .to_string()
+            .ends_with("Average wait: nothing served\nTop rated: nothing rated"));
+    }
+}
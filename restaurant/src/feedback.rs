@@ -0,0 +1,216 @@
+//! What guests thought of their meal: a rating from 1 to 5 stars for a dish they ordered,
+//! with a comment if they have one.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::front_of_house::serving::{Order, OrderId};
+
+/// From 1 star, the worst, to 5, the best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rating(u8);
+
+impl Rating {
+    pub fn new(stars: u8) -> Result<Rating, FeedbackError> {
+        if (1..=5).contains(&stars) {
+            Ok(Rating(stars))
+        } else {
+            Err(FeedbackError::InvalidRating(stars))
+        }
+    }
+
+    pub fn stars(self) -> u8 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedbackError {
+    /// Ratings go from 1 to 5.
+    InvalidRating(u8),
+    /// Guests can only rate what they ordered.
+    NotOnOrder { order: OrderId, dish: String },
+}
+
+impl fmt::Display for FeedbackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FeedbackError::InvalidRating(stars) => {
+                write!(f, "a rating is 1 to 5 stars, not {}", stars)
+            }
+            FeedbackError::NotOnOrder { order, dish } => {
+                write!(f, "{} wasn't on {}", dish, order)
+            }
+        }
+    }
+}
+
+impl Error for FeedbackError {}
+
+/// One guest's rating of one dish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feedback {
+    pub order: OrderId,
+    pub dish: String,
+    pub rating: Rating,
+    /// Empty if the guest didn't say anything.
+    pub comment: String,
+}
+
+/// All the ratings a dish got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DishRating {
+    pub dish: String,
+    pub total_stars: u32,
+    pub ratings: u32,
+}
+
+impl DishRating {
+    pub fn average(&self) -> f64 {
+        self.total_stars as f64 / self.ratings as f64
+    }
+}
+
+/// Adds up the stars of each dish, and sorts the dishes best first. Between two dishes with
+/// the same average, the one rated more often is more certainly that good, so it comes
+/// first; after that, they're by name.
+pub fn rank<'a>(ratings: impl IntoIterator<Item = (&'a str, u8)>) -> Vec<DishRating> {
+    let mut by_dish: BTreeMap<&str, DishRating> = BTreeMap::new();
+    for (dish, stars) in ratings {
+        let rating = by_dish.entry(dish).or_insert_with(|| DishRating {
+            dish: dish.to_string(),
+            total_stars: 0,
+            ratings: 0,
+        });
+        rating.total_stars += stars as u32;
+        rating.ratings += 1;
+    }
+    let mut ranked: Vec<DishRating> = by_dish.into_values().collect();
+    // Comparing `total_stars / ratings` by cross-multiplying keeps it in whole numbers.
+    ranked.sort_by(|a, b| {
+        (b.total_stars * a.ratings)
+            .cmp(&(a.total_stars * b.ratings))
+            .then(b.ratings.cmp(&a.ratings))
+            .then(a.dish.cmp(&b.dish))
+    });
+    ranked
+}
+
+/// Every rating given.
+#[derive(Debug, Default)]
+pub struct FeedbackBook {
+    entries: Vec<Feedback>,
+}
+
+impl FeedbackBook {
+    pub fn new() -> FeedbackBook {
+        FeedbackBook::default()
+    }
+
+    /// Writes down a guest's rating of `dish`, which has to be on `order`.
+    pub fn rate(
+        &mut self,
+        order: &Order,
+        dish: &str,
+        stars: u8,
+        comment: &str,
+    ) -> Result<(), FeedbackError> {
+        let rating = Rating::new(stars)?;
+        if !order.items().iter().any(|item| item.name == dish) {
+            return Err(FeedbackError::NotOnOrder {
+                order: order.id(),
+                dish: dish.to_string(),
+            });
+        }
+        self.entries.push(Feedback {
+            order: order.id(),
+            dish: dish.to_string(),
+            rating,
+            comment: comment.to_string(),
+        });
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[Feedback] {
+        &self.entries
+    }
+
+    /// The `n` best rated dishes, best first, as `rank` sorts them.
+    pub fn top_rated(&self, n: usize) -> Vec<DishRating> {
+        let mut ranked = rank(
+            self.entries
+                .iter()
+                .map(|feedback| (feedback.dish.as_str(), feedback.rating.stars())),
+        );
+        ranked.truncate(n);
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::hosting::TableId;
+    use crate::front_of_house::serving::{MenuItem, Orders};
+
+    fn breakfast() -> Order {
+        let mut orders = Orders::new();
+        let items = vec![
+            MenuItem::new("Pancakes", 850),
+            MenuItem::new("Omelette", 1000),
+            MenuItem::new("Coffee", 250),
+        ];
+        let id = orders.take_order(TableId(1), items);
+        orders.get(id).unwrap().clone()
+    }
+
+    #[test]
+    fn the_best_rated_dishes_come_first() {
+        let order = breakfast();
+        let mut book = FeedbackBook::new();
+        book.rate(&order, "Pancakes", 5, "Fluffy!").unwrap();
+        book.rate(&order, "Pancakes", 4, "").unwrap();
+        book.rate(&order, "Omelette", 3, "A bit dry").unwrap();
+        book.rate(&order, "Coffee", 5, "").unwrap();
+        book.rate(&order, "Coffee", 4, "").unwrap();
+
+        let top: Vec<(String, f64)> = book
+            .top_rated(2)
+            .iter()
+            .map(|rating| (rating.dish.clone(), rating.average()))
+            .collect();
+        // A tie on both the average and the number of ratings goes by name.
+        assert_eq!(
+            vec![("Coffee".to_string(), 4.5), ("Pancakes".to_string(), 4.5)],
+            top
+        );
+    }
+
+    #[test]
+    fn more_ratings_win_a_tie() {
+        let ranked = rank([("Soup", 4), ("Tea", 4), ("Tea", 4)]);
+        assert_eq!(
+            vec!["Tea", "Soup"],
+            ranked.iter().map(|r| r.dish.as_str()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn only_what_was_ordered_and_one_to_five_stars() {
+        let order = breakfast();
+        let mut book = FeedbackBook::new();
+        assert_eq!(
+            Err(FeedbackError::InvalidRating(6)),
+            book.rate(&order, "Pancakes", 6, "")
+        );
+        assert_eq!(
+            Err(FeedbackError::NotOnOrder {
+                order: order.id(),
+                dish: "Soup".to_string()
+            }),
+            book.rate(&order, "Soup", 5, "")
+        );
+        assert!(book.entries().is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+//! Greeting guests, and keeping them in line until a table is free.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+/// A group of guests who want a table together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Party {
+    pub name: String,
+    pub size: u32,
+}
+
+impl Party {
+    pub fn new(name: impl Into<String>, size: u32) -> Party {
+        Party {
+            name: name.into(),
+            size,
+        }
+    }
+}
+
+/// Handed to a party when it joins the waitlist, to ask where it is in line or to leave.
+/// Only the waitlist makes them, so every ticket was really handed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TicketId(u32);
+
+impl fmt::Display for TicketId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A table in the dining room, by its number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TableId(pub u32);
+
+impl fmt::Display for TableId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "table {}", self.0)
+    }
+}
+
+/// The parties waiting for a table, first come, first served, and which tables the ones
+/// that were seated are at.
+#[derive(Debug, Default)]
+pub struct Waitlist {
+    queue: VecDeque<(TicketId, Party)>,
+    seated: BTreeMap<TableId, TicketId>,
+    next_ticket: u32,
+}
+
+impl Waitlist {
+    pub fn new() -> Waitlist {
+        Waitlist::default()
+    }
+
+    /// Puts `party` at the back of the line, and returns its ticket.
+    pub fn add(&mut self, party: Party) -> TicketId {
+        self.next_ticket += 1;
+        let ticket = TicketId(self.next_ticket);
+        self.queue.push_back((ticket, party));
+        ticket
+    }
+
+    /// Takes the party at the front of the line to `table`. Returns `None` if nobody is
+    /// waiting, or someone is already sitting there.
+    pub fn seat_next(&mut self, table: TableId) -> Option<Party> {
+        if !self.is_free(table) {
+            return None;
+        }
+        let (ticket, party) = self.queue.pop_front()?;
+        self.seated.insert(table, ticket);
+        Some(party)
+    }
+
+    pub fn is_free(&self, table: TableId) -> bool {
+        !self.seated.contains_key(&table)
+    }
+
+    /// The table the party with `ticket` was seated at, if it was.
+    pub fn table_of(&self, ticket: TicketId) -> Option<TableId> {
+        self.seated
+            .iter()
+            .find(|(_, t)| **t == ticket)
+            .map(|(table, _)| *table)
+    }
+
+    /// Frees `table` once its party has left, and returns the party's ticket.
+    pub fn clear_table(&mut self, table: TableId) -> Option<TicketId> {
+        self.seated.remove(&table)
+    }
+
+    /// How many parties are ahead of the one with `ticket`, so 0 is next. `None` if the
+    /// ticket isn't in line: it was seated or canceled, or never handed out.
+    pub fn position(&self, ticket: TicketId) -> Option<usize> {
+        self.queue.iter().position(|(t, _)| *t == ticket)
+    }
+
+    /// Takes the party with `ticket` out of the line, and returns it.
+    pub fn cancel(&mut self, ticket: TicketId) -> Option<Party> {
+        let index = self.position(ticket)?;
+        self.queue.remove(index).map(|(_, party)| party)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// The parties in line, from the front.
+    pub fn iter(&self) -> impl Iterator<Item = (TicketId, &Party)> {
+        self.queue.iter().map(|(ticket, party)| (*ticket, party))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_come_first_served() {
+        let mut waitlist = Waitlist::new();
+        let ferris = waitlist.add(Party::new("Ferris", 2));
+        let corro = waitlist.add(Party::new("Corro", 4));
+        assert_eq!(Some(0), waitlist.position(ferris));
+        assert_eq!(Some(1), waitlist.position(corro));
+
+        assert_eq!(
+            Some(Party::new("Ferris", 2)),
+            waitlist.seat_next(TableId(1))
+        );
+        assert_eq!(Some(TableId(1)), waitlist.table_of(ferris));
+        assert_eq!(None, waitlist.position(ferris));
+        assert_eq!(Some(0), waitlist.position(corro));
+    }
+
+    #[test]
+    fn a_taken_table_seats_nobody() {
+        let mut waitlist = Waitlist::new();
+        waitlist.add(Party::new("Ferris", 2));
+        waitlist.add(Party::new("Corro", 4));
+        waitlist.seat_next(TableId(1));
+
+        assert_eq!(None, waitlist.seat_next(TableId(1)));
+        assert_eq!(1, waitlist.len());
+        assert!(waitlist.clear_table(TableId(1)).is_some());
+        assert_eq!("Corro", waitlist.seat_next(TableId(1)).unwrap().name);
+        assert_eq!(None, waitlist.seat_next(TableId(2)));
+    }
+
+    #[test]
+    fn canceling_leaves_the_line() {
+        let mut waitlist = Waitlist::new();
+        let ferris = waitlist.add(Party::new("Ferris", 2));
+        let corro = waitlist.add(Party::new("Corro", 4));
+
+        assert_eq!(Some(Party::new("Ferris", 2)), waitlist.cancel(ferris));
+        assert_eq!(None, waitlist.cancel(ferris));
+        assert_eq!(Some(0), waitlist.position(corro));
+        let names: Vec<&str> = waitlist
+            .iter()
+            .map(|(_, party)| party.name.as_str())
+            .collect();
+        assert_eq!(vec!["Corro"], names);
+    }
+}
@@ -0,0 +1,316 @@
+//! Taking orders, bringing the food out, and getting paid.
+//!
+//! Every order goes through the same states, in the same order:
+//!
+//! ```text
+//! Placed → Cooking → Served → Paid
+//! ```
+//!
+//! The state is an enum, and the only way to change it is `Order::advance`, which refuses
+//! to skip a state or go back to one.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::front_of_house::hosting::TableId;
+
+/// Something on the menu, with its price in cents, so adding prices up can't be off by a
+/// rounding error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    pub name: String,
+    pub price: u32,
+}
+
+impl MenuItem {
+    pub fn new(name: impl Into<String>, price: u32) -> MenuItem {
+        MenuItem {
+            name: name.into(),
+            price,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OrderId(pub(crate) u32);
+
+impl fmt::Display for OrderId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "order {}", self.0)
+    }
+}
+
+/// Where an order is, from being placed to being paid for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OrderState {
+    Placed,
+    Cooking,
+    Served,
+    Paid,
+}
+
+impl OrderState {
+    /// The state that comes after this one, or `None` once the order is paid for.
+    pub fn next(self) -> Option<OrderState> {
+        match self {
+            OrderState::Placed => Some(OrderState::Cooking),
+            OrderState::Cooking => Some(OrderState::Served),
+            OrderState::Served => Some(OrderState::Paid),
+            OrderState::Paid => None,
+        }
+    }
+}
+
+/// What can go wrong with an order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    NoSuchOrder(OrderId),
+    /// `to` doesn't come right after `from`, like serving food that isn't cooked.
+    InvalidTransition {
+        order: OrderId,
+        from: OrderState,
+        to: OrderState,
+    },
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::NoSuchOrder(order) => write!(f, "there's no {}", order),
+            OrderError::InvalidTransition { order, from, to } => {
+                write!(f, "{} can't go from {:?} to {:?}", order, from, to)
+            }
+        }
+    }
+}
+
+impl Error for OrderError {}
+
+/// What a table paid for an order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    pub order: OrderId,
+    pub table: TableId,
+    /// In cents.
+    pub total: u32,
+}
+
+/// What one table ordered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    id: OrderId,
+    table: TableId,
+    items: Vec<MenuItem>,
+    state: OrderState,
+}
+
+impl Order {
+    /// An order as it was saved, for `persistence::load`.
+    pub(crate) fn restore(
+        id: OrderId,
+        table: TableId,
+        items: Vec<MenuItem>,
+        state: OrderState,
+    ) -> Order {
+        Order {
+            id,
+            table,
+            items,
+            state,
+        }
+    }
+
+    pub fn id(&self) -> OrderId {
+        self.id
+    }
+
+    pub fn table(&self) -> TableId {
+        self.table
+    }
+
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    pub fn state(&self) -> OrderState {
+        self.state
+    }
+
+    /// The sum of the items' prices, in cents.
+    pub fn total(&self) -> u32 {
+        self.items.iter().map(|item| item.price).sum()
+    }
+
+    /// Whether the order still needs something done: it isn't paid for yet.
+    pub fn is_open(&self) -> bool {
+        self.state != OrderState::Paid
+    }
+
+    /// Moves the order on to `to`, which has to be the state right after the one it's in.
+    pub fn advance(&mut self, to: OrderState) -> Result<(), OrderError> {
+        if self.state.next() != Some(to) {
+            return Err(OrderError::InvalidTransition {
+                order: self.id,
+                from: self.state,
+                to,
+            });
+        }
+        self.state = to;
+        Ok(())
+    }
+}
+
+/// All the orders of the day, and the receipts of the ones that were paid for.
+#[derive(Debug, Default)]
+pub struct Orders {
+    orders: BTreeMap<OrderId, Order>,
+    receipts: Vec<Receipt>,
+    next_id: u32,
+}
+
+impl Orders {
+    pub fn new() -> Orders {
+        Orders::default()
+    }
+
+    /// Puts saved orders and receipts back, for `persistence::load`. New orders get ids
+    /// after all of them.
+    pub(crate) fn restore(orders: Vec<Order>, receipts: Vec<Receipt>) -> Orders {
+        let next_id = orders.iter().map(|order| order.id.0).max().unwrap_or(0);
+        Orders {
+            orders: orders.into_iter().map(|order| (order.id, order)).collect(),
+            receipts,
+            next_id,
+        }
+    }
+
+    /// Writes down what `table` ordered. The order starts out `Placed`.
+    pub fn take_order(&mut self, table: TableId, items: Vec<MenuItem>) -> OrderId {
+        self.next_id += 1;
+        let id = OrderId(self.next_id);
+        let order = Order {
+            id,
+            table,
+            items,
+            state: OrderState::Placed,
+        };
+        self.orders.insert(id, order);
+        id
+    }
+
+    /// The kitchen started on the order.
+    pub fn start_cooking(&mut self, id: OrderId) -> Result<(), OrderError> {
+        self.get_mut(id)?.advance(OrderState::Cooking)
+    }
+
+    pub fn serve_order(&mut self, id: OrderId) -> Result<(), OrderError> {
+        self.get_mut(id)?.advance(OrderState::Served)
+    }
+
+    /// Marks the order paid, keeps its receipt, and returns what it came to, in cents.
+    pub fn take_payment(&mut self, id: OrderId) -> Result<u32, OrderError> {
+        let order = self.get_mut(id)?;
+        order.advance(OrderState::Paid)?;
+        let receipt = Receipt {
+            order: id,
+            table: order.table,
+            total: order.total(),
+        };
+        let total = receipt.total;
+        self.receipts.push(receipt);
+        Ok(total)
+    }
+
+    /// The receipts, in the order the payments were taken.
+    pub fn receipts(&self) -> &[Receipt] {
+        &self.receipts
+    }
+
+    pub fn get(&self, id: OrderId) -> Option<&Order> {
+        self.orders.get(&id)
+    }
+
+    /// The orders at `table` that aren't paid for yet, oldest first.
+    pub fn open_for(&self, table: TableId) -> impl Iterator<Item = &Order> {
+        self.orders
+            .values()
+            .filter(move |order| order.table == table && order.is_open())
+    }
+
+    /// Every order, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Order> {
+        self.orders.values()
+    }
+
+    fn get_mut(&mut self, id: OrderId) -> Result<&mut Order, OrderError> {
+        self.orders.get_mut(&id).ok_or(OrderError::NoSuchOrder(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pancakes() -> Vec<MenuItem> {
+        vec![MenuItem::new("Pancakes", 850), MenuItem::new("Coffee", 250)]
+    }
+
+    #[test]
+    fn an_order_goes_from_placed_to_paid() {
+        let mut orders = Orders::new();
+        let id = orders.take_order(TableId(1), pancakes());
+        assert_eq!(OrderState::Placed, orders.get(id).unwrap().state());
+
+        orders.start_cooking(id).unwrap();
+        orders.serve_order(id).unwrap();
+        assert_eq!(Ok(1100), orders.take_payment(id));
+        assert_eq!(OrderState::Paid, orders.get(id).unwrap().state());
+        assert_eq!(
+            [Receipt {
+                order: id,
+                table: TableId(1),
+                total: 1100
+            }],
+            orders.receipts()
+        );
+    }
+
+    #[test]
+    fn states_cannot_be_skipped_or_repeated() {
+        let mut orders = Orders::new();
+        let id = orders.take_order(TableId(1), pancakes());
+
+        assert_eq!(
+            Err(OrderError::InvalidTransition {
+                order: id,
+                from: OrderState::Placed,
+                to: OrderState::Served
+            }),
+            orders.serve_order(id)
+        );
+        assert!(orders.take_payment(id).is_err());
+        orders.start_cooking(id).unwrap();
+        assert!(orders.start_cooking(id).is_err());
+        assert_eq!(OrderState::Cooking, orders.get(id).unwrap().state());
+    }
+
+    #[test]
+    fn open_orders_are_looked_up_by_table() {
+        let mut orders = Orders::new();
+        let first = orders.take_order(TableId(1), pancakes());
+        let second = orders.take_order(TableId(1), pancakes());
+        orders.take_order(TableId(2), pancakes());
+
+        orders.start_cooking(first).unwrap();
+        orders.serve_order(first).unwrap();
+        orders.take_payment(first).unwrap();
+
+        let open: Vec<OrderId> = orders.open_for(TableId(1)).map(Order::id).collect();
+        assert_eq!(vec![second], open);
+        assert_eq!(
+            Err(OrderError::NoSuchOrder(OrderId(9))),
+            orders.serve_order(OrderId(9))
+        );
+    }
+}
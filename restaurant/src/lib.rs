@@ -1,19 +1,17 @@
-mod front_of_house {
-    mod hosting {
-        fn add_to_waitlist() {}
+pub mod front_of_house {
+    // A module's body can live in a file of its own, even when the module is declared inside
+    // another one like this: `hosting` is in `src/front_of_house/hosting.rs`.
+    pub mod hosting;
 
-        fn seat_at_table() {}
-    }
-
-    mod serving {
-        fn take_order() {}
-
-        fn serve_order() {}
-
-        fn take_payment() {}
-    }
+    pub mod serving;
 }
 
+pub mod back_of_house;
+pub mod events;
+pub mod feedback;
+pub mod json;
+pub mod persistence;
+
 // We define a module by starting with the `mod` keyword and then specify the name of the module
 // and place curly brackets around the body of the module.
 // Inside modules, we can have other modules. Modules can also hold definitions for other items,
@@ -22,3 +20,259 @@ mod front_of_house {
 // By using modules, we can group related definitions together and name why they're related.
 
 // The entire module tree is rooted under the implicit module named `crate`.
+
+// `pub use` re-exports items at the root of the crate, so code using it writes
+// `restaurant::Party` rather than `restaurant::front_of_house::hosting::Party`, and doesn't
+// need to know how the modules are laid out inside.
+pub use crate::back_of_house::{Inventory, Kitchen, KitchenError, Recipe};
+pub use crate::events::{DailyReport, Event, EventLog};
+pub use crate::feedback::{DishRating, Feedback, FeedbackBook, FeedbackError, Rating};
+pub use crate::front_of_house::hosting::{Party, TableId, TicketId, Waitlist};
+pub use crate::front_of_house::serving::{
+    MenuItem, Order, OrderError, OrderId, OrderState, Orders, Receipt,
+};
+
+use std::error::Error;
+use std::fmt;
+
+/// Why the restaurant couldn't do something.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestaurantError {
+    Order(OrderError),
+    Kitchen(KitchenError),
+    Feedback(FeedbackError),
+}
+
+impl fmt::Display for RestaurantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RestaurantError::Order(e) => write!(f, "{}", e),
+            RestaurantError::Kitchen(e) => write!(f, "{}", e),
+            RestaurantError::Feedback(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for RestaurantError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RestaurantError::Order(e) => Some(e),
+            RestaurantError::Kitchen(e) => Some(e),
+            RestaurantError::Feedback(e) => Some(e),
+        }
+    }
+}
+
+impl From<OrderError> for RestaurantError {
+    fn from(e: OrderError) -> RestaurantError {
+        RestaurantError::Order(e)
+    }
+}
+
+impl From<KitchenError> for RestaurantError {
+    fn from(e: KitchenError) -> RestaurantError {
+        RestaurantError::Kitchen(e)
+    }
+}
+
+impl From<FeedbackError> for RestaurantError {
+    fn from(e: FeedbackError) -> RestaurantError {
+        RestaurantError::Feedback(e)
+    }
+}
+
+/// The whole restaurant: the waitlist at the door, the orders and their bills, and the
+/// kitchen. It's a facade: each of those is still its own type, but this is the one place
+/// that uses them together, and records what happens in the event log as it goes.
+#[derive(Debug, Default)]
+pub struct Restaurant {
+    waitlist: Waitlist,
+    orders: Orders,
+    kitchen: Kitchen,
+    feedback: FeedbackBook,
+    log: EventLog,
+}
+
+impl Restaurant {
+    /// A restaurant with `kitchen` to cook in, and nobody in it yet.
+    pub fn new(kitchen: Kitchen) -> Restaurant {
+        Restaurant {
+            kitchen,
+            ..Restaurant::default()
+        }
+    }
+
+    /// A party comes in, and joins the waitlist.
+    pub fn arrive(&mut self, party: Party) -> TicketId {
+        self.waitlist.add(party)
+    }
+
+    /// Seats the next party in line at `table`, if there is one and the table is free.
+    pub fn seat_next(&mut self, table: TableId) -> Option<Party> {
+        let party = self.waitlist.seat_next(table)?;
+        self.log.record(Event::Seated {
+            table,
+            party: party.clone(),
+        });
+        Some(party)
+    }
+
+    pub fn take_order(&mut self, table: TableId, items: Vec<MenuItem>) -> OrderId {
+        let order = self.orders.take_order(table, items);
+        self.log.record(Event::Ordered { order, table });
+        order
+    }
+
+    /// Has the kitchen cook `order`. If it can't, the order stays `Placed`.
+    pub fn cook(&mut self, order: OrderId) -> Result<(), RestaurantError> {
+        let placed = self
+            .orders
+            .get(order)
+            .ok_or(OrderError::NoSuchOrder(order))?;
+        if placed.state() != OrderState::Placed {
+            return Err(OrderError::InvalidTransition {
+                order,
+                from: placed.state(),
+                to: OrderState::Cooking,
+            }
+            .into());
+        }
+        self.kitchen.cook_order(placed)?;
+        self.orders.start_cooking(order)?;
+        self.log.record(Event::Cooked { order });
+        Ok(())
+    }
+
+    pub fn serve(&mut self, order: OrderId) -> Result<(), RestaurantError> {
+        self.orders.serve_order(order)?;
+        self.log.record(Event::Served { order });
+        Ok(())
+    }
+
+    /// Takes payment for `order`, and returns what it came to, in cents.
+    pub fn pay(&mut self, order: OrderId) -> Result<u32, RestaurantError> {
+        let amount = self.orders.take_payment(order)?;
+        self.log.record(Event::Paid { order, amount });
+        Ok(amount)
+    }
+
+    /// A guest rates `dish`, from `order`, 1 to 5 stars. The rating goes into the day's
+    /// report too.
+    pub fn rate(
+        &mut self,
+        order: OrderId,
+        dish: &str,
+        stars: u8,
+        comment: &str,
+    ) -> Result<(), RestaurantError> {
+        let rated = self
+            .orders
+            .get(order)
+            .ok_or(OrderError::NoSuchOrder(order))?;
+        self.feedback.rate(rated, dish, stars, comment)?;
+        self.log.record(Event::Rated {
+            order,
+            dish: dish.to_string(),
+            stars,
+        });
+        Ok(())
+    }
+
+    /// The party at `table` left, so it's free for the next one.
+    pub fn clear_table(&mut self, table: TableId) {
+        self.waitlist.clear_table(table);
+    }
+
+    pub fn waitlist(&self) -> &Waitlist {
+        &self.waitlist
+    }
+
+    pub fn orders(&self) -> &Orders {
+        &self.orders
+    }
+
+    /// The kitchen, to restock the inventory or add recipes.
+    pub fn kitchen_mut(&mut self) -> &mut Kitchen {
+        &mut self.kitchen
+    }
+
+    pub fn kitchen(&self) -> &Kitchen {
+        &self.kitchen
+    }
+
+    /// What the guests thought, comments included.
+    pub fn feedback(&self) -> &FeedbackBook {
+        &self.feedback
+    }
+
+    pub fn log(&self) -> &EventLog {
+        &self.log
+    }
+
+    /// The report of the day so far.
+    pub fn report(&self) -> DailyReport {
+        self.log.report()
+    }
+}
+
+/// A whole visit, from the door to the bill, through the public API only.
+pub fn eat_at_restaurant() -> DailyReport {
+    let mut kitchen = Kitchen::new();
+    kitchen.add_recipe(Recipe::new("Pancakes", &[("flour", 100), ("egg", 1)]));
+    kitchen.inventory.restock("flour", 1000);
+    kitchen.inventory.restock("egg", 12);
+    let mut restaurant = Restaurant::new(kitchen);
+
+    restaurant.arrive(Party::new("Ferris", 2));
+    let table = TableId(1);
+    restaurant.seat_next(table);
+
+    let pancakes = MenuItem::new("Pancakes", 850);
+    let order = restaurant.take_order(table, vec![pancakes.clone(), pancakes]);
+    restaurant
+        .cook(order)
+        .expect("the kitchen has what pancakes take");
+    restaurant.serve(order).expect("the order was cooked");
+    restaurant.pay(order).expect("the order was served");
+    restaurant
+        .rate(order, "Pancakes", 5, "Just like at home")
+        .expect("pancakes were on the order");
+    restaurant.clear_table(table);
+
+    restaurant.report()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_visit_from_the_door_to_the_bill() {
+        let report = eat_at_restaurant();
+        assert_eq!(2, report.covers);
+        assert_eq!(1, report.orders_served);
+        assert_eq!(1700, report.revenue);
+        assert_eq!("Pancakes", report.ratings[0].dish);
+    }
+
+    #[test]
+    fn an_order_the_kitchen_cannot_make_stays_placed() {
+        let mut restaurant = Restaurant::new(Kitchen::new());
+        restaurant.arrive(Party::new("Ferris", 2));
+        restaurant.seat_next(TableId(1));
+        let order = restaurant.take_order(TableId(1), vec![MenuItem::new("Soup", 600)]);
+
+        assert_eq!(
+            Err(RestaurantError::Kitchen(KitchenError::NoRecipe(
+                "Soup".to_string()
+            ))),
+            restaurant.cook(order)
+        );
+        assert_eq!(
+            OrderState::Placed,
+            restaurant.orders().get(order).unwrap().state()
+        );
+        assert!(restaurant.serve(order).is_err());
+        assert_eq!(2, restaurant.log().entries().len());
+    }
+}
@@ -0,0 +1,266 @@
+//! Saving the day's orders and receipts to a file, so a restart doesn't lose the tickets
+//! that are still open.
+//!
+//! The file is JSON, with a `version` field. A file from a newer version of the format is
+//! refused, since it may mean something this version can't tell. Fields this version
+//! doesn't know about are skipped, so a newer version can add some without raising it.
+//!
+//! ```text
+//! {"orders":[{"id":1,"items":[{"name":"Pancakes","price":850}],"state":"Paid","table":1}],
+//!  "receipts":[{"order":1,"table":1,"total":850}],"version":1}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::front_of_house::hosting::TableId;
+use crate::front_of_house::serving::{MenuItem, Order, OrderId, OrderState, Orders, Receipt};
+use crate::json::{self, Value};
+
+/// The version of the format this crate writes, and the newest it reads.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Json(json::ParseError),
+    /// The file is JSON, but not what was saved: `{0}` says what's wrong.
+    Format(String),
+    /// The file was written by a newer version of the format.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "couldn't read the orders: {}", e),
+            LoadError::Json(e) => write!(f, "the orders aren't valid JSON: {}", e),
+            LoadError::Format(message) => write!(f, "the orders are malformed: {}", message),
+            LoadError::UnsupportedVersion(version) => write!(
+                f,
+                "the orders were saved in version {} of the format, and only up to {} can be read",
+                version, VERSION
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Writes `orders` to `path`. It's written to a file next to it first, and then moved into
+/// place, so a crash halfway through leaves the old file as it was.
+pub fn save(orders: &Orders, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, to_json(orders) + "\n")?;
+    fs::rename(&temporary, path)
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<Orders, LoadError> {
+    let text = fs::read_to_string(path).map_err(LoadError::Io)?;
+    from_json(&text)
+}
+
+pub fn to_json(orders: &Orders) -> String {
+    let orders_json = orders.iter().map(order_to_json).collect();
+    let receipts = orders
+        .receipts()
+        .iter()
+        .map(|receipt| {
+            Value::object([
+                ("order", receipt.order.0.into()),
+                ("table", receipt.table.0.into()),
+                ("total", receipt.total.into()),
+            ])
+        })
+        .collect();
+    Value::object([
+        ("version", VERSION.into()),
+        ("orders", Value::Array(orders_json)),
+        ("receipts", Value::Array(receipts)),
+    ])
+    .to_string()
+}
+
+fn order_to_json(order: &Order) -> Value {
+    let items = order
+        .items()
+        .iter()
+        .map(|item| {
+            Value::object([
+                ("name", item.name.as_str().into()),
+                ("price", item.price.into()),
+            ])
+        })
+        .collect();
+    Value::object([
+        ("id", order.id().0.into()),
+        ("table", order.table().0.into()),
+        ("state", state_name(order.state()).into()),
+        ("items", Value::Array(items)),
+    ])
+}
+
+pub fn from_json(text: &str) -> Result<Orders, LoadError> {
+    let root = json::parse(text).map_err(LoadError::Json)?;
+    let version = u32_field(&root, "version")?;
+    if version > VERSION {
+        return Err(LoadError::UnsupportedVersion(version));
+    }
+
+    let orders = array_field(&root, "orders")?
+        .iter()
+        .map(order_from_json)
+        .collect::<Result<_, _>>()?;
+    let receipts = array_field(&root, "receipts")?
+        .iter()
+        .map(|receipt| {
+            Ok(Receipt {
+                order: OrderId(u32_field(receipt, "order")?),
+                table: TableId(u32_field(receipt, "table")?),
+                total: u32_field(receipt, "total")?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Orders::restore(orders, receipts))
+}
+
+fn order_from_json(order: &Value) -> Result<Order, LoadError> {
+    let state = str_field(order, "state")?;
+    let state = state_from_name(state)
+        .ok_or_else(|| LoadError::Format(format!("{:?} isn't an order state", state)))?;
+    let items = array_field(order, "items")?
+        .iter()
+        .map(|item| {
+            Ok(MenuItem::new(
+                str_field(item, "name")?,
+                u32_field(item, "price")?,
+            ))
+        })
+        .collect::<Result<_, LoadError>>()?;
+    Ok(Order::restore(
+        OrderId(u32_field(order, "id")?),
+        TableId(u32_field(order, "table")?),
+        items,
+        state,
+    ))
+}
+
+fn state_name(state: OrderState) -> &'static str {
+    match state {
+        OrderState::Placed => "Placed",
+        OrderState::Cooking => "Cooking",
+        OrderState::Served => "Served",
+        OrderState::Paid => "Paid",
+    }
+}
+
+fn state_from_name(name: &str) -> Option<OrderState> {
+    match name {
+        "Placed" => Some(OrderState::Placed),
+        "Cooking" => Some(OrderState::Cooking),
+        "Served" => Some(OrderState::Served),
+        "Paid" => Some(OrderState::Paid),
+        _ => None,
+    }
+}
+
+fn field<'a>(object: &'a Value, key: &str) -> Result<&'a Value, LoadError> {
+    object
+        .get(key)
+        .ok_or_else(|| LoadError::Format(format!("missing field {:?}", key)))
+}
+
+fn u32_field(object: &Value, key: &str) -> Result<u32, LoadError> {
+    field(object, key)?
+        .as_u32()
+        .ok_or_else(|| LoadError::Format(format!("{:?} should be a whole number", key)))
+}
+
+fn str_field<'a>(object: &'a Value, key: &str) -> Result<&'a str, LoadError> {
+    field(object, key)?
+        .as_str()
+        .ok_or_else(|| LoadError::Format(format!("{:?} should be a string", key)))
+}
+
+fn array_field<'a>(object: &'a Value, key: &str) -> Result<&'a [Value], LoadError> {
+    field(object, key)?
+        .as_array()
+        .ok_or_else(|| LoadError::Format(format!("{:?} should be an array", key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn day() -> Orders {
+        let mut orders = Orders::new();
+        let paid = orders.take_order(TableId(1), vec![MenuItem::new("Pancakes", 850)]);
+        orders.start_cooking(paid).unwrap();
+        orders.serve_order(paid).unwrap();
+        orders.take_payment(paid).unwrap();
+        let open = orders.take_order(TableId(2), vec![MenuItem::new("Soup \"du jour\"", 600)]);
+        orders.start_cooking(open).unwrap();
+        orders
+    }
+
+    #[test]
+    fn orders_survive_a_restart() {
+        let path = env::temp_dir().join(format!("restaurant-orders-{}.json", std::process::id()));
+        let before = day();
+        save(&before, &path).unwrap();
+        let mut after = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            before.iter().collect::<Vec<_>>(),
+            after.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(before.receipts(), after.receipts());
+        let open: Vec<OrderId> = after.open_for(TableId(2)).map(Order::id).collect();
+        assert_eq!(1, open.len());
+        assert_eq!(Ok(()), after.serve_order(open[0]));
+
+        let next = after.take_order(TableId(3), Vec::new());
+        assert!(after.iter().filter(|order| order.id() == next).count() == 1);
+        assert_eq!(3, after.iter().count());
+    }
+
+    #[test]
+    fn unknown_fields_are_skipped() {
+        let text = r#"{"version":1,"opened_by":"Ferris","orders":[
+            {"id":4,"table":2,"state":"Served","items":[],"server":{"name":"Corro"}}
+        ],"receipts":[]}"#;
+        let orders = from_json(text).unwrap();
+        assert_eq!(OrderState::Served, orders.get(OrderId(4)).unwrap().state());
+    }
+
+    #[test]
+    fn newer_versions_and_bad_files_are_refused() {
+        let newer = r#"{"version":2,"orders":[],"receipts":[]}"#;
+        assert!(matches!(
+            from_json(newer),
+            Err(LoadError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(from_json("{"), Err(LoadError::Json(_))));
+        let bad_state = r#"{"version":1,"orders":[{"id":1,"table":1,"state":"Lost","items":[]}],"receipts":[]}"#;
+        assert!(matches!(from_json(bad_state), Err(LoadError::Format(_))));
+        assert!(matches!(
+            load("/nonexistent/orders.json"),
+            Err(LoadError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+//! Layered settings, loaded with the error-propagation patterns from *main.rs*.
+//!
+//! Settings are `key = value` lines. Blank lines and lines starting with `#` are
+//! skipped. Three layers are read, from highest to lowest priority:
+//! 1. the `RESULT_CONFIG` environment variable, whose entries are separated by `;`
+//! 2. the user file, *~/.resultrc*
+//! 3. the default path, *config.txt* in the current directory
+//!
+//! A key set in a higher layer overrides the same key in a lower one.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+pub const ENV_VAR: &str = "RESULT_CONFIG";
+pub const DEFAULT_PATH: &str = "config.txt";
+
+/// One error type that represents every way loading the settings can fail, even
+/// though the failures come from different places.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// None of the layers exist.
+    NotFound,
+    /// A layer couldn't be read. Reading a single file that doesn't exist is an
+    /// `Io` error too, with the kind `ErrorKind::NotFound`.
+    Io(io::Error),
+    /// A line isn't of the form `key = value`. Lines are numbered from 1.
+    Parse { line: usize },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::NotFound => write!(f, "no configuration found"),
+            ConfigError::Io(e) => write!(f, "could not read configuration: {}", e),
+            ConfigError::Parse { line } => write!(f, "expected `key = value` on line {}", line),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// With this `From` implementation, the `?` operator converts an `io::Error` into
+/// a `ConfigError` for us. A missing file isn't `ConfigError::NotFound`: one
+/// missing layer is fine, and only `load_from` knows whether they all are.
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+    values: HashMap<String, String>,
+}
+
+impl Config {
+    /// Loads and merges the environment variable, the user file, and the default path.
+    pub fn load() -> Result<Config, ConfigError> {
+        let user_path = env::var_os("HOME").map(|home| PathBuf::from(home).join(".resultrc"));
+
+        Config::load_from(env::var(ENV_VAR).ok(), user_path.as_deref(), Path::new(DEFAULT_PATH))
+    }
+
+    /// Like `load`, but with every layer passed in explicitly. Missing layers are
+    /// skipped; only when all of them are missing is `ConfigError::NotFound` returned.
+    pub fn load_from(
+        env_value: Option<String>,
+        user_path: Option<&Path>,
+        default_path: &Path,
+    ) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        let mut found = false;
+
+        // Lowest priority first, so later layers overwrite earlier ones.
+        for path in [Some(default_path), user_path].into_iter().flatten() {
+            if let Some(layer) = optional(Config::read(path))? {
+                config.merge(layer);
+                found = true;
+            }
+        }
+
+        if let Some(value) = env_value {
+            config.merge(Config::parse(&value.replace(';', "\n"))?);
+            found = true;
+        }
+
+        if found {
+            Ok(config)
+        } else {
+            Err(ConfigError::NotFound)
+        }
+    }
+
+    /// Reads a single settings file. Both the I/O error and the parse error are
+    /// propagated with `?`.
+    pub fn read(path: &Path) -> Result<Config, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        Config::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Config, ConfigError> {
+        let mut values = HashMap::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ConfigError::Parse { line: i + 1 })?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(ConfigError::Parse { line: i + 1 });
+            }
+
+            values.insert(key.to_string(), value.trim().to_string());
+        }
+
+        Ok(Config { values })
+    }
+
+    /// Overwrites this layer's values with the values of a higher-priority layer.
+    pub fn merge(&mut self, other: Config) {
+        self.values.extend(other.values);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.get("username")
+    }
+}
+
+/// Turns "this optional layer doesn't exist" into `Ok(None)` and leaves every
+/// other error alone.
+fn optional(result: Result<Config, ConfigError>) -> Result<Option<Config>, ConfigError> {
+    match result {
+        Ok(config) => Ok(Some(config)),
+        Err(ConfigError::Io(e)) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("result-config-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn higher_layers_override_lower_ones() {
+        let default = temp_file("default", "username = default\ncolor = blue\n");
+        let user = temp_file("user", "# user settings\nusername = ferris\nshell = zsh\n");
+
+        let config = Config::load_from(
+            Some(String::from("shell = fish; editor = hx")),
+            Some(&user),
+            &default,
+        )
+        .unwrap();
+
+        assert_eq!(Some("ferris"), config.username());
+        assert_eq!(Some("blue"), config.get("color"));
+        assert_eq!(Some("fish"), config.get("shell"));
+        assert_eq!(Some("hx"), config.get("editor"));
+
+        fs::remove_file(default).unwrap();
+        fs::remove_file(user).unwrap();
+    }
+
+    #[test]
+    fn missing_layers_are_skipped_until_none_are_left() {
+        let missing = env::temp_dir().join("result-config-does-not-exist");
+
+        let config = Config::load_from(Some(String::from("username=corro")), None, &missing);
+        assert_eq!(Some("corro"), config.unwrap().username());
+
+        let error = Config::load_from(None, Some(&missing), &missing).unwrap_err();
+        assert!(matches!(error, ConfigError::NotFound));
+    }
+
+    #[test]
+    fn a_single_missing_file_is_an_io_error() {
+        let missing = env::temp_dir().join("result-config-does-not-exist");
+
+        let error = Config::read(&missing).unwrap_err();
+        assert!(matches!(&error, ConfigError::Io(e) if e.kind() == ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn parse_errors_report_the_line() {
+        let error = Config::parse("a = 1\n\nnot a setting\n").unwrap_err();
+        assert!(matches!(error, ConfigError::Parse { line: 3 }));
+
+        let error = Config::parse(" = value").unwrap_err();
+        assert!(matches!(error, ConfigError::Parse { line: 1 }));
+    }
+
+    #[test]
+    fn io_errors_other_than_not_found_are_kept() {
+        // Reading a directory as a file fails with something other than `NotFound`.
+        let error = Config::read(&env::temp_dir()).unwrap_err();
+        assert!(matches!(error, ConfigError::Io(_)));
+        assert!(error.source().is_some());
+    }
+}
@@ -4,7 +4,10 @@ use std::fs::File;
 // use std::io::{self, Read};
 use std::fs;
 use std::io;
-use std::io::Read;
+
+use config::{Config, ConfigError};
+
+mod config;
 
 // fn main() {
 // Recoverable Errors with `Result`
@@ -119,8 +122,27 @@ use std::io::Read;
 // Using `?` on a `Result` value in a `main` function with the error type `Box<dyn Error>` is
 // now allowed, because it allows any `Err` value to be returned early.
 fn main() -> Result<(), Box<dyn Error>> {
-    // `main` can also return a `Result<(), E>`.
-    let f = File::open("hello.txt")?;
+    // Settings from the `config` module win; without any, fall back to *hello.txt*.
+    // A missing configuration is expected, but any other `ConfigError` is boxed
+    // and returned.
+    let username = match Config::load() {
+        Ok(config) => config.username().map(String::from),
+        Err(ConfigError::NotFound) => None,
+        Err(e) => return Err(e.into()),
+    };
+    let username = match username {
+        Some(username) => username,
+        None => read_username_from_file()?,
+    };
+    println!("Hello, {}!", username.trim());
+
+    if let Some(c) = last_char_of_first_line(&username) {
+        println!("The name ends with {:?}", c);
+    }
+
+    // `main` can also return a `Result<(), E>`. This runs last, so the settings above
+    // are used even when *hello.txt* doesn't exist.
+    let _f = File::open("hello.txt")?;
 
     Ok(())
     // When a `main` function returns a `Result<(), E>`, the executable will exit with a value of
@@ -1 +1,2 @@
-pub pub mod hosting;  // Next we extract the `hosting` module to its own file as well.
\ No newline at end of file
+pub mod hosting;  // Next we extract the `hosting` module to its own file as well.
+pub mod serving; // And `serving` goes in `src/front_of_house/serving.rs`, next to it.
@@ -1 +1,93 @@
-pub fn add_to_waitlist() {}
\ No newline at end of file
+// The waitlist is written once, in the `restaurant` crate, and both crates use it. `pub use`
+// re-exports it, so it's `front_of_house::hosting::Waitlist` here too.
+pub use restaurant::front_of_house::hosting::{Party, TableId, TicketId, Waitlist};
+
+use std::collections::BTreeMap;
+
+/// The host at the door: keeps the waitlist, and knows how many seats each table has.
+#[derive(Debug)]
+pub struct Host {
+    waitlist: Waitlist,
+    tables: BTreeMap<TableId, u32>,
+}
+
+impl Host {
+    /// A host for a dining room with `tables`, each with how many seats it has.
+    pub fn new(tables: &[(TableId, u32)]) -> Host {
+        Host {
+            waitlist: Waitlist::new(),
+            tables: tables.iter().copied().collect(),
+        }
+    }
+
+    /// Puts `party` in line, and returns its ticket. A party bigger than every table could
+    /// never be seated, and would keep everyone behind it waiting too, so it's given back
+    /// instead.
+    pub fn add_to_waitlist(&mut self, party: Party) -> Result<TicketId, Party> {
+        if self.tables.values().all(|seats| *seats < party.size) {
+            return Err(party);
+        }
+        Ok(self.waitlist.add(party))
+    }
+
+    /// Seats the party at the front of the line at the smallest free table big enough for
+    /// it. Returns `None` if nobody is waiting, or no free table fits them yet, in which
+    /// case they keep their place in line.
+    pub fn seat_at_table(&mut self) -> Option<(TableId, Party)> {
+        let (_, party) = self.waitlist.iter().next()?;
+        let table = self
+            .tables
+            .iter()
+            .filter(|(table, seats)| **seats >= party.size && self.waitlist.is_free(**table))
+            .min_by_key(|(_, seats)| **seats)
+            .map(|(table, _)| *table)?;
+        let party = self.waitlist.seat_next(table)?;
+        Some((table, party))
+    }
+
+    /// The party at `table` left.
+    pub fn free_table(&mut self, table: TableId) {
+        self.waitlist.clear_table(table);
+    }
+
+    pub fn waitlist(&self) -> &Waitlist {
+        &self.waitlist
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parties_get_the_smallest_table_that_fits() {
+        let mut host = Host::new(&[(TableId(1), 6), (TableId(2), 2), (TableId(3), 4)]);
+        host.add_to_waitlist(Party::new("Ferris", 3)).unwrap();
+        host.add_to_waitlist(Party::new("Corro", 4)).unwrap();
+        host.add_to_waitlist(Party::new("Bors", 6)).unwrap();
+
+        assert_eq!(TableId(3), host.seat_at_table().unwrap().0);
+        assert_eq!(TableId(1), host.seat_at_table().unwrap().0);
+        // The table of six is taken, so Bors keeps their place in line.
+        assert_eq!(None, host.seat_at_table());
+        assert_eq!(1, host.waitlist().len());
+
+        host.free_table(TableId(1));
+        assert_eq!(TableId(1), host.seat_at_table().unwrap().0);
+        assert!(host.waitlist().is_empty());
+    }
+
+    #[test]
+    fn parties_no_table_fits_are_turned_away() {
+        let mut host = Host::new(&[(TableId(1), 6), (TableId(2), 2)]);
+
+        assert_eq!(
+            Err(Party::new("Bors", 8)),
+            host.add_to_waitlist(Party::new("Bors", 8))
+        );
+        assert!(host.waitlist().is_empty());
+        // So they don't hold up a party that fits.
+        host.add_to_waitlist(Party::new("Ann", 2)).unwrap();
+        assert_eq!(TableId(2), host.seat_at_table().unwrap().0);
+    }
+}
@@ -0,0 +1,39 @@
+// Like the waitlist, orders are written once, in the `restaurant` crate.
+pub use restaurant::front_of_house::serving::{MenuItem, Order, OrderError, OrderId, Orders};
+
+use crate::front_of_house::hosting::TableId;
+
+/// A server, taking the orders of the tables and bringing them their food and the bill.
+///
+/// There's no kitchen in this crate, so an order goes to the cooks as soon as it's taken.
+#[derive(Debug, Default)]
+pub struct Server {
+    orders: Orders,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server::default()
+    }
+
+    pub fn take_order(&mut self, table: TableId, items: Vec<MenuItem>) -> OrderId {
+        let order = self.orders.take_order(table, items);
+        self.orders
+            .start_cooking(order)
+            .expect("a new order can always be cooked");
+        order
+    }
+
+    pub fn serve_order(&mut self, order: OrderId) -> Result<(), OrderError> {
+        self.orders.serve_order(order)
+    }
+
+    /// Returns what the order came to, in cents.
+    pub fn take_payment(&mut self, order: OrderId) -> Result<u32, OrderError> {
+        self.orders.take_payment(order)
+    }
+
+    pub fn orders(&self) -> &Orders {
+        &self.orders
+    }
+}
@@ -2,8 +2,40 @@ mod front_of_house; // First we extract the `front_of_house` module to its own f
 // We only need to load the contents of a file using a `mod` declaration once somewhere
 // in our module tree.
 
-pub use crate::front_of_house::hosting;
+pub use crate::front_of_house::{hosting, serving};
 
-pub fn eat_at_restaurant() {
-    hosting::add_to_waitlist();
+use crate::hosting::{Party, TableId};
+use crate::serving::MenuItem;
+
+/// An evening in a small dining room: parties come in, get seated as tables free up, order,
+/// eat, and pay. Returns what was taken, in cents.
+pub fn eat_at_restaurant() -> u32 {
+    let mut host = hosting::Host::new(&[(TableId(1), 2), (TableId(2), 4)]);
+    let mut server = serving::Server::new();
+
+    for (name, size) in [("Ferris", 2), ("Corro", 4), ("Bors", 3)] {
+        host.add_to_waitlist(Party::new(name, size))
+            .expect("a table seats everyone");
+    }
+
+    let mut taken = 0;
+    // Bors has to wait for a table of four, so they're seated once Corro has left.
+    while let Some((table, party)) = host.seat_at_table() {
+        let meal = vec![MenuItem::new("Soup", 600); party.size as usize];
+        let order = server.take_order(table, meal);
+        server.serve_order(order).expect("the order is cooking");
+        taken += server.take_payment(order).expect("the order was served");
+        host.free_table(table);
+    }
+    taken
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn everyone_is_seated_and_pays() {
+        assert_eq!(9 * 600, eat_at_restaurant());
+    }
 }
\ No newline at end of file